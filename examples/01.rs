@@ -166,6 +166,7 @@ enum ParseError {
 /// The `tokens` field is a vector of all the tokens found in the input string
 /// The `pos` field is the current position in the token stream
 struct Parser<'a> {
+    #[allow(dead_code)]
     input: &'a str,
     tokens: Vec<(Token, &'a str)>,
     pos: usize,
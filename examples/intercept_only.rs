@@ -27,22 +27,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   Output: {}", serde_json::to_string_pretty(&result3)?);
     println!();
 
-    // Example 4: Show what happens with invalid syntax
-    println!("4. Invalid syntax (should fail): y ~ 1 - 1");
+    // Example 4: Removing the intercept again leaves the empty model, as in R
+    println!("4. Intercept removed again: y ~ 1 - 1");
     let formula4 = "y ~ 1 - 1";
-    match parse_formula(formula4) {
-        Ok(_) => println!("   Unexpected: This should have failed!"),
-        Err(e) => println!("   Expected error: {}", e),
-    }
+    let result4 = parse_formula(formula4)?;
+    println!("   Input: {}", formula4);
+    println!("   has_intercept: {}", result4["metadata"]["has_intercept"]);
     println!();
 
-    // Example 5: Show what happens with invalid zero combination
-    println!("5. Invalid syntax (should fail): y ~ 0 + 1");
+    // Example 5: Intercept markers may be combined; the last one wins
+    println!("5. Last intercept marker wins: y ~ 0 + 1");
     let formula5 = "y ~ 0 + 1";
-    match parse_formula(formula5) {
-        Ok(_) => println!("   Unexpected: This should have failed!"),
-        Err(e) => println!("   Expected error: {}", e),
-    }
+    let result5 = parse_formula(formula5)?;
+    println!("   Input: {}", formula5);
+    println!("   has_intercept: {}", result5["metadata"]["has_intercept"]);
 
     Ok(())
 }
@@ -3,8 +3,8 @@ use logos::Logos;
 
 fn main() {
     let input = "mpg ~ cyl + wt*hp + poly(disp, 4) - 1";
-    let mut lexer = Token::lexer(input);
-    while let Some(tok) = lexer.next() {
+    let lexer = Token::lexer(input);
+    for tok in lexer {
         println!("{:?}", tok);
     }
 }
@@ -45,8 +45,9 @@
 
 /// Distribution families for statistical models
 ///
-/// These represent the error distribution family in generalized linear models.
-/// Each family corresponds to a specific probability distribution and link function.
+/// These represent the response distribution of a (generalized) linear model.
+/// Each family has one or more distributional parameters (`mu`, `sigma`,
+/// `zi`, ...), and each parameter has a link function; see [`FamilySpec`].
 ///
 /// # Examples
 ///
@@ -59,23 +60,189 @@
 /// // Binomial family for logistic regression
 /// let binomial = Family::Binomial;
 ///
-/// // Poisson family for count data
-/// let poisson = Family::Poisson;
+/// // Zero-inflated Poisson family for counts with excess zeros
+/// let zip = Family::ZeroInflatedPoisson;
+/// assert_eq!(zip.name(), "zero_inflated_poisson");
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 pub enum Family {
     /// Gaussian (normal) distribution - used for linear regression
-    /// Link function: identity
-    /// Variance function: constant
+    /// Parameters: mu (identity link), sigma (log link)
     Gaussian,
+    /// Student's t distribution - a robust alternative to the Gaussian
+    /// Parameters: mu, sigma, nu (degrees of freedom)
+    Student,
+    /// Skew normal distribution
+    /// Parameters: mu, sigma, alpha (skewness)
+    SkewNormal,
     /// Binomial distribution - used for logistic regression
-    /// Link function: logit
-    /// Variance function: μ(1-μ)
+    /// Parameters: mu (logit link)
     Binomial,
+    /// Bernoulli distribution - binomial with a single trial
+    /// Parameters: mu (logit link)
+    Bernoulli,
+    /// Beta-binomial distribution - an overdispersed binomial
+    /// Parameters: mu, phi (precision)
+    BetaBinomial,
     /// Poisson distribution - used for count data
-    /// Link function: log
-    /// Variance function: μ
+    /// Parameters: mu (log link)
     Poisson,
+    /// Negative binomial distribution - overdispersed counts
+    /// Parameters: mu, shape
+    NegBinomial,
+    /// Geometric distribution - negative binomial with shape 1
+    /// Parameters: mu
+    Geometric,
+    /// Gamma distribution - positive continuous data (`Gamma` in R)
+    /// Parameters: mu (inverse link), shape
+    Gamma,
+    /// Log-normal distribution
+    /// Parameters: mu, sigma
+    Lognormal,
+    /// Shifted log-normal distribution - e.g. reaction times
+    /// Parameters: mu, sigma, ndt (non-decision time)
+    ShiftedLognormal,
+    /// Wiener diffusion model - reaction times with a decision, `y | dec(d)`
+    /// Parameters: mu (drift rate), bs (boundary separation), ndt, bias
+    Wiener,
+    /// Exponentially modified Gaussian distribution
+    /// Parameters: mu, sigma, beta
+    ExGaussian,
+    /// Weibull distribution - survival times
+    /// Parameters: mu, shape
+    Weibull,
+    /// Exponential distribution - survival times
+    /// Parameters: mu
+    Exponential,
+    /// Frechet distribution
+    /// Parameters: mu, nu
+    Frechet,
+    /// Beta distribution - proportions in (0, 1)
+    /// Parameters: mu (logit link), phi (precision)
+    Beta,
+    /// Von Mises distribution - circular data
+    /// Parameters: mu, kappa
+    VonMises,
+    /// Asymmetric Laplace distribution - quantile regression
+    /// Parameters: mu, sigma, quantile
+    AsymLaplace,
+    /// Cumulative ordinal model
+    /// Parameters: mu, disc (discrimination)
+    Cumulative,
+    /// Stopping ratio ordinal model
+    /// Parameters: mu, disc
+    Sratio,
+    /// Continuation ratio ordinal model
+    /// Parameters: mu, disc
+    Cratio,
+    /// Adjacent category ordinal model
+    /// Parameters: mu, disc
+    Acat,
+    /// Categorical distribution - unordered categories
+    /// Parameters: mu (logit link)
+    Categorical,
+    /// Multinomial distribution - counts of unordered categories
+    /// Parameters: mu (logit link)
+    Multinomial,
+    /// Zero-inflated Poisson distribution
+    /// Parameters: mu, zi (zero-inflation probability)
+    ZeroInflatedPoisson,
+    /// Zero-inflated negative binomial distribution
+    /// Parameters: mu, shape, zi
+    ZeroInflatedNegBinomial,
+    /// Zero-inflated binomial distribution
+    /// Parameters: mu, zi
+    ZeroInflatedBinomial,
+    /// Zero-inflated beta distribution
+    /// Parameters: mu, phi, zi
+    ZeroInflatedBeta,
+    /// Zero-one-inflated beta distribution
+    /// Parameters: mu, phi, zoi (zero-or-one inflation), coi (conditional one inflation)
+    ZeroOneInflatedBeta,
+    /// Hurdle Poisson distribution
+    /// Parameters: mu, hu (hurdle probability)
+    HurdlePoisson,
+    /// Hurdle negative binomial distribution
+    /// Parameters: mu, shape, hu
+    HurdleNegBinomial,
+    /// Hurdle gamma distribution
+    /// Parameters: mu, shape, hu
+    HurdleGamma,
+    /// Hurdle log-normal distribution
+    /// Parameters: mu, sigma, hu
+    HurdleLognormal,
+    /// A finite mixture of families: `mixture(gaussian, gaussian)`
+    /// Parameters: those of each component with its index (mu1, sigma1, mu2, ...)
+    /// and a mixing proportion per component (theta1, theta2, ...)
+    Mixture(Vec<Family>),
+}
+
+impl Family {
+    /// The family's name as written in a formula, e.g. `"zero_inflated_poisson"` or `"Gamma"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Family::Gaussian => "gaussian",
+            Family::Student => "student",
+            Family::SkewNormal => "skew_normal",
+            Family::Binomial => "binomial",
+            Family::Bernoulli => "bernoulli",
+            Family::BetaBinomial => "beta_binomial",
+            Family::Poisson => "poisson",
+            Family::NegBinomial => "negbinomial",
+            Family::Geometric => "geometric",
+            Family::Gamma => "Gamma",
+            Family::Lognormal => "lognormal",
+            Family::ShiftedLognormal => "shifted_lognormal",
+            Family::Wiener => "wiener",
+            Family::ExGaussian => "exgaussian",
+            Family::Weibull => "weibull",
+            Family::Exponential => "exponential",
+            Family::Frechet => "frechet",
+            Family::Beta => "beta",
+            Family::VonMises => "von_mises",
+            Family::AsymLaplace => "asym_laplace",
+            Family::Cumulative => "cumulative",
+            Family::Sratio => "sratio",
+            Family::Cratio => "cratio",
+            Family::Acat => "acat",
+            Family::Categorical => "categorical",
+            Family::Multinomial => "multinomial",
+            Family::ZeroInflatedPoisson => "zero_inflated_poisson",
+            Family::ZeroInflatedNegBinomial => "zero_inflated_negbinomial",
+            Family::ZeroInflatedBinomial => "zero_inflated_binomial",
+            Family::ZeroInflatedBeta => "zero_inflated_beta",
+            Family::ZeroOneInflatedBeta => "zero_one_inflated_beta",
+            Family::HurdlePoisson => "hurdle_poisson",
+            Family::HurdleNegBinomial => "hurdle_negbinomial",
+            Family::HurdleGamma => "hurdle_gamma",
+            Family::HurdleLognormal => "hurdle_lognormal",
+            Family::Mixture(_) => "mixture",
+        }
+    }
+}
+
+/// A family together with the link function of each of its parameters
+///
+/// The parser fills in the default link of every parameter the family has,
+/// so `links` lists all distributional parameters of the family in order.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::ast::{Family, FamilySpec};
+///
+/// // binomial(link = "probit")
+/// let probit = FamilySpec {
+///     family: Family::Binomial,
+///     links: vec![("mu".to_string(), "probit".to_string())]
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FamilySpec {
+    /// The distribution family
+    pub family: Family,
+    /// Each distributional parameter with its link function, `mu` first
+    pub links: Vec<(String, String)>,
 }
 
 /// Response variable specification
@@ -86,12 +253,440 @@ pub enum Family {
 /// # Examples
 /// - `y` → `Response::Single("y")`
 /// - `bind(y1, y2)` → `Response::Multivariate(vec!["y1", "y2"])`
+/// - `log(y)` → `Response::Function { name: "log", args: [Ident("y")] }`
+/// - `cbind(succ, fail)` → `Response::Binomial { successes: "succ", failures: "fail" }`
+/// - `I(y / 2)` → `Response::AsIs(Binary { Divide, y, 2 })`
 #[derive(Debug, Clone, PartialEq)]
 pub enum Response {
     /// Single response variable
     Single(String),
     /// Multiple response variables bound together
     Multivariate(Vec<String>),
+    /// A transformed response variable such as `log(y)`
+    Function {
+        /// The function name
+        name: String,
+        /// The function arguments, which may contain nested calls
+        args: Vec<Argument>,
+    },
+    /// A two-column binomial response: `cbind(successes, failures)`
+    Binomial {
+        /// The column counting successes
+        successes: String,
+        /// The column counting failures
+        failures: String,
+    },
+    /// An arithmetic response such as `I(y / 2)`
+    AsIs(Expression),
+}
+
+/// A response addition term written after `|` on the left-hand side
+///
+/// brms-style formulas attach extra information to the response, such as the
+/// number of binomial trials or a censoring indicator: `y | trials(n) ~ x`.
+/// Several addition terms are joined with `+`: `y | weights(w) + cens(c) ~ x`.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::ast::{AdditionTerm, Argument};
+///
+/// // y | trials(10)
+/// let trials = AdditionTerm::Trials(Argument::Integer(10));
+///
+/// // y | trunc(lb = 0)
+/// let truncation = AdditionTerm::Trunc {
+///     lower: Some(Argument::Integer(0)),
+///     upper: None
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdditionTerm {
+    /// Number of binomial trials, a column or a constant
+    ///
+    /// # Examples
+    /// - `trials(n)` → `AdditionTerm::Trials(Ident("n"))`
+    /// - `trials(10)` → `AdditionTerm::Trials(Integer(10))`
+    Trials(Argument),
+
+    /// Censoring indicator, with an optional upper bound column for interval censoring
+    ///
+    /// # Examples
+    /// - `cens(censored)` → `AdditionTerm::Censored { variable: "censored", upper: None }`
+    /// - `cens(censored, y2)` → `AdditionTerm::Censored { variable: "censored", upper: Some("y2") }`
+    Censored {
+        /// The censoring indicator column
+        variable: String,
+        /// The upper bound column for interval censoring
+        upper: Option<String>,
+    },
+
+    /// Case weights
+    ///
+    /// # Examples
+    /// - `weights(w)` → `AdditionTerm::Weights { variable: "w", scale: false }`
+    /// - `weights(w, scale = TRUE)` → `AdditionTerm::Weights { variable: "w", scale: true }`
+    Weights {
+        /// The weights column
+        variable: String,
+        /// Whether the weights are rescaled to sum to the number of observations
+        scale: bool,
+    },
+
+    /// Known standard errors of the response
+    ///
+    /// # Examples
+    /// - `se(s)` → `AdditionTerm::Se { variable: "s", sigma: false }`
+    /// - `se(s, sigma = TRUE)` → `AdditionTerm::Se { variable: "s", sigma: true }`
+    Se {
+        /// The standard error column
+        variable: String,
+        /// Whether the residual standard deviation is estimated as well
+        sigma: bool,
+    },
+
+    /// Truncation bounds, each a number or a column
+    ///
+    /// # Examples
+    /// - `trunc(lb = 0)` → `AdditionTerm::Trunc { lower: Some(Integer(0)), upper: None }`
+    /// - `trunc(ub = upper)` → `AdditionTerm::Trunc { lower: None, upper: Some(Ident("upper")) }`
+    Trunc {
+        /// The lower bound
+        lower: Option<Argument>,
+        /// The upper bound
+        upper: Option<Argument>,
+    },
+
+    /// Missing values in the response, with an optional measurement error column
+    ///
+    /// # Examples
+    /// - `mi()` → `AdditionTerm::Mi(None)`
+    /// - `mi(sdy)` → `AdditionTerm::Mi(Some("sdy"))`
+    Mi(Option<String>),
+
+    /// Decision column for response times (Wiener diffusion models)
+    ///
+    /// # Examples
+    /// - `dec(choice)` → `AdditionTerm::Dec("choice")`
+    Dec(String),
+
+    /// Exposure column for rate models
+    ///
+    /// # Examples
+    /// - `rate(exposure)` → `AdditionTerm::Rate("exposure")`
+    Rate(String),
+}
+
+/// A sub-formula that predicts a model parameter
+///
+/// brms-style models can predict distributional parameters such as `sigma`,
+/// `zi` or `disc` with their own right-hand side, written after the main
+/// formula: `y ~ x, sigma ~ x + (1 | g)`.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::ast::{ParameterFormula, Term};
+///
+/// // sigma ~ x
+/// let sigma = ParameterFormula {
+///     parameter: "sigma".to_string(),
+///     terms: vec![Term::Column("x".to_string())],
+///     has_intercept: true
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParameterFormula {
+    /// The name of the predicted parameter
+    ///
+    /// # Examples
+    /// - `"sigma"` for `sigma ~ x`
+    /// - `"zi"` for `zi ~ x + (1 | g)`
+    pub parameter: String,
+
+    /// The terms on the right-hand side
+    pub terms: Vec<Term>,
+
+    /// Whether the sub-formula includes an intercept
+    pub has_intercept: bool,
+}
+
+/// A distributional parameter fixed by a formula clause instead of predicted
+///
+/// The parser keeps every `name = value` clause that is not a known formula
+/// option as a fixed parameter; once the family is known, names that are not
+/// among its parameters become formula options instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::ast::{Argument, FixedParameter};
+///
+/// // sigma2 = "sigma1": both mixture components share one residual standard deviation
+/// let shared = FixedParameter {
+///     parameter: "sigma2".to_string(),
+///     value: Argument::String("sigma1".to_string())
+/// };
+///
+/// // bias = 0.5: an unbiased Wiener diffusion model
+/// let constant = FixedParameter {
+///     parameter: "bias".to_string(),
+///     value: Argument::Number(0.5)
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedParameter {
+    /// The name of the fixed parameter
+    pub parameter: String,
+
+    /// The value: a number is a constant, a string names another parameter to take the value of
+    pub value: Argument,
+}
+
+/// A formula-level option such as `fill = "mean"` or `center = FALSE`
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::ast::{Argument, FormulaOption};
+///
+/// // fill = "mean"
+/// let fill = FormulaOption {
+///     name: "fill".to_string(),
+///     value: Argument::String("mean".to_string())
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormulaOption {
+    /// The option name
+    pub name: String,
+
+    /// The value: a boolean, a number, a string or a name (`Argument::Ident`)
+    pub value: Argument,
+}
+
+/// An autocorrelation structure of the residuals
+///
+/// Written as a term on the right-hand side (`y ~ x + ar(time, g, p = 2)`) or
+/// as a `cor_ar(~ 1 | g)` component of a model specification. `ar()`, `ma()`
+/// and their `cor_` forms are autoregressive moving average structures with
+/// one of the orders set to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::ast::Autocorrelation;
+///
+/// // cor_ar(~ 1 | g)
+/// let ar = Autocorrelation::Arma {
+///     time: None,
+///     gr: Some("g".to_string()),
+///     p: 1,
+///     q: 0,
+///     cov: false
+/// };
+/// assert_eq!(ar.term(), "arma(time = NA, gr = g, p = 1, q = 0, cov = FALSE)");
+///
+/// // sar(W, type = "error")
+/// let sar = Autocorrelation::Sar { matrix: "W".to_string(), kind: "error".to_string() };
+/// assert_eq!(sar.name(), "sar");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Autocorrelation {
+    /// Autoregressive moving average of order (p, q): `ar()`, `ma()` and `arma()`
+    Arma {
+        /// The time variable, `None` for the data order (`time = NA`)
+        time: Option<String>,
+        /// The grouping variable, `None` for a single series (`gr = NA`)
+        gr: Option<String>,
+        /// The autoregressive order
+        p: u32,
+        /// The moving average order
+        q: u32,
+        /// Whether the structure is modelled through the residual covariance matrix
+        cov: bool,
+    },
+
+    /// Compound symmetry: `cosy()`
+    Cosy {
+        /// The time variable, `None` for the data order (`time = NA`)
+        time: Option<String>,
+        /// The grouping variable, `None` for a single series (`gr = NA`)
+        gr: Option<String>,
+    },
+
+    /// Spatial conditional autoregression over an adjacency matrix: `car(M)`
+    Car {
+        /// The name of the adjacency matrix
+        matrix: String,
+        /// The grouping variable matching the rows of the matrix, if any
+        gr: Option<String>,
+        /// The kind of structure: `"escar"`, `"esicar"`, `"icar"` or `"bym2"`
+        kind: String,
+    },
+
+    /// Spatial simultaneous autoregression over a weights matrix: `sar(W)`
+    Sar {
+        /// The name of the spatial weights matrix
+        matrix: String,
+        /// The kind of structure: `"lag"` or `"error"`
+        kind: String,
+    },
+}
+
+impl Autocorrelation {
+    /// The structure's name: `"arma"`, `"cosy"`, `"car"` or `"sar"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Autocorrelation::Arma { .. } => "arma",
+            Autocorrelation::Cosy { .. } => "cosy",
+            Autocorrelation::Car { .. } => "car",
+            Autocorrelation::Sar { .. } => "sar",
+        }
+    }
+
+    /// The structure written as a term with all its arguments, as brms prints it
+    pub fn term(&self) -> String {
+        let na = |name: &Option<String>| name.as_deref().unwrap_or("NA").to_string();
+        match self {
+            Autocorrelation::Arma { time, gr, p, q, cov } => format!(
+                "arma(time = {}, gr = {}, p = {}, q = {}, cov = {})",
+                na(time),
+                na(gr),
+                p,
+                q,
+                if *cov { "TRUE" } else { "FALSE" }
+            ),
+            Autocorrelation::Cosy { time, gr } => {
+                format!("cosy(time = {}, gr = {})", na(time), na(gr))
+            }
+            Autocorrelation::Car { matrix, gr, kind } => {
+                format!("car({}, gr = {}, type = \"{}\")", matrix, na(gr), kind)
+            }
+            Autocorrelation::Sar { matrix, kind } => format!("sar({}, type = \"{}\")", matrix, kind),
+        }
+    }
+}
+
+/// A parsed formula with all its clauses
+///
+/// Holds the response and right-hand side of `y ~ x + z` together with the
+/// clauses written after it: the family, parameter sub-formulas, fixed
+/// parameters and formula options.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::parser::Parser;
+/// use fiasto::internal::ast::{ParsedFormula, Response};
+///
+/// let mut parser = Parser::new("y ~ x, sigma ~ z, family = gaussian").unwrap();
+/// let ParsedFormula { response, terms, family, parameters, .. } = parser.parse_formula().unwrap();
+/// assert!(matches!(response, Some(Response::Single(name)) if name == "y"));
+/// assert_eq!(terms.len(), 1);
+/// assert!(family.is_some());
+/// assert_eq!(parameters[0].parameter, "sigma");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParsedFormula {
+    /// The response, `None` for one-sided formulas such as `~ x + z`
+    pub response: Option<Response>,
+
+    /// The right-hand side terms; empty for `nl = TRUE` formulas
+    pub terms: Vec<Term>,
+
+    /// Whether the model has an intercept
+    pub has_intercept: bool,
+
+    /// The family and its links, if specified
+    pub family: Option<FamilySpec>,
+
+    /// Sub-formulas for model parameters such as `sigma ~ x`, in formula order
+    pub parameters: Vec<ParameterFormula>,
+
+    /// For `nl = TRUE` formulas, the non-linear expression that replaces the terms
+    pub nonlinear: Option<Expression>,
+
+    /// Response addition terms such as `trials(n)` in `y | trials(n) ~ x`
+    pub additions: Vec<AdditionTerm>,
+
+    /// Parameters fixed to a constant or to another parameter: `bias = 0.5`, `sigma2 = "sigma1"`
+    pub fixed: Vec<FixedParameter>,
+
+    /// Formula options such as `fill = "mean"` or `nl = TRUE`, in formula order
+    pub options: Vec<FormulaOption>,
+}
+
+/// A component of a brms-style model specification
+///
+/// Model specifications add formulas, families and options together:
+/// `bf(y ~ a * exp(b * x), nl = TRUE) + lf(a ~ 1) + lf(b ~ z) + gaussian()`.
+/// Each formula component keeps the source text of its formulas, with the
+/// main or non-linear formula first and then one entry per parameter formula.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::ast::{Family, FamilySpec, ModelComponent};
+///
+/// // gaussian()
+/// let family = ModelComponent::Family(FamilySpec {
+///     family: Family::Gaussian,
+///     links: vec![
+///         ("mu".to_string(), "identity".to_string()),
+///         ("sigma".to_string(), "log".to_string())
+///     ]
+/// });
+///
+/// // set_rescor(FALSE)
+/// let rescor = ModelComponent::Rescor(false);
+/// ```
+#[derive(Debug, Clone)]
+pub enum ModelComponent {
+    /// A main formula: `bf(y ~ x, sigma ~ z)` or `(y ~ x)`; multivariate models have one per response
+    Formula {
+        /// The parsed formula with its clauses
+        formula: ParsedFormula,
+        /// The main formula's text followed by one text per parameter formula
+        texts: Vec<String>,
+    },
+
+    /// Linear parameter formulas: `lf(b ~ z + (1 | g), dpar = "sigma")`
+    Linear {
+        /// The parameter formulas
+        formulas: Vec<ParameterFormula>,
+        /// The distributional parameter the formulas belong to, if given
+        dpar: Option<String>,
+        /// The response the formulas belong to in a multivariate model, if given
+        resp: Option<String>,
+        /// One text per parameter formula
+        texts: Vec<String>,
+    },
+
+    /// A non-linear parameter formula: `nlf(sigma ~ a * exp(b * x), a ~ x)`
+    NonLinear {
+        /// The parameter the expression predicts
+        parameter: String,
+        /// The non-linear expression
+        expression: Expression,
+        /// Parameter formulas given inside `nlf()`
+        formulas: Vec<ParameterFormula>,
+        /// The distributional parameter the formula belongs to, if given
+        dpar: Option<String>,
+        /// The response the formula belongs to in a multivariate model, if given
+        resp: Option<String>,
+        /// The non-linear formula's text followed by one text per parameter formula
+        texts: Vec<String>,
+    },
+
+    /// A response family: `gaussian()` or `binomial(link = "probit")`, applied to the preceding main formula
+    Family(FamilySpec),
+
+    /// Residual correlations between responses: `set_rescor(TRUE)`
+    Rescor(bool),
+
+    /// An autocorrelation structure: `cor_ar(~ 1 | g)`, applied to the preceding main formula
+    Autocorrelation(Autocorrelation),
 }
 
 /// A term in a statistical formula
@@ -102,7 +697,7 @@ pub enum Response {
 /// # Examples
 ///
 /// ```rust
-/// use fiasto::internal::ast::{Term, Argument, RandomEffect, Grouping, CorrelationType};
+/// use fiasto::internal::ast::{Term, Argument, RandomEffect, Grouping, CorrelationType, InteractionOperator};
 ///
 /// // Simple variable
 /// let var_term = Term::Column("x".to_string());
@@ -116,7 +711,8 @@ pub enum Response {
 /// // Interaction
 /// let interaction = Term::Interaction {
 ///     left: Box::new(Term::Column("x".to_string())),
-///     right: Box::new(Term::Column("z".to_string()))
+///     right: Box::new(Term::Column("z".to_string())),
+///     operator: InteractionOperator::InteractionOnly
 /// };
 ///
 /// // Random effect
@@ -152,13 +748,16 @@ pub enum Term {
     /// An interaction between two terms
     ///
     /// # Examples
-    /// - `x:z` → `Term::Interaction { left: Column("x"), right: Column("z") }`
-    /// - `poly(x,2):log(y)` → `Term::Interaction { left: Function{...}, right: Function{...} }`
+    /// - `x:z` → `Term::Interaction { left: Column("x"), right: Column("z"), operator: InteractionOnly }`
+    /// - `x*z` → `Term::Interaction { left: Column("x"), right: Column("z"), operator: InteractionAndEffect }`
+    /// - `poly(x,2):log(y)` → `Term::Interaction { left: Function{...}, right: Function{...}, operator: InteractionOnly }`
     Interaction {
         /// The left-hand side of the interaction
         left: Box<Term>,
         /// The right-hand side of the interaction
         right: Box<Term>,
+        /// The operator joining the two sides
+        operator: InteractionOperator,
     },
 
     /// A random effects term
@@ -182,11 +781,59 @@ pub enum Term {
     /// - `0` → `Term::Zero`
     /// - Used in formulas like `y ~ 0` for models without intercept
     Zero,
+
+    /// A parenthesised group of terms
+    ///
+    /// Groups distribute over interaction operators, so `(a + b) * c`
+    /// expands to `a + b + c + a:c + b:c`.
+    ///
+    /// # Examples
+    /// - `(a + b)` → `Term::Group(vec![Column("a"), Column("b")])`
+    /// - `(a + b):c` → `Term::Interaction { left: Group(...), right: Column("c"), operator: InteractionOnly }`
+    Group(Vec<Term>),
+
+    /// A term raised to a power, limiting the order of its interactions
+    ///
+    /// `(a + b + c)^2` crosses the group with itself but keeps only products
+    /// of at most two of its members: `a + b + c + a:b + a:c + b:c`.
+    ///
+    /// # Examples
+    /// - `(a + b + c)^2` → `Term::Power { base: Group(...), order: 2 }`
+    Power { base: Box<Term>, order: u32 },
+
+    /// An arithmetic expression protected with `I()`
+    ///
+    /// Inside `I()` the operators `+ - * / ^` are arithmetic rather than
+    /// formula operators, so `I(x^2)` is the square of `x`.
+    ///
+    /// # Examples
+    /// - `I(x^2)` → `Term::AsIs(Expression::Binary { operator: Power, ... })`
+    /// - `I(price / 1000)` → `Term::AsIs(Expression::Binary { operator: Divide, ... })`
+    AsIs(Expression),
+
+    /// An autocorrelation structure of the residuals
+    ///
+    /// # Examples
+    /// - `ar(time, g)` → `Term::Autocorrelation(Arma { time: Some("time"), gr: Some("g"), p: 1, q: 0, .. })`
+    /// - `car(M, type = "icar")` → `Term::Autocorrelation(Car { matrix: "M", kind: "icar", .. })`
+    Autocorrelation(Autocorrelation),
+
+    /// A term removed from the model with the `-` operator
+    ///
+    /// Removal is applied once all other terms and their interactions have
+    /// been expanded. `- 1` and `- 0` are intercept markers and are not
+    /// represented as removals.
+    ///
+    /// # Examples
+    /// - `- w` → `Term::Removal(Column("w"))`
+    /// - `- a:b:c` → `Term::Removal(Interaction{...})`
+    Removal(Box<Term>),
 }
 
 /// Arguments to function calls
 ///
-/// Function arguments can be identifiers, integers, strings, or boolean values.
+/// Function arguments can be identifiers, numbers, strings, boolean values or
+/// nested function calls.
 /// These are used in function calls like `poly(x, 3)` or `gr(group, cor = TRUE)`.
 ///
 /// # Examples
@@ -200,13 +847,16 @@ pub enum Term {
 /// // Integer argument
 /// let int_arg = Argument::Integer(3);
 ///
+/// // Decimal argument
+/// let num_arg = Argument::Number(0.5);
+///
 /// // String argument
 /// let str_arg = Argument::String("student".to_string());
 ///
 /// // Boolean argument
 /// let bool_arg = Argument::Boolean(true);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Argument {
     /// An identifier (variable name)
     ///
@@ -222,6 +872,14 @@ pub enum Argument {
     /// - `0` → `Argument::Integer(0)`
     Integer(u32),
 
+    /// A decimal, scientific or negative number
+    ///
+    /// # Examples
+    /// - `0.5` → `Argument::Number(0.5)`
+    /// - `1e-3` → `Argument::Number(0.001)`
+    /// - `-1` → `Argument::Number(-1.0)`
+    Number(f64),
+
     /// A string literal
     ///
     /// # Examples
@@ -232,9 +890,10 @@ pub enum Argument {
     /// A named argument (key=value)
     ///
     /// # Examples
-    /// - `ref=treatment` → `Argument::Named("ref", "treatment")`
-    /// - `level=high` → `Argument::Named("level", "high")`
-    Named(String, String),
+    /// - `ref=treatment` → `Argument::Named("ref", Ident("treatment"))`
+    /// - `level="high"` → `Argument::Named("level", String("high"))`
+    /// - `lb=0.5` → `Argument::Named("lb", Number(0.5))`
+    Named(String, Box<Argument>),
 
     /// A boolean value
     ///
@@ -242,6 +901,95 @@ pub enum Argument {
     /// - `TRUE` → `Argument::Boolean(true)`
     /// - `FALSE` → `Argument::Boolean(false)`
     Boolean(bool),
+
+    /// A nested function call
+    ///
+    /// # Examples
+    /// - `log(exposure)` in `offset(log(exposure))` → `Argument::Function { name: "log", args: [Ident("exposure")] }`
+    /// - `scale(x)` in `log(scale(x))` → `Argument::Function { name: "scale", args: [Ident("x")] }`
+    Function { name: String, args: Vec<Argument> },
+}
+
+/// An arithmetic expression inside `I()`
+///
+/// Expressions form a tree that materializers can evaluate directly.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::ast::{BinaryOperator, Expression};
+///
+/// // I(x^2)
+/// let squared = Expression::Binary {
+///     operator: BinaryOperator::Power,
+///     left: Box::new(Expression::Column("x".to_string())),
+///     right: Box::new(Expression::Number(2.0)),
+/// };
+///
+/// // I(-log(x))
+/// let negated = Expression::Negate(Box::new(Expression::Call {
+///     name: "log".to_string(),
+///     args: vec![Expression::Column("x".to_string())],
+/// }));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    /// A column reference
+    ///
+    /// # Examples
+    /// - `x` → `Expression::Column("x")`
+    Column(String),
+
+    /// A numeric literal
+    ///
+    /// # Examples
+    /// - `2` → `Expression::Number(2.0)`
+    Number(f64),
+
+    /// Unary minus
+    ///
+    /// # Examples
+    /// - `-x` → `Expression::Negate(Column("x"))`
+    Negate(Box<Expression>),
+
+    /// A binary arithmetic operation
+    ///
+    /// # Examples
+    /// - `x + z` → `Expression::Binary { operator: Add, left: Column("x"), right: Column("z") }`
+    Binary {
+        /// The arithmetic operator
+        operator: BinaryOperator,
+        /// The left operand
+        left: Box<Expression>,
+        /// The right operand
+        right: Box<Expression>,
+    },
+
+    /// A function call within the expression
+    ///
+    /// # Examples
+    /// - `log(x)` → `Expression::Call { name: "log", args: [Column("x")] }`
+    Call {
+        /// The function name
+        name: String,
+        /// The function arguments
+        args: Vec<Expression>,
+    },
+}
+
+/// Arithmetic operators available inside `I()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+    /// Addition: `+`
+    Add,
+    /// Subtraction: `-`
+    Subtract,
+    /// Multiplication: `*`
+    Multiply,
+    /// Division: `/`
+    Divide,
+    /// Exponentiation: `^`
+    Power,
 }
 
 /// A random effects specification
@@ -534,6 +1282,55 @@ pub enum GrOption {
     Dist(String),
 }
 
+/// Operators that combine two fixed-effects terms
+///
+/// The operator decides which terms the interaction expands to.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::ast::InteractionOperator;
+///
+/// // Crossing with main effects: a*b → a + b + a:b
+/// let crossing = InteractionOperator::InteractionAndEffect;
+///
+/// // Product term only: a:b → a:b
+/// let product = InteractionOperator::InteractionOnly;
+///
+/// // Nesting: a/b → a + a:b
+/// let nesting = InteractionOperator::Nesting;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InteractionOperator {
+    /// `*` adds both main effects and their interaction
+    ///
+    /// # Examples
+    /// - `a*b` → `a + b + a:b`
+    /// - `(a + b)*c` → `a + b + c + a:c + b:c`
+    InteractionAndEffect,
+
+    /// `:` adds only the interaction
+    ///
+    /// # Examples
+    /// - `a:b` → `a:b`
+    /// - `(a + b):c` → `a:c + b:c`
+    InteractionOnly,
+
+    /// `/` nests the right-hand side within the left-hand side
+    ///
+    /// # Examples
+    /// - `a/b` → `a + a:b`
+    /// - `(a + b)/c` → `a + b + a:b:c`
+    Nesting,
+
+    /// `%in%` nests the left-hand side within the right-hand side
+    ///
+    /// # Examples
+    /// - `b %in% a` → `b:a`
+    /// - `a + b %in% a` → `a + b:a`
+    NestedWithin,
+}
+
 /// Correlation types for random effects
 ///
 /// Defines how random effects are correlated within and across grouping levels.
@@ -0,0 +1,302 @@
+//! # Metadata Assembly
+//!
+//! Turns a checked `Model` into the `FormulaMetaData` returned by `parse_formula`:
+//! one `MetaBuilder` pass per formula, parameter blocks for `dpars` and `nlpars`,
+//! and per-response blocks for multivariate models.
+
+use std::collections::HashMap;
+
+use crate::internal::{
+    ast::{Family, FamilySpec, ParsedFormula, Response},
+    data_structures::{FamilyMetaData, FormulaMetaData, ParameterMetaData, VariableInfo},
+    model::{response_name, Model, ResponseModel},
+    parse::{MetaBuilder, Term},
+};
+
+/// Builds the metadata of a checked model
+///
+/// Models with several formulas get one block per response under `responses`;
+/// an `mvbind(y1, y2) ~ x` formula gets one block per bound response that
+/// shares the right-hand side.
+pub(crate) fn build_metadata(formula: &str, model: Model) -> FormulaMetaData {
+    let Model {
+        mut responses,
+        formulas,
+        rescor,
+        warnings,
+    } = model;
+
+    let mut meta = if responses.len() > 1 {
+        build_multivariate(formula, responses)
+    } else {
+        let model = responses.remove(0);
+        match &model.parsed.response {
+            // mvbind(y1, y2) ~ x: every response shares the right-hand side
+            Some(Response::Multivariate(names)) => {
+                let mut blocks = HashMap::new();
+                for name in names {
+                    let mut single = model.clone();
+                    single.parsed.response = Some(Response::Single(name.clone()));
+                    blocks.insert(name.clone(), build_response(formula, single));
+                }
+                let mut meta = build_response(formula, model);
+                meta.responses = blocks;
+                meta
+            }
+            _ => build_response(formula, model),
+        }
+    };
+    meta.metadata.rescor = rescor;
+    meta.formulas = formulas;
+    meta.metadata.warnings.extend(warnings);
+    meta
+}
+
+/// Builds the metadata of one response's formula with its parameter blocks
+fn build_response(formula: &str, model: ResponseModel) -> FormulaMetaData {
+    let ParsedFormula {
+        response,
+        terms,
+        has_intercept,
+        family: family_opt,
+        parameters,
+        nonlinear,
+        additions,
+        fixed,
+        options,
+    } = model.parsed;
+
+    let mut mb = MetaBuilder::new();
+    if let Some(response) = &response {
+        mb.push_response(response);
+        if !additions.is_empty() {
+            mb.push_response_additions(response, &additions);
+        }
+    }
+    push_terms(&mut mb, terms);
+    if let Some(expression) = &nonlinear {
+        let names: Vec<String> = parameters.iter().map(|p| p.parameter.clone()).collect();
+        mb.push_nonlinear_expression(expression, &names);
+    }
+    let family_name = family_opt
+        .as_ref()
+        .map(|spec| spec.family.name().to_string());
+    let mut meta = mb.build(formula, has_intercept, family_name);
+    meta.family = family_opt.as_ref().map(family_metadata);
+    meta.fixed_parameters = fixed
+        .iter()
+        .map(|f| (f.parameter.clone(), MetaBuilder::argument_to_json(&f.value)))
+        .collect();
+    meta.metadata.options = options
+        .iter()
+        .map(|o| (o.name.clone(), MetaBuilder::argument_to_json(&o.value)))
+        .collect();
+
+    // The main formula is the `mu` parameter
+    let mut correlation_ids = HashMap::new();
+    link_correlation_ids(&mut correlation_ids, "mu", &meta.columns);
+
+    // A non-linear parameter (`nlf(sigma ~ a * exp(b * x))`) is a dpars block
+    // whose columns are the covariates of its expression
+    let mut nlpar_names = meta
+        .nonlinear
+        .as_ref()
+        .map(|n| n.parameters.clone())
+        .unwrap_or_default();
+    let names: Vec<String> = parameters.iter().map(|p| p.parameter.clone()).collect();
+    for (parameter, expression) in model.nonlinear_dpars {
+        let mut pmb = MetaBuilder::new();
+        pmb.push_nonlinear_expression(&expression, &names);
+        let pmeta = pmb.build(formula, false, None);
+        if let Some(nonlinear) = &pmeta.nonlinear {
+            nlpar_names.extend(nonlinear.parameters.iter().cloned());
+        }
+        meta.dpars.insert(
+            parameter,
+            ParameterMetaData {
+                has_intercept: false,
+                columns: pmeta.columns,
+                all_generated_columns: pmeta.all_generated_columns,
+                nonlinear: pmeta.nonlinear,
+            },
+        );
+    }
+
+    // Each parameter sub-formula (`sigma ~ x`) gets its own columns block
+    for parameter in parameters {
+        let mut pmb = MetaBuilder::new();
+        push_terms(&mut pmb, parameter.terms);
+        let pmeta = pmb.build(formula, parameter.has_intercept, None);
+        meta.metadata.is_random_effects_model |= pmeta.metadata.is_random_effects_model;
+        meta.metadata.has_uncorrelated_slopes_and_intercepts |=
+            pmeta.metadata.has_uncorrelated_slopes_and_intercepts;
+        for warning in pmeta.metadata.warnings {
+            meta.metadata
+                .warnings
+                .push(format!("{}: {}", parameter.parameter, warning));
+        }
+        link_correlation_ids(&mut correlation_ids, &parameter.parameter, &pmeta.columns);
+        let block = if nlpar_names.contains(&parameter.parameter) {
+            &mut meta.nlpars
+        } else {
+            &mut meta.dpars
+        };
+        block.insert(
+            parameter.parameter,
+            ParameterMetaData {
+                has_intercept: parameter.has_intercept,
+                columns: pmeta.columns,
+                all_generated_columns: pmeta.all_generated_columns,
+                nonlinear: None,
+            },
+        );
+    }
+    meta.correlation_ids = correlation_ids;
+    meta
+}
+
+/// Builds the metadata of a model with one formula per response
+///
+/// Each response gets its own block under `responses`. The top level combines
+/// the responses, in formula order, with the right-hand side terms written in
+/// every formula; term removals and parameter formulas only apply in the
+/// blocks.
+fn build_multivariate(formula: &str, responses: Vec<ResponseModel>) -> FormulaMetaData {
+    let mut mb = MetaBuilder::new();
+    // Responses come first so that they keep the lowest IDs
+    for model in &responses {
+        if let Some(response) = &model.parsed.response {
+            mb.push_response(response);
+        }
+    }
+    for model in &responses {
+        let ParsedFormula {
+            response,
+            terms,
+            parameters,
+            nonlinear,
+            additions,
+            ..
+        } = &model.parsed;
+        if let Some(response) = response {
+            if !additions.is_empty() {
+                mb.push_response_additions(response, additions);
+            }
+        }
+        let terms = terms
+            .iter()
+            .filter(|t| !matches!(t, Term::Removal(_)))
+            .cloned()
+            .collect();
+        push_terms(&mut mb, terms);
+        if let Some(expression) = nonlinear {
+            let names: Vec<String> = parameters.iter().map(|p| p.parameter.clone()).collect();
+            mb.push_nonlinear_expression(expression, &names);
+        }
+    }
+    let has_intercept = responses.iter().any(|m| m.parsed.has_intercept);
+    // The family is only shared when every response has the same one
+    let family = responses
+        .iter()
+        .all(|m| m.parsed.family == responses[0].parsed.family)
+        .then(|| responses[0].parsed.family.clone())
+        .flatten();
+    let family_name = family.as_ref().map(|spec| spec.family.name().to_string());
+    let mut meta = mb.build(formula, has_intercept, family_name);
+    meta.family = family.as_ref().map(family_metadata);
+    // Response additions, non-linear expressions and autocorrelation structures
+    // are described in the blocks
+    meta.response = None;
+    meta.nonlinear = None;
+    meta.autocorrelation.clear();
+    meta.metadata.warnings.clear();
+
+    for model in responses {
+        let key = model
+            .parsed
+            .response
+            .as_ref()
+            .and_then(response_name)
+            .unwrap_or_default();
+        let block = build_response(formula, model);
+        meta.metadata.is_random_effects_model |= block.metadata.is_random_effects_model;
+        meta.metadata.has_uncorrelated_slopes_and_intercepts |=
+            block.metadata.has_uncorrelated_slopes_and_intercepts;
+        for warning in &block.metadata.warnings {
+            meta.metadata.warnings.push(format!("{}: {}", key, warning));
+        }
+        meta.metadata.options.extend(block.metadata.options.clone());
+        // A correlation ID shared across responses links parameters of both
+        for (id, parameters) in &block.correlation_ids {
+            let linked = meta.correlation_ids.entry(id.clone()).or_default();
+            linked.extend(parameters.iter().map(|p| format!("{}_{}", p, key)));
+        }
+        meta.responses.insert(key, block);
+    }
+    meta
+}
+
+/// Describes a family with its distributional parameters and their links
+fn family_metadata(spec: &FamilySpec) -> FamilyMetaData {
+    FamilyMetaData {
+        name: spec.family.name().to_string(),
+        dpars: spec.links.iter().map(|(dpar, _)| dpar.clone()).collect(),
+        links: spec.links.iter().cloned().collect(),
+        components: match &spec.family {
+            Family::Mixture(families) => families.iter().map(|f| f.name().to_string()).collect(),
+            _ => Vec::new(),
+        },
+    }
+}
+
+/// Pushes the right-hand side terms of a formula into a builder
+///
+/// Terms are applied in formula order, as in R: a removal only affects the
+/// terms written before it, so `y ~ x - x + x` keeps `x`.
+fn push_terms(mb: &mut MetaBuilder, terms: Vec<Term>) {
+    for t in terms {
+        match t {
+            Term::Column(name) => mb.push_plain_term(&name),
+            Term::Function { name, args } => mb.push_function_term(&name, &args),
+            Term::Interaction {
+                left,
+                right,
+                operator,
+            } => mb.push_interaction(&left, &right, operator),
+            Term::RandomEffect(random_effect) => mb.push_random_effect(&random_effect),
+            Term::Group(terms) => mb.push_group(&terms),
+            Term::Power { base, order } => mb.push_power(&base, order),
+            Term::AsIs(expression) => mb.push_as_is_term(&expression),
+            Term::Autocorrelation(autocorrelation) => mb.push_autocorrelation(&autocorrelation),
+            Term::Intercept => {
+                // Intercept terms are handled by the has_intercept flag in the build method
+                // No additional processing needed here
+            }
+            Term::Zero => {
+                // Zero terms indicate no intercept - this is handled by the has_intercept flag
+                // No additional processing needed here
+            }
+            Term::Removal(term) => mb.remove_term(&term),
+        }
+    }
+}
+
+/// Records the cross-parameter correlation IDs used by one parameter's columns
+///
+/// `y ~ x + (1 |ID1| g), zi ~ (1 |ID1| g)` links `ID1` to `["mu", "zi"]`.
+fn link_correlation_ids(
+    links: &mut HashMap<String, Vec<String>>,
+    parameter: &str,
+    columns: &HashMap<String, VariableInfo>,
+) {
+    let ids = columns
+        .values()
+        .flat_map(|v| &v.random_effects)
+        .filter_map(|r| r.correlation_id.as_ref());
+    for id in ids {
+        let parameters = links.entry(id.clone()).or_default();
+        if !parameters.iter().any(|p| p == parameter) {
+            parameters.push(parameter.to_string());
+        }
+    }
+}
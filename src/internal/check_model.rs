@@ -0,0 +1,370 @@
+//! # Model Checks
+//!
+//! Validation that needs the whole model rather than a single clause: fixed
+//! parameters and parameter formulas must belong to the family, non-linear
+//! formulas need parameters, autocorrelation structures may only appear
+//! once in a main formula, a formula cannot repeat a smooth or spline, and
+//! the response cannot reappear as a predictor.
+
+use crate::internal::{
+    ast::{Argument, Expression, Family, FamilySpec, FixedParameter, FormulaOption, Term},
+    errors::ParseError,
+    model::{response_name, response_variables, Model},
+    parse_family::is_distributional_parameter,
+};
+
+/// Runs every model-level check, resolving fixed parameters first
+pub(crate) fn check_model(model: Model) -> Result<Model, ParseError> {
+    resolve_fixed_parameters(model)
+        .and_then(check_nonlinear_parameters)
+        .and_then(check_family_parameters)
+        .and_then(check_autocorrelation)
+        .and_then(check_duplicate_bases)
+        .and_then(check_response_predictors)
+}
+
+/// Sorts the `name = value` clauses of each formula into fixed parameters and options
+///
+/// A distributional parameter name fixes that parameter: `bias = 0.5` to a
+/// constant, `sigma2 = "sigma1"` to another parameter. With a family, both must
+/// be parameters of the family, as for parameter formulas; formulas without a
+/// family are not checked. `mu` is predicted by the main formula, so it cannot
+/// be fixed, and constants must be finite. Any other name is kept as a formula
+/// option with a warning.
+fn resolve_fixed_parameters(mut model: Model) -> Result<Model, ParseError> {
+    let several = model.responses.len() > 1;
+    for response in &mut model.responses {
+        let parsed = &mut response.parsed;
+        let (fixed, options): (Vec<FixedParameter>, Vec<FixedParameter>) =
+            std::mem::take(&mut parsed.fixed)
+                .into_iter()
+                .partition(|f| is_distributional_parameter(&f.parameter));
+        for f in &fixed {
+            if f.parameter == "mu" {
+                return Err(ParseError::Syntax(
+                    "parameter 'mu' is predicted by the main formula and cannot be fixed".into(),
+                ));
+            }
+            let target = match &f.value {
+                Argument::String(target) => Some(target),
+                Argument::Number(n) if !n.is_finite() => {
+                    return Err(ParseError::Syntax(format!(
+                        "parameter '{}' must be fixed to a finite number",
+                        f.parameter
+                    )))
+                }
+                Argument::Integer(_) | Argument::Number(_) => None,
+                _ => {
+                    return Err(ParseError::Syntax(format!(
+                        "parameter '{}' can only be fixed to a number or to another parameter in quotes",
+                        f.parameter
+                    )))
+                }
+            };
+            if let Some(spec) = &parsed.family {
+                check_family_parameter(spec, &f.parameter)?;
+                if let Some(target) = target {
+                    check_family_parameter(spec, target)?;
+                }
+            }
+        }
+        for option in options {
+            let warning = format!("unknown formula option '{}'", option.parameter);
+            match parsed.response.as_ref().and_then(response_name) {
+                Some(key) if several => model.warnings.push(format!("{}: {}", key, warning)),
+                _ => model.warnings.push(warning),
+            }
+            parsed.options.push(FormulaOption {
+                name: option.parameter,
+                value: option.value,
+            });
+        }
+        check_parameter_references(&fixed)?;
+        parsed.fixed = fixed;
+    }
+    Ok(model)
+}
+
+/// Rejects parameters fixed to themselves, directly or through other parameters
+///
+/// `sigma2 = "sigma2"` and `sigma1 = "sigma2", sigma2 = "sigma1"` leave the
+/// parameters without a value.
+fn check_parameter_references(fixed: &[FixedParameter]) -> Result<(), ParseError> {
+    let target = |name: &str| {
+        fixed.iter().find_map(|f| match &f.value {
+            Argument::String(target) if f.parameter == name => Some(target.as_str()),
+            _ => None,
+        })
+    };
+    for f in fixed {
+        let mut chain = vec![f.parameter.as_str()];
+        while let Some(next) = target(chain[chain.len() - 1]) {
+            if next == f.parameter {
+                return Err(ParseError::Syntax(if chain.len() == 1 {
+                    format!("parameter '{}' cannot be fixed to itself", f.parameter)
+                } else {
+                    format!(
+                        "parameters {} are fixed to each other in a cycle",
+                        chain.iter().map(|name| format!("'{}'", name)).collect::<Vec<_>>().join(", ")
+                    )
+                }));
+            }
+            if chain.contains(&next) {
+                break;
+            }
+            chain.push(next);
+        }
+    }
+    Ok(())
+}
+
+/// Checks that each non-linear expression uses at least one parameter formula
+///
+/// Names without a parameter formula are covariates, so an expression that
+/// uses none has no parameters left to estimate. Conversely, a parameter
+/// formula such as `a3 ~ 1` that no expression uses and that is not a
+/// distributional parameter predicts nothing.
+fn check_nonlinear_parameters(model: Model) -> Result<Model, ParseError> {
+    for response in &model.responses {
+        let parameters = &response.parsed.parameters;
+        let has_parameter = |expression: &Expression| {
+            parameters
+                .iter()
+                .any(|p| expression_uses(expression, &p.parameter))
+        };
+        if let Some(expression) = &response.parsed.nonlinear {
+            if !has_parameter(expression) {
+                return Err(ParseError::Syntax(
+                    "non-linear formula has no parameter formulas for its parameters".to_string(),
+                ));
+            }
+        }
+        for (parameter, expression) in &response.nonlinear_dpars {
+            if !has_parameter(expression) {
+                return Err(ParseError::Syntax(format!(
+                    "non-linear formula for '{}' has no parameter formulas for its parameters",
+                    parameter
+                )));
+            }
+        }
+        let mut expressions = response
+            .parsed
+            .nonlinear
+            .iter()
+            .chain(response.nonlinear_dpars.iter().map(|(_, e)| e))
+            .peekable();
+        if expressions.peek().is_none() {
+            continue;
+        }
+        let expressions: Vec<&Expression> = expressions.collect();
+        if let Some(unused) = parameters.iter().find(|p| {
+            !is_distributional_parameter(&p.parameter)
+                && !expressions.iter().any(|e| expression_uses(e, &p.parameter))
+        }) {
+            return Err(ParseError::Syntax(format!(
+                "parameter formula for '{}' is not used by the non-linear formula",
+                unused.parameter
+            )));
+        }
+    }
+    Ok(model)
+}
+
+/// Checks that every parameter formula predicts a parameter of the family
+///
+/// With `family = poisson`, `sigma ~ x` has nothing to predict. Parameters
+/// of a non-linear expression are not distributional parameters and are
+/// not checked. Formulas without a family are not checked either.
+fn check_family_parameters(model: Model) -> Result<Model, ParseError> {
+    for response in &model.responses {
+        let Some(spec) = &response.parsed.family else {
+            continue;
+        };
+        let expressions: Vec<&Expression> = response
+            .parsed
+            .nonlinear
+            .iter()
+            .chain(response.nonlinear_dpars.iter().map(|(_, e)| e))
+            .collect();
+        let dpars = response
+            .parsed
+            .parameters
+            .iter()
+            .map(|p| &p.parameter)
+            .filter(|name| !expressions.iter().any(|e| expression_uses(e, name)))
+            .chain(response.nonlinear_dpars.iter().map(|(name, _)| name));
+        for name in dpars {
+            check_family_parameter(spec, name)?;
+        }
+    }
+    Ok(model)
+}
+
+/// Checks that autocorrelation structures are only used once, in main formulas
+fn check_autocorrelation(model: Model) -> Result<Model, ParseError> {
+    let is_autocorrelation = |t: &&Term| matches!(t, Term::Autocorrelation(_));
+    for response in &model.responses {
+        for parameter in &response.parsed.parameters {
+            if parameter.terms.iter().any(|t| is_autocorrelation(&t)) {
+                return Err(ParseError::Syntax(format!(
+                    "autocorrelation structures belong in the main formula, not in the formula for '{}'",
+                    parameter.parameter
+                )));
+            }
+        }
+        let mut names = Vec::new();
+        for term in response.parsed.terms.iter().filter(is_autocorrelation) {
+            if let Term::Autocorrelation(autocorrelation) = term {
+                if names.contains(&autocorrelation.name()) {
+                    return Err(ParseError::Syntax(format!(
+                        "a formula can have only one {} autocorrelation structure",
+                        autocorrelation.name()
+                    )));
+                }
+                names.push(autocorrelation.name());
+            }
+        }
+    }
+    Ok(model)
+}
+
+/// Rejects a smooth or spline written twice in one formula, such as `s(x) + s(x)`
+///
+/// Both would describe the same basis functions, so the second one cannot be
+/// estimated. Bases of the same variable with other arguments are distinct
+/// terms with their own columns.
+fn check_duplicate_bases(model: Model) -> Result<Model, ParseError> {
+    fn collect<'a>(
+        terms: &'a [Term],
+        bases: &mut Vec<(&'a str, &'a [Argument])>,
+    ) -> Result<(), ParseError> {
+        for term in terms {
+            match term {
+                Term::Group(terms) => collect(terms, bases)?,
+                Term::Function { name, args }
+                    if matches!(name.as_str(), "s" | "te" | "ti" | "t2" | "bs" | "ns") =>
+                {
+                    let basis = (name.as_str(), args.as_slice());
+                    if bases.contains(&basis) {
+                        let variables: Vec<&str> = args
+                            .iter()
+                            .map_while(|a| match a {
+                                Argument::Ident(s) => Some(s.as_str()),
+                                _ => None,
+                            })
+                            .collect();
+                        return Err(ParseError::Syntax(format!(
+                            "the basis {}({}) appears more than once in the formula",
+                            name,
+                            variables.join(", ")
+                        )));
+                    }
+                    bases.push(basis);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    for response in &model.responses {
+        collect(&response.parsed.terms, &mut Vec::new())?;
+        for parameter in &response.parsed.parameters {
+            collect(&parameter.terms, &mut Vec::new())?;
+        }
+    }
+    Ok(model)
+}
+
+/// Rejects a main formula that uses its response as a predictor, such as `log(y) ~ log(y)`
+///
+/// The response and the predictor would share the response's columns, so the
+/// predictor would disappear from the model. Random effects and removed terms
+/// may still name the response.
+fn check_response_predictors(model: Model) -> Result<Model, ParseError> {
+    fn argument_uses(arg: &Argument, name: &str) -> bool {
+        match arg {
+            Argument::Ident(column) => column == name,
+            Argument::Named(_, value) => argument_uses(value, name),
+            Argument::Function { args, .. } => args.iter().any(|a| argument_uses(a, name)),
+            _ => false,
+        }
+    }
+    fn term_uses(term: &Term, name: &str) -> bool {
+        match term {
+            Term::Column(column) => column == name,
+            Term::Function { args, .. } => args.iter().any(|a| argument_uses(a, name)),
+            Term::AsIs(expression) => expression_uses(expression, name),
+            Term::Interaction { left, right, .. } => term_uses(left, name) || term_uses(right, name),
+            Term::Power { base, .. } => term_uses(base, name),
+            Term::Group(terms) => terms.iter().any(|t| term_uses(t, name)),
+            _ => false,
+        }
+    }
+
+    for response in &model.responses {
+        let parsed = &response.parsed;
+        let variables = parsed.response.as_ref().map(response_variables).unwrap_or_default();
+        if let Some(name) = variables
+            .into_iter()
+            .find(|name| parsed.terms.iter().any(|t| term_uses(t, name)))
+        {
+            return Err(ParseError::Syntax(format!(
+                "the response '{}' cannot also be a predictor",
+                name
+            )));
+        }
+    }
+    Ok(model)
+}
+
+/// Checks that a family has the distributional parameter `name`
+fn check_family_parameter(spec: &FamilySpec, name: &str) -> Result<(), ParseError> {
+    if spec.links.iter().any(|(dpar, _)| dpar == name) {
+        return Ok(());
+    }
+    check_component_index(spec, name)?;
+    let names: Vec<&str> = spec.links.iter().map(|(dpar, _)| dpar.as_str()).collect();
+    Err(ParseError::Syntax(format!(
+        "family {} has no parameter '{}'; its parameters are {}",
+        spec.family.name(),
+        name,
+        names.join(", ")
+    )))
+}
+
+/// Rejects a mixture parameter such as `mu3` whose index has no component
+fn check_component_index(spec: &FamilySpec, name: &str) -> Result<(), ParseError> {
+    let Family::Mixture(families) = &spec.family else {
+        return Ok(());
+    };
+    // A mixture parameter is a component parameter followed by its index
+    let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let is_component_parameter = spec
+        .links
+        .iter()
+        .any(|(dpar, _)| *dpar == format!("{}1", prefix));
+    match name[prefix.len()..].parse::<usize>() {
+        Ok(k) if is_component_parameter && (k == 0 || k > families.len()) => {
+            Err(ParseError::Syntax(format!(
+                "mixture has {} components, so '{}' has no component",
+                families.len(),
+                name
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks whether an expression reads the column `name`
+fn expression_uses(expression: &Expression, name: &str) -> bool {
+    match expression {
+        Expression::Column(column) => column == name,
+        Expression::Number(_) => false,
+        Expression::Negate(operand) => expression_uses(operand, name),
+        Expression::Binary { left, right, .. } => {
+            expression_uses(left, name) || expression_uses(right, name)
+        }
+        Expression::Call { args, .. } => args.iter().any(|arg| expression_uses(arg, name)),
+    }
+}
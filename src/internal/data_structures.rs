@@ -70,7 +70,7 @@ use std::collections::HashMap;
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum VariableRole {
-    /// The dependent/response variable (always gets ID 1)
+    /// The dependent/response variable (gets ID 1; multivariate responses get 1, 2, ...)
     ///
     /// # Examples
     /// - `y` in `y ~ x + z`
@@ -119,6 +119,26 @@ pub enum VariableRole {
     /// - `c(treatment, ref=control)` for categorical treatment with control as reference
     /// - `c(group, ref="group1")` for categorical group with "group1" as reference
     Categorical,
+
+    /// A covariate read directly by a non-linear expression
+    ///
+    /// # Examples
+    /// - `x` in `y ~ a1 - a2^x, a1 ~ 1, a2 ~ 1, nl = TRUE`
+    NonLinearCovariate,
+
+    /// A variable used by a response addition term
+    ///
+    /// # Examples
+    /// - `n` in `y | trials(n) ~ x`
+    /// - `w` in `y | weights(w) ~ x`
+    ResponseAddition,
+
+    /// A time or grouping variable of an autocorrelation structure
+    ///
+    /// # Examples
+    /// - `time` and `g` in `y ~ x + ar(time, g)`
+    /// - `g` in `(y ~ x) + cor_ar(~ 1 | g)`
+    Autocorrelation,
 }
 
 /// A transformation applied to a variable
@@ -140,14 +160,16 @@ pub enum VariableRole {
 ///         "degree": 3,
 ///         "orthogonal": true
 ///     }),
-///     generates_columns: vec!["x_poly_1".to_string(), "x_poly_2".to_string(), "x_poly_3".to_string()]
+///     generates_columns: vec!["x_poly_1".to_string(), "x_poly_2".to_string(), "x_poly_3".to_string()],
+///     input: None
 /// };
 ///
 /// // Logarithm transformation: log(y)
 /// let log_transform = Transformation {
 ///     function: "log".to_string(),
 ///     parameters: json!({}),
-///     generates_columns: vec!["y_log".to_string()]
+///     generates_columns: vec!["y_log".to_string()],
+///     input: None
 /// };
 ///
 /// // Scaling transformation: scale(z)
@@ -157,7 +179,16 @@ pub enum VariableRole {
 ///         "center": true,
 ///         "scale": true
 ///     }),
-///     generates_columns: vec!["z_scaled".to_string()]
+///     generates_columns: vec!["z_scaled".to_string()],
+///     input: None
+/// };
+///
+/// // Chained step of offset(log(exposure)), applied to the output of log()
+/// let offset_transform = Transformation {
+///     function: "offset".to_string(),
+///     parameters: json!({"arg_0": "exposure_log"}),
+///     generates_columns: vec!["exposure_log_offset".to_string()],
+///     input: Some("exposure_log".to_string())
 /// };
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -185,6 +216,15 @@ pub struct Transformation {
     /// - `["y_log"]` for log(y)
     /// - `["z_scaled"]` for scale(z)
     pub generates_columns: Vec<String>,
+
+    /// The column this transformation is applied to when it is part of a
+    /// nested call chain, omitted otherwise
+    ///
+    /// # Examples
+    /// - `None` for log(x)
+    /// - `Some("exposure_log")` for the `offset` step of offset(log(exposure))
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
 }
 
 /// An interaction between variables
@@ -262,7 +302,8 @@ pub struct Interaction {
 ///     has_intercept: true,
 ///     correlated: true,
 ///     includes_interactions: vec![],
-///     variables: Some(vec![])
+///     variables: Some(vec![]),
+///     correlation_id: None
 /// };
 ///
 /// // Random slope: (x | group)
@@ -272,7 +313,8 @@ pub struct Interaction {
 ///     has_intercept: false,
 ///     correlated: true,
 ///     includes_interactions: vec![],
-///     variables: None
+///     variables: None,
+///     correlation_id: None
 /// };
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -320,6 +362,17 @@ pub struct RandomEffectInfo {
     /// - `Some(vec!["x"])` for `(x | group)`
     /// - `None` for slope random effects
     pub variables: Option<Vec<String>>, // For grouping kind
+
+    /// The cross-parameter correlation ID, omitted when there is none
+    ///
+    /// Random effects that share an ID are correlated with each other, also
+    /// across parameter sub-formulas.
+    ///
+    /// # Examples
+    /// - `Some("ID1")` for `(1 |ID1| g)`
+    /// - `Some("2")` for `(1 | gr(g, id = 2))`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 /// Complete information about a variable in the model
@@ -350,7 +403,8 @@ pub struct RandomEffectInfo {
 ///     transformations: vec![Transformation {
 ///         function: "poly".to_string(),
 ///         parameters: json!({"degree": 2}),
-///         generates_columns: vec!["x_poly_1".to_string(), "x_poly_2".to_string()]
+///         generates_columns: vec!["x_poly_1".to_string(), "x_poly_2".to_string()],
+///         input: None
 ///     }],
 ///     interactions: vec![Interaction {
 ///         with: vec!["z".to_string()],
@@ -364,14 +418,15 @@ pub struct RandomEffectInfo {
 ///         has_intercept: false,
 ///         correlated: true,
 ///         includes_interactions: vec![],
-///         variables: None
+///         variables: None,
+///         correlation_id: None
 ///     }],
 ///     generated_columns: vec!["x_poly_1".to_string(), "x_poly_2".to_string()]
 /// };
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VariableInfo {
-    /// Unique identifier for this variable (response always gets ID 1)
+    /// Unique identifier for this variable (the response gets ID 1)
     pub id: u32,
 
     /// All roles this variable plays in the model
@@ -399,6 +454,7 @@ pub struct VariableInfo {
 ///
 /// ```rust
 /// use fiasto::internal::data_structures::FormulaMetadataInfo;
+/// use std::collections::HashMap;
 ///
 /// // Simple linear model
 /// let linear_meta = FormulaMetadataInfo {
@@ -406,7 +462,10 @@ pub struct VariableInfo {
 ///     is_random_effects_model: false,
 ///     has_uncorrelated_slopes_and_intercepts: false,
 ///     family: Some("gaussian".to_string()),
-///     response_variable_count: 1
+///     response_variable_count: 1,
+///     warnings: vec![],
+///     rescor: None,
+///     options: HashMap::new()
 /// };
 ///
 /// // Mixed effects model with uncorrelated effects
@@ -415,7 +474,10 @@ pub struct VariableInfo {
 ///     is_random_effects_model: true,
 ///     has_uncorrelated_slopes_and_intercepts: true,
 ///     family: Some("gaussian".to_string()),
-///     response_variable_count: 1
+///     response_variable_count: 1,
+///     warnings: vec![],
+///     rescor: None,
+///     options: HashMap::new()
 /// };
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -432,8 +494,364 @@ pub struct FormulaMetadataInfo {
     /// The distribution family for the model (if specified)
     pub family: Option<String>,
 
-    /// Number of response variables (1 for single response, >1 for multivariate)
+    /// Number of response variables (0 for one-sided formulas, 1 for single response, >1 for multivariate)
     pub response_variable_count: u32,
+
+    /// Non-fatal problems found while building the metadata
+    ///
+    /// # Examples
+    /// - `["term 'w' is not in the model and cannot be removed"]` for `y ~ x - w`
+    pub warnings: Vec<String>,
+
+    /// Whether the residuals of a multivariate model are correlated
+    ///
+    /// Omitted unless set with `set_rescor()`, e.g. `true` for
+    /// `bf(y1 ~ x) + bf(y2 ~ z) + set_rescor(TRUE)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rescor: Option<bool>,
+
+    /// Formula options given as `key = value` clauses, keyed by option name
+    ///
+    /// Names are written as strings. Omitted when the formula has no options.
+    ///
+    /// # Examples
+    /// - `{"fill": "mean"}` for `y ~ x + (1 | g), fill = "mean"`
+    /// - `{"nl": true}` for `y ~ a * x, a ~ 1, nl = TRUE`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub options: HashMap<String, serde_json::Value>,
+}
+
+/// Metadata for a parameter sub-formula such as `sigma ~ x + (1 | g)`
+///
+/// Each sub-formula gets its own variable-centric `columns` map and its own
+/// `all_generated_columns` list, built exactly like those of the main formula.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::data_structures::{ParameterMetaData, VariableInfo, VariableRole};
+/// use std::collections::HashMap;
+///
+/// // sigma ~ x
+/// let mut columns = HashMap::new();
+/// columns.insert("x".to_string(), VariableInfo {
+///     id: 1,
+///     roles: vec![VariableRole::Identity],
+///     transformations: vec![],
+///     interactions: vec![],
+///     random_effects: vec![],
+///     generated_columns: vec!["x".to_string()]
+/// });
+///
+/// let sigma = ParameterMetaData {
+///     has_intercept: true,
+///     columns,
+///     all_generated_columns: vec!["intercept".to_string(), "x".to_string()],
+///     nonlinear: None
+/// };
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParameterMetaData {
+    /// Whether the sub-formula includes an intercept term
+    pub has_intercept: bool,
+
+    /// Detailed information about each variable in the sub-formula
+    pub columns: HashMap<String, VariableInfo>,
+
+    /// All generated column names ordered by variable ID, including intercept if present
+    pub all_generated_columns: Vec<String>,
+
+    /// The expression of a non-linear parameter formula
+    ///
+    /// Omitted unless the parameter is given by `nlf()`, e.g.
+    /// `nlf(sigma ~ a * exp(b * x))`. The columns are then its covariates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonlinear: Option<NonLinearMetaData>,
+}
+
+/// The role a formula plays in a model specification
+///
+/// # Examples
+/// - `Main` for `y ~ x` in `bf(y ~ x) + lf(sigma ~ z)`
+/// - `LinearPredictor` for `sigma ~ z` in `bf(y ~ x) + lf(sigma ~ z)`
+/// - `NonLinearPredictor` for `sigma ~ a * exp(b * x)` in `nlf(sigma ~ a * exp(b * x))`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum FormulaRole {
+    /// The main formula of a response
+    Main,
+    /// A linear formula for a parameter: `lf(b ~ z)` or a `b ~ z` clause
+    LinearPredictor,
+    /// A non-linear formula for a parameter: `nlf(sigma ~ a * exp(b * x))`
+    NonLinearPredictor,
+    /// An autocorrelation structure: `cor_ar(~ 1 | g)`, listed as `autocor ~ arma(...)`
+    Autocorrelation,
+}
+
+/// One formula of a model specification such as `bf(y ~ x) + lf(sigma ~ z)`
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::data_structures::{FormulaRole, ModelFormula};
+///
+/// // lf(b ~ z + (1 | g), dpar = "sigma")
+/// let formula = ModelFormula {
+///     formula: "b ~ z + (1 | g)".to_string(),
+///     role: FormulaRole::LinearPredictor,
+///     parameter: "b".to_string(),
+///     dpar: Some("sigma".to_string()),
+///     response: None
+/// };
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModelFormula {
+    /// The formula text with normalized spacing
+    pub formula: String,
+
+    /// The role of the formula in the model
+    pub role: FormulaRole,
+
+    /// The parameter the formula predicts (`mu` for the main formula)
+    pub parameter: String,
+
+    /// The distributional parameter the formula belongs to, when stated
+    ///
+    /// Set by the `dpar` option of `lf()` and `nlf()`, and for formulas nested
+    /// inside `nlf(sigma ~ ..., a ~ x)`, which belong to `sigma`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dpar: Option<String>,
+
+    /// The response the formula belongs to in a multivariate model
+    ///
+    /// Omitted when the model has a single response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+}
+
+/// The expression of a non-linear (`nl = TRUE`) formula
+///
+/// The main right-hand side of a non-linear formula is an arithmetic expression
+/// over parameters and covariates. Parameters are the names that have their own
+/// sub-formula; every other name is a covariate read from the data.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::data_structures::NonLinearMetaData;
+/// use serde_json::json;
+///
+/// // y ~ a1 * x, a1 ~ 1, nl = TRUE
+/// let nonlinear = NonLinearMetaData {
+///     expression: json!({
+///         "type": "binary",
+///         "operator": "*",
+///         "left": {"type": "column", "name": "a1"},
+///         "right": {"type": "column", "name": "x"}
+///     }),
+///     parameters: vec!["a1".to_string()],
+///     covariates: vec!["x".to_string()]
+/// };
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NonLinearMetaData {
+    /// The expression tree, in the same JSON form as `I()` expressions
+    pub expression: serde_json::Value,
+
+    /// The non-linear parameters in order of appearance in the expression
+    pub parameters: Vec<String>,
+
+    /// The covariates in order of appearance in the expression
+    pub covariates: Vec<String>,
+}
+
+/// An autocorrelation structure of the residuals
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::data_structures::AutocorrelationMetaData;
+/// use serde_json::json;
+///
+/// // cor_ar(~ 1 | g)
+/// let autocorrelation = AutocorrelationMetaData {
+///     structure: "arma".to_string(),
+///     term: "arma(time = NA, gr = g, p = 1, q = 0, cov = FALSE)".to_string(),
+///     parameters: json!({"time": null, "gr": "g", "p": 1, "q": 0, "cov": false})
+/// };
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutocorrelationMetaData {
+    /// The kind of structure: `"arma"`, `"cosy"`, `"car"` or `"sar"`
+    ///
+    /// `ar()` and `ma()` are `"arma"` structures with one order set to zero.
+    pub structure: String,
+
+    /// The structure written out with all its arguments
+    pub term: String,
+
+    /// The arguments of the structure, `null` for a missing time or grouping variable
+    ///
+    /// # Examples
+    /// - `{"time": "t", "gr": null, "p": 2, "q": 0, "cov": false}` for `ar(t, p = 2)`
+    /// - `{"matrix": "W", "type": "lag"}` for `sar(W)`
+    pub parameters: serde_json::Value,
+}
+
+/// Response addition information such as trials, censoring or weights
+///
+/// Present when the left-hand side has addition terms after `|`, e.g.
+/// `y | trials(n) ~ x`, or is a `cbind(successes, failures)` binomial
+/// response. Only the fields used by the formula are set.
+///
+/// # Examples
+///
+/// ```rust
+/// use fiasto::internal::data_structures::{ResponseInfo, Truncation};
+/// use serde_json::json;
+///
+/// // y | trials(n) + trunc(lb = 0)
+/// let response = ResponseInfo {
+///     name: "y".to_string(),
+///     trials: Some(json!("n")),
+///     truncation: Some(Truncation { lower: Some(json!(0)), upper: None }),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ResponseInfo {
+    /// The response variable name
+    pub name: String,
+
+    /// Censoring from `cens(censored)` or `cens(censored, y2)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub censoring: Option<Censoring>,
+
+    /// Number of trials from `trials(n)`: a column name or a constant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trials: Option<serde_json::Value>,
+
+    /// Case weights column from `weights(w)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<String>,
+
+    /// Whether the weights are rescaled, from `weights(w, scale = TRUE)`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub scale_weights: bool,
+
+    /// Known standard errors from `se(s)` or `se(s, sigma = TRUE)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub known_meas_error: Option<KnownMeasurementError>,
+
+    /// Truncation bounds from `trunc(lb = 0, ub = 100)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<Truncation>,
+
+    /// Decision column from `dec(choice)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decisions: Option<String>,
+
+    /// Exposure column from `rate(exposure)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<String>,
+
+    /// Whether missing responses are modelled with `mi()`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mi: bool,
+
+    /// Measurement error column from `mi(sdy)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mi_sd: Option<String>,
+
+    /// The two columns of a `cbind(successes, failures)` response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binomial: Option<BinomialResponse>,
+}
+
+/// A two-column binomial response
+///
+/// # Examples
+/// - `cbind(succ, fail)` → `{"successes": "succ", "failures": "fail"}`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BinomialResponse {
+    /// The column counting successes
+    pub successes: String,
+
+    /// The column counting failures
+    pub failures: String,
+}
+
+/// Censoring of the response
+///
+/// # Examples
+/// - `cens(censored)` → `{"variable": "censored"}`
+/// - `cens(censored, y2)` → `{"variable": "censored", "upper_bound_var": "y2"}`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Censoring {
+    /// The censoring indicator column
+    pub variable: String,
+
+    /// The upper bound column for interval censoring
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upper_bound_var: Option<String>,
+}
+
+/// Known measurement error of the response
+///
+/// # Examples
+/// - `se(s, sigma = TRUE)` → `{"variable": "s", "include_residual_sigma": true}`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KnownMeasurementError {
+    /// The standard error column
+    pub variable: String,
+
+    /// Whether the residual standard deviation is estimated as well
+    pub include_residual_sigma: bool,
+}
+
+/// Truncation bounds of the response, each a number or a column name
+///
+/// # Examples
+/// - `trunc(lb = 0)` → `{"lower": 0}`
+/// - `trunc(lb = 0, ub = max_y)` → `{"lower": 0, "upper": "max_y"}`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Truncation {
+    /// The lower bound
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lower: Option<serde_json::Value>,
+
+    /// The upper bound
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upper: Option<serde_json::Value>,
+}
+
+/// The response family with the link function of each distributional parameter
+///
+/// `dpars` lists every distributional parameter of the family in order, with
+/// `mu` (the main formula) first; a sub-formula such as `sigma ~ x` must
+/// predict one of them.
+///
+/// # Examples
+/// - `family = binomial(link = "probit")` → `{"name": "binomial", "dpars": ["mu"], "links": {"mu": "probit"}}`
+/// - `family = student` → `{"name": "student", "dpars": ["mu", "sigma", "nu"], "links": {"mu": "identity", "sigma": "log", "nu": "logm1"}}`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FamilyMetaData {
+    /// The family name, e.g. `"gaussian"` or `"zero_inflated_poisson"`
+    pub name: String,
+
+    /// The distributional parameters of the family
+    pub dpars: Vec<String>,
+
+    /// The link function of each distributional parameter
+    pub links: HashMap<String, String>,
+
+    /// The component families of a mixture, in component order
+    ///
+    /// Omitted for families that are not mixtures.
+    ///
+    /// # Examples
+    /// - `["gaussian", "gaussian"]` for `mixture(gaussian, gaussian)`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<String>,
 }
 
 /// Complete formula metadata structure
@@ -465,7 +883,10 @@ pub struct FormulaMetadataInfo {
 ///         is_random_effects_model: true,
 ///         has_uncorrelated_slopes_and_intercepts: false,
 ///         family: Some("gaussian".to_string()),
-///         response_variable_count: 1
+///         response_variable_count: 1,
+///         warnings: vec![],
+///         rescor: None,
+///         options: HashMap::new()
 ///     },
 ///     columns,
 ///     all_generated_columns: vec!["y".to_string(), "intercept".to_string(), "x".to_string(), "group".to_string()],
@@ -476,7 +897,17 @@ pub struct FormulaMetadataInfo {
 ///         map.insert("3".to_string(), "x".to_string());
 ///         map.insert("4".to_string(), "group".to_string());
 ///         map
-///     }
+///     },
+///     dpars: HashMap::new(),
+///     correlation_ids: HashMap::new(),
+///     nonlinear: None,
+///     nlpars: HashMap::new(),
+///     response: None,
+///     family: None,
+///     fixed_parameters: HashMap::new(),
+///     autocorrelation: vec![],
+///     formulas: vec![],
+///     responses: HashMap::new()
 /// };
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -515,6 +946,92 @@ pub struct FormulaMetaData {
     /// }
     /// ```
     pub all_generated_columns_formula_order: HashMap<String, String>,
+
+    /// Sub-formulas for distributional parameters, keyed by parameter name
+    ///
+    /// Omitted when the formula has no sub-formulas.
+    ///
+    /// # Examples
+    /// - `{"sigma": {...}}` for `y ~ x, sigma ~ z`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub dpars: HashMap<String, ParameterMetaData>,
+
+    /// Cross-parameter correlation IDs and the parameters that use them
+    ///
+    /// The main formula is the `mu` parameter. Omitted when no random effect
+    /// has a correlation ID.
+    ///
+    /// # Examples
+    /// - `{"ID1": ["mu", "zi"]}` for `y ~ x + (1 |ID1| g), zi ~ x + (1 |ID1| g)`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub correlation_ids: HashMap<String, Vec<String>>,
+
+    /// The expression of a non-linear formula
+    ///
+    /// Omitted unless the formula has `nl = TRUE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonlinear: Option<NonLinearMetaData>,
+
+    /// Sub-formulas for non-linear parameters, keyed by parameter name
+    ///
+    /// Sub-formulas for names that do not appear in the non-linear expression
+    /// (such as `sigma`) stay in `dpars`. Omitted for linear formulas.
+    ///
+    /// # Examples
+    /// - `{"a1": {...}, "a2": {...}}` for `y ~ a1 - a2^x, a1 ~ 1, a2 ~ x, nl = TRUE`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub nlpars: HashMap<String, ParameterMetaData>,
+
+    /// Response addition information such as trials, censoring or weights
+    ///
+    /// Omitted unless the left-hand side has addition terms (`y | trials(n) ~ x`)
+    /// or a `cbind(successes, failures)` response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<ResponseInfo>,
+
+    /// The family with its distributional parameters and link functions
+    ///
+    /// Omitted unless the formula specifies a family. `metadata.family` holds
+    /// the family name on its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<FamilyMetaData>,
+
+    /// Distributional parameters fixed to a value instead of predicted
+    ///
+    /// A number is a constant; a string names another parameter the parameter
+    /// is set equal to. Omitted when no parameter is fixed.
+    ///
+    /// # Examples
+    /// - `{"bias": 0.5}` for `rt | dec(d) ~ x, bias = 0.5, family = wiener`
+    /// - `{"sigma2": "sigma1"}` for `y ~ x, sigma2 = "sigma1", family = mixture(gaussian, gaussian)`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fixed_parameters: HashMap<String, serde_json::Value>,
+
+    /// Autocorrelation structures of the residuals, in formula order
+    ///
+    /// Omitted unless the formula has a term such as `ar(time, g)` or the model
+    /// specification a component such as `cor_ar(~ 1 | g)`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub autocorrelation: Vec<AutocorrelationMetaData>,
+
+    /// Every formula of a model specification with its role, in formula order
+    ///
+    /// Omitted for plain formulas; set when the model is assembled from
+    /// components such as `bf(y ~ x) + lf(sigma ~ z) + gaussian()`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formulas: Vec<ModelFormula>,
+
+    /// One block per response of a multivariate model, keyed by response name
+    ///
+    /// Each block is the metadata of that response's own formula, with its own
+    /// IDs, `columns` and `all_generated_columns`. The top level then combines
+    /// the responses and the right-hand side terms of every formula. Omitted
+    /// for models with a single response.
+    ///
+    /// # Examples
+    /// - `{"y1": {...}, "y2": {...}}` for `bf(y1 ~ x) + bf(y2 ~ z)` or `mvbind(y1, y2) ~ x`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub responses: HashMap<String, FormulaMetaData>,
 }
 
 // Legacy structures for backward compatibility
@@ -0,0 +1,86 @@
+use crate::internal::lexer::Token;
+
+/// Checks whether the token at `pos` is a function name followed by `(`.
+///
+/// Keywords that head a call (such as `log` or `scale`) keep their own token,
+/// so function names are matched by their text: any name-like token directly
+/// followed by an opening parenthesis starts a call. Boolean and null literals
+/// are never function names.
+///
+/// # Arguments
+/// * `tokens` - Reference to the vector of tokens
+/// * `pos` - Position of the candidate function name
+///
+/// # Returns
+/// * `bool` - True if `tokens[pos]` is a name and `tokens[pos + 1]` is `(`
+///
+/// # Example
+/// ```
+/// use fiasto::internal::is_call::is_call;
+/// use fiasto::internal::lexer::Token;
+///
+/// let tokens = vec![
+///     (Token::Log, "log"),
+///     (Token::FunctionStart, "("),
+///     (Token::ColumnName, "x"),
+///     (Token::FunctionEnd, ")"),
+/// ];
+///
+/// assert!(is_call(&tokens, 0)); // log(
+/// assert!(!is_call(&tokens, 2)); // x is not followed by (
+/// ```
+///
+/// # Use Cases
+/// - Nested calls in function arguments: `offset(log(exposure))`
+/// - Calls inside `I()` and non-linear expressions: `I(log(x) + 1)`
+/// - Transformed responses: `log(y) ~ x`
+/// - Telling call parentheses from grouping parentheses while lexing
+pub fn is_call(tokens: &[(Token, &str)], pos: usize) -> bool {
+    let is_name = crate::internal::peek::peek(tokens, pos).is_some_and(|(tok, slice)| {
+        slice.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '.')
+            && !matches!(
+                tok,
+                Token::True
+                    | Token::TrueUpper
+                    | Token::False
+                    | Token::FalseUpper
+                    | Token::Null
+                    | Token::NullUpper
+            )
+    });
+    is_name
+        && crate::internal::peek::peek(tokens, pos + 1)
+            .map(|(t, _)| matches!(t, Token::FunctionStart))
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_call_function_name() {
+        let tokens = vec![(Token::ColumnName, "my_fn"), (Token::FunctionStart, "(")];
+        assert!(is_call(&tokens, 0));
+    }
+
+    #[test]
+    fn test_is_call_requires_parenthesis() {
+        let tokens = vec![(Token::ColumnName, "x"), (Token::Plus, "+")];
+        assert!(!is_call(&tokens, 0));
+        assert!(!is_call(&tokens, 1));
+        assert!(!is_call(&tokens, 2));
+    }
+
+    #[test]
+    fn test_is_call_rejects_literals_and_operators() {
+        let tokens = vec![
+            (Token::TrueUpper, "TRUE"),
+            (Token::FunctionStart, "("),
+            (Token::Plus, "+"),
+            (Token::FunctionStart, "("),
+        ];
+        assert!(!is_call(&tokens, 0));
+        assert!(!is_call(&tokens, 2));
+    }
+}
@@ -21,10 +21,13 @@
 //! - `~` for formula separation (response ~ predictors)
 //! - `|`, `||` for random effects grouping
 //! - `:` for interactions
+//! - `^` for limiting interaction order
+//! - `/` and `%in%` for nesting
 //!
 //! ### Identifiers and Literals
-//! - Variable names: `[a-zA-Z][a-zA-Z0-9_]*`
-//! - Integers: `0`, `1`, `[2-9]\d*`
+//! - Variable names: `x`, `Sepal.Length`, `_id`, `é_score`, or backtick-quoted `` `revenue 2024` ``
+//! - Integers: `0`, `1`, `[0-9]+`
+//! - Decimal and scientific numbers: `0.5`, `1e-3`
 //! - Strings: `"[^"]*"`
 //! - Booleans: `true`, `false`, `TRUE`, `FALSE`
 //! - Null values: `null`, `NULL`
@@ -32,13 +35,20 @@
 //! ### Function Tokens
 //! - Transformations: `poly`, `log`, `scale`, `center`, etc.
 //! - Random effects: `gr`, `mm`, `mmc`, `cs`
-//! - Statistical functions: `offset`, `factor`, `bs`, `gp`, etc.
+//! - Statistical functions: `offset`, `factor`, `bs`, `ns`, `gp`, etc.
+//! - Smooths: `s`, `te`, `ti`, `t2`
+//!
+//! These words are contextual keywords: the parser turns them back into
+//! `ColumnName` tokens unless they head a function call, name a `gr()` option,
+//! start the `family =` clause or name a family component (`+ gaussian()`),
+//! so `y ~ id + lag` is a valid formula.
 //!
 //! ### Special Syntax
 //! - Parentheses: `(`, `)`
 //! - Comma: `,`
 //! - Equals: `=`
-//! - Family specification: `family`, `gaussian`, `binomial`, `poisson`
+//! - Family specification: `family`, `gaussian`, `binomial`, `poisson` (other family
+//!   names such as `student` are column names the parser recognizes by their text)
 //!
 //! ## Examples
 //!
@@ -101,19 +111,25 @@ pub enum Token {
 
     /// Zero: `0`
     /// Used for intercept suppression in random effects
-    #[token("0")]
+    #[token("0", priority = 3)]
     Zero,
 
     /// One: `1`
     /// Used for intercept terms in random effects
-    #[token("1")]
+    #[token("1", priority = 3)]
     One,
 
-    /// Integer numbers: `2`, `3`, `4`, etc.
-    /// Used for polynomial degrees and other numeric parameters
-    #[regex(r"[2-9]\d*")]
+    /// Integer numbers: `2`, `10`, `250`, etc.
+    /// Used for polynomial degrees and other numeric parameters.
+    /// The exact literals `0` and `1` lex as `Zero` and `One` instead.
+    #[regex(r"[0-9]+")]
     Integer,
 
+    /// Decimal and scientific numbers: `0.5`, `.25`, `1e-3`, `2.5E4`
+    /// Used for numeric parameters such as `trunc(lb = 0.5)`
+    #[regex(r"([0-9]+\.[0-9]*|\.[0-9]+)([eE][+-]?[0-9]+)?|[0-9]+[eE][+-]?[0-9]+")]
+    Number,
+
     /// String literals: `"text"`
     /// Used for string arguments in function calls
     #[regex(r#""[^"]*""#)]
@@ -144,9 +160,13 @@ pub enum Token {
     #[token("NULL")]
     NullUpper,
 
-    /// Variable names and identifiers: `x`, `group`, `response_var`
-    /// Matches: `[a-zA-Z][a-zA-Z0-9_]*`
-    #[regex(r"[a-zA-Z][a-zA-Z0-9_]*")]
+    /// Variable names and identifiers: `x`, `group`, `Sepal.Length`, `_id`, `é_score`
+    /// Names start with a letter, `_`, or a `.` that is not followed by a digit,
+    /// and continue with letters, digits, `_` and `.`. Backtick-quoted names such
+    /// as `` `revenue 2024` `` may contain any character except a backtick; the
+    /// parser drops the backticks.
+    #[regex(r"([\p{L}_]|\.[\p{L}_.])[\p{L}\p{N}_.]*")]
+    #[regex(r"`[^`]+`")]
     ColumnName,
 
     // Formula structure operators
@@ -178,15 +198,25 @@ pub enum Token {
     InteractionOnly,
 
     /// Slash: `/`
-    /// Used for nested grouping in random effects
+    /// Used for nesting, both in fixed effects (`a/b`) and in random effects grouping
     #[token("/")]
     Slash,
 
+    /// Nested-within operator: `%in%`
+    /// Nests the terms on its left within those on its right: `b %in% a`
+    #[token("%in%")]
+    NestedWithin,
+
     /// Asterisk: `*`
     /// Creates full interactions (main effects + interaction)
     #[token("*")]
     InteractionAndEffect,
 
+    /// Caret: `^`
+    /// Limits the order of interactions: `(a + b + c)^2`
+    #[token("^")]
+    Caret,
+
     // Function delimiters
     /// Opening parenthesis: `(`
     /// Starts function calls and random effects
@@ -231,14 +261,34 @@ pub enum Token {
     #[token("log")]
     Log,
 
-    /// B-splines: `bs(x)`
+    /// B-splines: `bs(x, df = 5)`
     #[token("bs")]
     BSplines,
 
+    /// Natural cubic splines: `ns(x, df = 4)`
+    #[token("ns")]
+    NaturalSplines,
+
     /// Gaussian process: `gp(x)`
     #[token("gp")]
     GaussianProcess,
 
+    /// Smooth term: `s(x, k = 10, bs = "cr")`
+    #[token("s", priority = 3)]
+    Smooth,
+
+    /// Tensor product smooth: `te(x, z)`
+    #[token("te")]
+    TensorSmooth,
+
+    /// Tensor product interaction: `ti(x, z)`
+    #[token("ti")]
+    TensorInteraction,
+
+    /// Tensor product smooth in the t2 parametrization: `t2(x, z)`
+    #[token("t2")]
+    T2Smooth,
+
     /// Monotonic transformation: `mono(x)`
     #[token("mono")]
     Monotonic,
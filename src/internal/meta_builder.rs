@@ -20,7 +20,8 @@
 //! ## Key Features
 //!
 //! - **Variable-Centric Design**: Variables are the primary entities with comprehensive attributes
-//! - **ID Management**: Response variable always gets ID 1, others start from ID 2
+//! - **ID Management**: The response gets ID 1 (multivariate responses 1, 2, ...) and
+//!   other variables follow (or start from ID 1 in one-sided formulas such as `~ x + z`)
 //! - **Generated Columns**: Tracks all columns that will be created for the model
 //! - **Role Flexibility**: Variables can have multiple roles (e.g., both FixedEffect and RandomEffect)
 //! - **Transformation Support**: Handles complex transformations with parameter tracking
@@ -93,10 +94,13 @@
 //! ```
 
 use super::{
-    ast::{Argument, Grouping, RandomEffect, RandomTerm, Response},
+    ast::{
+        AdditionTerm, Argument, Autocorrelation, BinaryOperator, Expression, GrOption, Grouping, InteractionOperator,
+        RandomEffect, RandomTerm, Response,
+    },
     data_structures::{
-        FormulaMetadataInfo, Interaction, RandomEffectInfo, Transformation, VariableInfo,
-        VariableRole,
+        AutocorrelationMetaData, BinomialResponse, Censoring, FormulaMetadataInfo, Interaction, KnownMeasurementError, NonLinearMetaData,
+        RandomEffectInfo, ResponseInfo, Transformation, Truncation, VariableInfo, VariableRole,
     },
 };
 use std::collections::HashMap;
@@ -129,6 +133,12 @@ pub struct MetaBuilder {
     /// - `"group"` → `3` (grouping variable gets ID 3)
     name_to_id: HashMap<String, u32>,
 
+    /// Response variable names in formula order
+    ///
+    /// # Examples
+    /// - `["y1", "y2"]` for `bind(y1, y2) ~ x`
+    responses: Vec<String>,
+
     /// Maps variable names to their complete information
     ///
     /// Contains all variables with their roles, transformations,
@@ -151,9 +161,30 @@ pub struct MetaBuilder {
 
     /// The next available ID for new variables
     ///
-    /// Starts at 2 (since response gets ID 1) and increments
-    /// for each new variable added.
+    /// Starts at 1 and increments for each new variable added.
+    /// `push_response` moves it past the response IDs.
     next_id: u32,
+
+    /// Non-fatal problems collected while building the metadata
+    ///
+    /// # Examples
+    /// - `"term 'w' is not in the model and cannot be removed"` for `y ~ x - w`
+    warnings: Vec<String>,
+
+    /// The expression of a non-linear (`nl = TRUE`) formula, if any
+    nonlinear: Option<NonLinearMetaData>,
+
+    /// Response addition information from `y | trials(n) ~ x`, if any
+    response_info: Option<ResponseInfo>,
+
+    /// Autocorrelation structures such as `ar(time, g)`, in formula order
+    autocorrelation: Vec<AutocorrelationMetaData>,
+
+    /// The `I()` terms met in interactions, by generated column
+    ///
+    /// # Examples
+    /// - `"I(x^2)"` → `x^2` for `y ~ I(x^2):z`
+    as_is_factors: HashMap<String, Expression>,
 }
 
 impl MetaBuilder {
@@ -161,7 +192,8 @@ impl MetaBuilder {
     ///
     /// Initializes the builder with empty collections and default values.
     /// The next_id starts at 1, but the response variable will be assigned ID 1,
-    /// so other variables will start from ID 2.
+    /// so other variables will start from ID 2. In one-sided formulas without a
+    /// response, the first variable gets ID 1.
     ///
     /// # Examples
     ///
@@ -173,10 +205,16 @@ impl MetaBuilder {
     pub fn new() -> Self {
         Self {
             name_to_id: HashMap::new(),
+            responses: Vec::new(),
             columns: HashMap::new(),
             has_uncorrelated_slopes_and_intercepts: false,
             is_random_effects_model: false,
             next_id: 1,
+            warnings: Vec::new(),
+            nonlinear: None,
+            response_info: None,
+            autocorrelation: Vec::new(),
+            as_is_factors: HashMap::new(),
         }
     }
 
@@ -234,21 +272,40 @@ impl MetaBuilder {
     /// Adds a transformation to a variable
     pub fn add_transformation(&mut self, name: &str, transformation: Transformation) {
         if let Some(var_info) = self.columns.get_mut(name) {
-            var_info.transformations.push(transformation.clone());
+            var_info.transformations.push(transformation);
 
             // If the variable has an Identity role, preserve the original variable name
-            // and add the transformation's generated columns
+            // and add the generated columns of every transformation
+            let mut new_columns = Vec::new();
             if var_info.roles.contains(&VariableRole::Identity) {
-                let mut new_columns = vec![name.to_string()]; // Keep the original variable name
-                new_columns.extend(transformation.generates_columns);
-                var_info.generated_columns = new_columns;
-            } else {
-                // Update generated columns with the transformation's generated columns
-                var_info.generated_columns = transformation.generates_columns;
+                new_columns.push(name.to_string()); // Keep the original variable name
             }
+            for column in Self::transformation_columns(&var_info.transformations) {
+                if !new_columns.contains(&column) {
+                    new_columns.push(column);
+                }
+            }
+            var_info.generated_columns = new_columns;
         }
     }
 
+    /// Collects the model columns generated by a list of transformations
+    ///
+    /// Steps of a nested call chain that only feed the next step, such as the
+    /// `log` in `offset(log(x))`, generate no model columns.
+    fn transformation_columns(transformations: &[Transformation]) -> Vec<String> {
+        transformations
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                transformations
+                    .get(i + 1)
+                    .is_none_or(|next| next.input.is_none())
+            })
+            .flat_map(|(_, t)| t.generates_columns.iter().cloned())
+            .collect()
+    }
+
     /// Adds an interaction to a variable
     pub fn add_interaction(&mut self, name: &str, interaction: Interaction) {
         if let Some(var_info) = self.columns.get_mut(name) {
@@ -265,9 +322,14 @@ impl MetaBuilder {
 
     /// Adds response variable(s) to the model
     ///
-    /// For single responses, the variable gets ID 1. For multivariate responses,
-    /// all response variables get ID 1 and are treated as a single response unit.
-    /// All other variables will be assigned IDs starting from 2.
+    /// For single responses, the variable gets ID 1. Multivariate responses get
+    /// consecutive IDs in formula order (`y1` is 1, `y2` is 2), and each further
+    /// call adds its response after those already present, so the responses of
+    /// `bf(y1 ~ x) + bf(y2 ~ z)` can share one builder.
+    /// A transformed response such as `log(y)` records the transformation on `y`
+    /// and generates `y_log`, and both columns of `cbind(successes, failures)`
+    /// share one ID and are described in the `response` section.
+    /// All other variables are assigned IDs after the responses.
     ///
     /// # Arguments
     ///
@@ -286,52 +348,250 @@ impl MetaBuilder {
     ///
     /// // Multivariate response
     /// builder.push_response(&Response::Multivariate(vec!["y1".to_string(), "y2".to_string()]));
+    ///
+    /// // Binomial response: cbind(succ, fail)
+    /// builder.push_response(&Response::Binomial {
+    ///     successes: "succ".to_string(),
+    ///     failures: "fail".to_string(),
+    /// });
     /// ```
     pub fn push_response(&mut self, response: &Response) {
+        // Each response takes the next ID after those already in the model
+        let id = self
+            .responses
+            .iter()
+            .filter_map(|name| self.name_to_id.get(name))
+            .max()
+            .map_or(1, |last| last + 1);
         match response {
             Response::Single(name) => {
-                // Single response variable gets ID 1
-                if !self.name_to_id.contains_key(name) {
-                    self.name_to_id.insert(name.to_string(), 1);
-                    self.columns.insert(
-                        name.to_string(),
-                        VariableInfo {
-                            id: 1,
-                            roles: vec![VariableRole::Response],
-                            transformations: Vec::new(),
-                            interactions: Vec::new(),
-                            random_effects: Vec::new(),
-                            generated_columns: vec![name.to_string()],
-                        },
-                    );
-                    self.next_id = 2; // Start other variables from ID 2
-                } else {
-                    self.add_role(name, VariableRole::Response);
-                }
+                self.add_response_variable(name, id);
             }
             Response::Multivariate(variables) => {
-                // All multivariate response variables get ID 1
-                for name in variables {
-                    if !self.name_to_id.contains_key(name) {
-                        self.name_to_id.insert(name.to_string(), 1);
-                        self.columns.insert(
-                            name.to_string(),
-                            VariableInfo {
-                                id: 1,
-                                roles: vec![VariableRole::Response],
-                                transformations: Vec::new(),
-                                interactions: Vec::new(),
-                                random_effects: Vec::new(),
-                                generated_columns: vec![name.to_string()],
-                            },
-                        );
-                    } else {
-                        self.add_role(name, VariableRole::Response);
+                // Multivariate response variables get consecutive IDs in formula order
+                for (offset, name) in variables.iter().enumerate() {
+                    self.add_response_variable(name, id + offset as u32);
+                }
+            }
+            Response::Function { name: fname, args } => {
+                // log(y): the response y gets the ID and generates y_log
+                if let Some(base_col) = Self::base_variable(args) {
+                    self.add_response_variable(base_col, id);
+                    for transformation in self.transformation_chain(fname, args) {
+                        self.add_transformation(base_col, transformation);
+                    }
+                }
+            }
+            Response::AsIs(expression) => {
+                // I(y / 2): the response y gets the ID and generates I(y / 2)
+                if let Some(base_col) = Self::expression_variables(expression).first() {
+                    self.add_response_variable(base_col, id);
+                    let transformation = Transformation {
+                        function: "I".to_string(),
+                        parameters: serde_json::json!({
+                            "expression": Self::expression_to_json(expression),
+                            "variables": Self::expression_variables(expression),
+                        }),
+                        generates_columns: vec![Self::as_is_column(expression)],
+                        input: None,
+                    };
+                    self.add_transformation(base_col, transformation);
+                }
+            }
+            Response::Binomial {
+                successes,
+                failures,
+            } => {
+                // Both columns of cbind(successes, failures) form one response with one ID
+                self.add_response_variable(successes, id);
+                self.add_response_variable(failures, id);
+                self.response_info = Some(ResponseInfo {
+                    name: successes.clone(),
+                    binomial: Some(BinomialResponse {
+                        successes: successes.clone(),
+                        failures: failures.clone(),
+                    }),
+                    ..Default::default()
+                });
+            }
+        }
+        // Start other variables after the responses
+        self.next_id = self
+            .responses
+            .iter()
+            .filter_map(|name| self.name_to_id.get(name))
+            .max()
+            .map_or(1, |last| last + 1);
+    }
+
+    /// Adds a response column with the given ID
+    fn add_response_variable(&mut self, name: &str, id: u32) {
+        self.responses.push(name.to_string());
+        if !self.name_to_id.contains_key(name) {
+            self.name_to_id.insert(name.to_string(), id);
+            self.columns.insert(
+                name.to_string(),
+                VariableInfo {
+                    id,
+                    roles: vec![VariableRole::Response],
+                    transformations: Vec::new(),
+                    interactions: Vec::new(),
+                    random_effects: Vec::new(),
+                    generated_columns: vec![name.to_string()],
+                },
+            );
+        } else {
+            self.add_role(name, VariableRole::Response);
+        }
+    }
+
+    /// Adds the response addition terms of `y | trials(n) + weights(w) ~ x`
+    ///
+    /// The terms are collected into the `response` section of the metadata, and
+    /// every column they read gets the `ResponseAddition` role. Multivariate
+    /// responses take no addition terms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fiasto::internal::meta_builder::MetaBuilder;
+    /// use fiasto::internal::ast::{AdditionTerm, Argument, Response};
+    ///
+    /// let mut builder = MetaBuilder::new();
+    /// let response = Response::Single("y".to_string());
+    /// builder.push_response(&response);
+    /// builder.push_response_additions(&response, &[AdditionTerm::Trials(Argument::Ident("n".to_string()))]);
+    ///
+    /// let metadata = builder.build("y | trials(n) ~ 1", true, None);
+    /// assert_eq!(metadata.response.unwrap().trials, Some(serde_json::json!("n")));
+    /// assert_eq!(metadata.all_generated_columns, vec!["y", "intercept", "n"]);
+    /// ```
+    pub fn push_response_additions(&mut self, response: &Response, additions: &[AdditionTerm]) {
+        let name = match response {
+            Response::Single(name) => name.clone(),
+            Response::Function { args, .. } => Self::base_variable(args).unwrap_or_default().to_string(),
+            Response::Binomial { successes, .. } => successes.clone(),
+            Response::AsIs(expression) => Self::expression_variables(expression)
+                .into_iter()
+                .next()
+                .unwrap_or_default(),
+            Response::Multivariate(_) => return,
+        };
+        let mut info = self.response_info.take().unwrap_or_else(|| ResponseInfo {
+            name,
+            ..Default::default()
+        });
+        let mut columns: Vec<&str> = Vec::new();
+
+        for addition in additions {
+            match addition {
+                AdditionTerm::Trials(value) => {
+                    if let Argument::Ident(name) = value {
+                        columns.push(name);
                     }
+                    info.trials = Some(Self::argument_to_json(value));
+                }
+                AdditionTerm::Censored { variable, upper } => {
+                    columns.push(variable);
+                    columns.extend(upper.as_deref());
+                    info.censoring = Some(Censoring {
+                        variable: variable.clone(),
+                        upper_bound_var: upper.clone(),
+                    });
+                }
+                AdditionTerm::Weights { variable, scale } => {
+                    columns.push(variable);
+                    info.weights = Some(variable.clone());
+                    info.scale_weights = *scale;
+                }
+                AdditionTerm::Se { variable, sigma } => {
+                    columns.push(variable);
+                    info.known_meas_error = Some(KnownMeasurementError {
+                        variable: variable.clone(),
+                        include_residual_sigma: *sigma,
+                    });
+                }
+                AdditionTerm::Trunc { lower, upper } => {
+                    for bound in lower.iter().chain(upper.iter()) {
+                        if let Argument::Ident(name) = bound {
+                            columns.push(name);
+                        }
+                    }
+                    info.truncation = Some(Truncation {
+                        lower: lower.as_ref().map(Self::argument_to_json),
+                        upper: upper.as_ref().map(Self::argument_to_json),
+                    });
+                }
+                AdditionTerm::Mi(sd) => {
+                    columns.extend(sd.as_deref());
+                    info.mi = true;
+                    info.mi_sd = sd.clone();
+                }
+                AdditionTerm::Dec(variable) => {
+                    columns.push(variable);
+                    info.decisions = Some(variable.clone());
+                }
+                AdditionTerm::Rate(variable) => {
+                    columns.push(variable);
+                    info.rate = Some(variable.clone());
                 }
-                self.next_id = 2; // Start other variables from ID 2
             }
         }
+
+        for name in columns {
+            self.ensure_variable(name);
+            self.add_role(name, VariableRole::ResponseAddition);
+        }
+        self.response_info = Some(info);
+    }
+
+    /// Adds an autocorrelation structure such as `ar(time, g)`
+    ///
+    /// The structure is described under `autocorrelation`, and its time and
+    /// grouping variables get columns with the `Autocorrelation` role. The
+    /// matrices of `car()` and `sar()` are not data columns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fiasto::internal::meta_builder::MetaBuilder;
+    /// use fiasto::internal::ast::Autocorrelation;
+    ///
+    /// let mut builder = MetaBuilder::new();
+    /// builder.push_autocorrelation(&Autocorrelation::Cosy {
+    ///     time: None,
+    ///     gr: Some("g".to_string()),
+    /// });
+    /// let metadata = builder.build("y ~ cosy(gr = g)", true, None);
+    /// assert_eq!(metadata.autocorrelation[0].term, "cosy(time = NA, gr = g)");
+    /// assert_eq!(metadata.all_generated_columns, vec!["intercept", "g"]);
+    /// ```
+    pub fn push_autocorrelation(&mut self, autocorrelation: &Autocorrelation) {
+        let (variables, parameters) = match autocorrelation {
+            Autocorrelation::Arma { time, gr, p, q, cov } => (
+                vec![time, gr],
+                serde_json::json!({"time": time, "gr": gr, "p": p, "q": q, "cov": cov}),
+            ),
+            Autocorrelation::Cosy { time, gr } => {
+                (vec![time, gr], serde_json::json!({"time": time, "gr": gr}))
+            }
+            Autocorrelation::Car { matrix, gr, kind } => (
+                vec![gr],
+                serde_json::json!({"matrix": matrix, "gr": gr, "type": kind}),
+            ),
+            Autocorrelation::Sar { matrix, kind } => {
+                (vec![], serde_json::json!({"matrix": matrix, "type": kind}))
+            }
+        };
+        for name in variables.into_iter().flatten() {
+            self.ensure_variable(name);
+            self.add_role(name, VariableRole::Autocorrelation);
+        }
+        self.autocorrelation.push(AutocorrelationMetaData {
+            structure: autocorrelation.name().to_string(),
+            term: autocorrelation.term(),
+            parameters,
+        });
     }
 
     /// Adds a plain variable term (identity transformation)
@@ -356,6 +616,26 @@ impl MetaBuilder {
     pub fn push_plain_term(&mut self, name: &str) {
         self.ensure_variable(name);
         self.add_role(name, VariableRole::Identity);
+        self.add_main_effect_column(name);
+    }
+
+    /// Checks whether a variable already in the model generates its own column
+    fn has_main_effect_column(&self, name: &str) -> bool {
+        self.columns
+            .get(name)
+            .is_some_and(|v| v.generated_columns.iter().any(|col| col == name))
+    }
+
+    /// Makes sure a variable used as a main effect generates its own column
+    ///
+    /// A variable first seen in a `:` interaction has no column of its own,
+    /// so a later main effect (`x:z + x`) has to add it back.
+    fn add_main_effect_column(&mut self, name: &str) {
+        if let Some(var_info) = self.columns.get_mut(name) {
+            if !var_info.generated_columns.iter().any(|col| col == name) {
+                var_info.generated_columns.insert(0, name.to_string());
+            }
+        }
     }
 
     /// Extracts all variable names from a potentially nested interaction term
@@ -363,60 +643,222 @@ impl MetaBuilder {
         match term {
             crate::internal::ast::Term::Column(name) => vec![name.clone()],
             crate::internal::ast::Term::Function { args, .. } => {
-                // For functions, extract the first argument if it's an identifier
-                args.iter()
-                    .find_map(|arg| match arg {
-                        Argument::Ident(s) => Some(s.clone()),
-                        _ => None,
-                    })
+                // For functions, extract the variable the call is applied to
+                Self::base_variable(args)
+                    .map(str::to_string)
                     .into_iter()
                     .collect()
             }
-            crate::internal::ast::Term::Interaction { left, right } => {
+            crate::internal::ast::Term::Interaction { left, right, .. } => {
                 let mut vars = Self::extract_all_variables(left);
                 vars.extend(Self::extract_all_variables(right));
                 vars
             }
+            crate::internal::ast::Term::Group(terms) => {
+                terms.iter().flat_map(Self::extract_all_variables).collect()
+            }
+            crate::internal::ast::Term::Power { base, .. } => Self::extract_all_variables(base),
+            crate::internal::ast::Term::AsIs(expression) => {
+                // An `I()` term is represented by its generated column, so that
+                // `I(x^2):z` gives `I(x^2)_z`
+                vec![Self::as_is_column(expression)]
+            }
             _ => vec![],
         }
     }
 
-    /// Generates all possible interaction combinations from a list of variables
-    fn generate_interaction_combinations(variables: &[String]) -> Vec<Vec<String>> {
+    /// Records the `I()` terms of an interaction so that their columns can be
+    /// traced back to their expressions
+    fn record_as_is_factors(&mut self, term: &crate::internal::ast::Term) {
+        match term {
+            crate::internal::ast::Term::AsIs(expression) => {
+                self.as_is_factors
+                    .insert(Self::as_is_column(expression), expression.clone());
+            }
+            crate::internal::ast::Term::Interaction { left, right, .. } => {
+                self.record_as_is_factors(left);
+                self.record_as_is_factors(right);
+            }
+            crate::internal::ast::Term::Group(terms) => {
+                for term in terms {
+                    self.record_as_is_factors(term);
+                }
+            }
+            crate::internal::ast::Term::Power { base, .. } => self.record_as_is_factors(base),
+            _ => {}
+        }
+    }
+
+    /// The variables behind a factor of an interaction: the variables of an
+    /// `I()` term, or the factor itself
+    fn factor_variables(&self, factor: &str) -> Vec<String> {
+        match self.as_is_factors.get(factor) {
+            Some(expression) => Self::expression_variables(expression),
+            None => vec![factor.to_string()],
+        }
+    }
+
+    /// Expands a fixed-effects term into the factor sets it contributes
+    ///
+    /// Each inner vector is one model term: a single variable is a main effect
+    /// and several variables form an interaction. Groups contribute each of
+    /// their members and interactions distribute over groups, so `(a + b) * c`
+    /// expands to `[a], [b], [c], [a, c], [b, c]` and `(a + b):c` expands to
+    /// `[a, c], [b, c]`. A removal inside a group drops the sets it expands to
+    /// from the members before it, so `(a + b - a):c` expands to `[b, c]`.
+    fn expand_term(term: &crate::internal::ast::Term) -> Vec<Vec<String>> {
+        match term {
+            crate::internal::ast::Term::Interaction {
+                left,
+                right,
+                operator,
+            } => Self::combine(
+                &Self::expand_term(left),
+                &Self::expand_term(right),
+                *operator,
+            ),
+            crate::internal::ast::Term::Group(terms) => {
+                let mut sets: Vec<Vec<String>> = Vec::new();
+                for term in terms {
+                    if let crate::internal::ast::Term::Removal(removed) = term {
+                        let removed = Self::expand_term(removed);
+                        sets.retain(|set| {
+                            !removed.iter().any(|r| {
+                                r.len() == set.len() && r.iter().all(|v| set.contains(v))
+                            })
+                        });
+                    } else {
+                        Self::extend_unique(&mut sets, Self::expand_term(term));
+                    }
+                }
+                sets
+            }
+            crate::internal::ast::Term::Power { base, order } => {
+                Self::generate_interaction_combinations(&Self::expand_term(base), *order as usize)
+            }
+            _ => Self::extract_all_variables(term)
+                .into_iter()
+                .map(|var| vec![var])
+                .collect(),
+        }
+    }
+
+    /// Generates every product of at most `max_order` of the given terms
+    ///
+    /// This is the expansion behind the `^` operator: with the terms
+    /// `[a], [b], [c]` and a `max_order` of 2 it produces the main effects and
+    /// the two-way interactions, but not `a:b:c`. As in R, an order above the
+    /// number of terms gives the same expansion as the number of terms.
+    fn generate_interaction_combinations(
+        terms: &[Vec<String>],
+        max_order: usize,
+    ) -> Vec<Vec<String>> {
+        let max_order = max_order.min(terms.len());
         let mut combinations = Vec::new();
-        let n = variables.len();
+        let mut products: Vec<Vec<String>> = vec![Vec::new()];
 
-        // Generate all combinations from order 2 up to n
-        for order in 2..=n {
-            combinations.extend(Self::combinations(variables, order));
+        for _ in 0..max_order {
+            products = Self::cross_products(&products, terms);
+            Self::extend_unique(&mut combinations, products.clone());
         }
 
         combinations
     }
 
-    /// Helper function to generate combinations of a given size
-    fn combinations(variables: &[String], k: usize) -> Vec<Vec<String>> {
-        if k == 0 {
-            return vec![vec![]];
-        }
-        if variables.is_empty() {
-            return vec![];
+    /// Multiplies every set on the left with every set on the right
+    fn cross_products(left: &[Vec<String>], right: &[Vec<String>]) -> Vec<Vec<String>> {
+        let mut products = Vec::new();
+        for l in left {
+            for r in right {
+                let mut product = l.clone();
+                for var in r {
+                    if !product.contains(var) {
+                        product.push(var.clone());
+                    }
+                }
+                Self::extend_unique(&mut products, vec![product]);
+            }
         }
+        products
+    }
 
-        let mut result = Vec::new();
-        let first = &variables[0];
-        let rest = &variables[1..];
+    /// Combines two expanded terms with an interaction operator
+    fn combine(
+        left: &[Vec<String>],
+        right: &[Vec<String>],
+        operator: InteractionOperator,
+    ) -> Vec<Vec<String>> {
+        match operator {
+            InteractionOperator::InteractionAndEffect => Self::cross(left, right),
+            InteractionOperator::InteractionOnly | InteractionOperator::NestedWithin => {
+                Self::cross_products(left, right)
+            }
+            InteractionOperator::Nesting => Self::nest(left, right),
+        }
+    }
 
-        // Include first element
-        for mut combo in Self::combinations(rest, k - 1) {
-            combo.insert(0, first.clone());
-            result.push(combo);
+    /// Nests one expanded term within another: `A / B = A + all(A):B`
+    ///
+    /// Every term on the right interacts with all variables of the left-hand
+    /// side together, so `(a + b)/c` gives `a + b + a:b:c`.
+    fn nest(outer: &[Vec<String>], inner: &[Vec<String>]) -> Vec<Vec<String>> {
+        let mut all_outer = Vec::new();
+        for var in outer.iter().flatten() {
+            if !all_outer.contains(var) {
+                all_outer.push(var.clone());
+            }
         }
 
-        // Exclude first element
-        result.extend(Self::combinations(rest, k));
+        let mut sets = outer.to_vec();
+        Self::extend_unique(&mut sets, Self::cross_products(&[all_outer], inner));
+        sets
+    }
 
-        result
+    /// Crosses two expanded terms: `A * B = A + B + A:B`
+    fn cross(left: &[Vec<String>], right: &[Vec<String>]) -> Vec<Vec<String>> {
+        let mut sets = left.to_vec();
+        Self::extend_unique(&mut sets, right.to_vec());
+        Self::extend_unique(&mut sets, Self::cross_products(left, right));
+        sets
+    }
+
+    /// Appends factor sets that are not already present (ignoring variable order)
+    fn extend_unique(sets: &mut Vec<Vec<String>>, new_sets: Vec<Vec<String>>) {
+        for set in new_sets {
+            let exists = sets
+                .iter()
+                .any(|s| s.len() == set.len() && s.iter().all(|v| set.contains(v)));
+            if !exists {
+                sets.push(set);
+            }
+        }
+    }
+
+    /// Adds each member of a parenthesised group such as `(a + b)`
+    pub fn push_group(&mut self, terms: &[crate::internal::ast::Term]) {
+        for term in terms {
+            match term {
+                crate::internal::ast::Term::Column(name) => self.push_plain_term(name),
+                crate::internal::ast::Term::Function { name, args } => {
+                    self.push_function_term(name, args)
+                }
+                crate::internal::ast::Term::Interaction {
+                    left,
+                    right,
+                    operator,
+                } => self.push_interaction(left, right, *operator),
+                crate::internal::ast::Term::RandomEffect(random_effect) => {
+                    self.push_random_effect(random_effect)
+                }
+                crate::internal::ast::Term::Group(terms) => self.push_group(terms),
+                crate::internal::ast::Term::Power { base, order } => {
+                    self.push_power(base, *order)
+                }
+                crate::internal::ast::Term::AsIs(expression) => self.push_as_is_term(expression),
+                crate::internal::ast::Term::Removal(term) => self.remove_term(term),
+                _ => {}
+            }
+        }
     }
 
     /// Creates an interaction variable name from a list of variables
@@ -425,20 +867,83 @@ impl MetaBuilder {
     }
 
     /// Adds an interaction term (completely rewritten for multi-way interactions)
+    ///
+    /// `*` adds the main effects of both sides together with their
+    /// interactions, while `:` adds only the interaction columns. Variables that
+    /// only take part in a `:` interaction are still described in `columns` but
+    /// generate no column of their own and have no `FixedEffect` role.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fiasto::internal::meta_builder::MetaBuilder;
+    /// use fiasto::internal::ast::{InteractionOperator, Term};
+    ///
+    /// let mut builder = MetaBuilder::new();
+    /// let x = Term::Column("x".to_string());
+    /// let z = Term::Column("z".to_string());
+    /// builder.push_interaction(&x, &z, InteractionOperator::InteractionOnly);
+    /// let metadata = builder.build("~ x:z", false, None);
+    /// assert_eq!(metadata.all_generated_columns, vec!["x_z"]);
+    /// ```
     pub fn push_interaction(
         &mut self,
         left: &crate::internal::ast::Term,
         right: &crate::internal::ast::Term,
+        operator: InteractionOperator,
     ) {
-        // Extract all variables from the interaction
-        let mut all_variables = Self::extract_all_variables(left);
-        all_variables.extend(Self::extract_all_variables(right));
+        self.record_as_is_factors(left);
+        self.record_as_is_factors(right);
+        let mut variables = Self::extract_all_variables(left);
+        variables.extend(Self::extract_all_variables(right));
+        let expanded = Self::combine(
+            &Self::expand_term(left),
+            &Self::expand_term(right),
+            operator,
+        );
+        self.push_expanded_terms(&variables, expanded);
+    }
 
-        // Remove duplicates while preserving order
+    /// Adds a term raised to a power such as `(a + b + c)^2`
+    ///
+    /// All main effects of the base are added together with the interactions
+    /// of up to `order` of its members.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fiasto::internal::meta_builder::MetaBuilder;
+    /// use fiasto::internal::ast::Term;
+    ///
+    /// let mut builder = MetaBuilder::new();
+    /// let base = Term::Group(vec![
+    ///     Term::Column("a".to_string()),
+    ///     Term::Column("b".to_string()),
+    ///     Term::Column("c".to_string()),
+    /// ]);
+    /// builder.push_power(&base, 2);
+    /// let metadata = builder.build("~ (a + b + c)^2", false, None);
+    /// assert!(metadata.columns.contains_key("a_b"));
+    /// assert!(!metadata.columns.contains_key("a_b_c"));
+    /// ```
+    pub fn push_power(&mut self, base: &crate::internal::ast::Term, order: u32) {
+        self.record_as_is_factors(base);
+        let variables = Self::extract_all_variables(base);
+        let expanded = Self::generate_interaction_combinations(
+            &Self::expand_term(base),
+            order as usize,
+        );
+        self.push_expanded_terms(&variables, expanded);
+    }
+
+    /// Adds the main effects and interactions produced by expanding a term
+    fn push_expanded_terms(&mut self, variables: &[String], mut expanded: Vec<Vec<String>>) {
+        // Remove duplicates while preserving order; a variable that was removed
+        // again, like `a` in `(a + b - a):c`, is left out
         let mut unique_variables = Vec::new();
-        for var in all_variables {
-            if !unique_variables.contains(&var) {
-                unique_variables.push(var);
+        for var in variables {
+            if !unique_variables.contains(var) && expanded.iter().any(|set| set.contains(var)) {
+                unique_variables.push(var.clone());
             }
         }
 
@@ -446,14 +951,39 @@ impl MetaBuilder {
             return;
         }
 
-        // Ensure all main effect variables exist and have FixedEffect role
-        for var in &unique_variables {
-            self.ensure_variable(var);
-            self.add_role(var, VariableRole::FixedEffect);
+        // Order the variables of each term and the terms themselves as in the formula
+        let position = |var: &String| unique_variables.iter().position(|v| v == var);
+        for set in &mut expanded {
+            set.sort_by_key(position);
         }
+        expanded.sort_by_key(|set| (set.len(), set.iter().map(position).collect::<Vec<_>>()));
 
-        // Generate all interaction combinations (2-way, 3-way, etc.)
-        let interaction_combinations = Self::generate_interaction_combinations(&unique_variables);
+        let (main_effects, interaction_combinations): (Vec<_>, Vec<_>) =
+            expanded.into_iter().partition(|set| set.len() == 1);
+
+        // Ensure all variables exist in formula order; only main effects get the
+        // FixedEffect role and a column of their own
+        for var in &unique_variables {
+            let is_main_effect = main_effects.iter().any(|set| set[0] == *var);
+            if let Some(expression) = self.as_is_factors.get(var).cloned() {
+                if is_main_effect {
+                    self.push_as_is_term(&expression);
+                    continue;
+                }
+            }
+            for var in self.factor_variables(var) {
+                let is_new = !self.name_to_id.contains_key(&var);
+                self.ensure_variable(&var);
+                if is_main_effect {
+                    self.add_role(&var, VariableRole::FixedEffect);
+                    self.add_main_effect_column(&var);
+                } else if is_new {
+                    if let Some(var_info) = self.columns.get_mut(&var) {
+                        var_info.generated_columns.clear();
+                    }
+                }
+            }
+        }
 
         for combo in interaction_combinations {
             let interaction_name = Self::create_interaction_name(&combo);
@@ -473,46 +1003,364 @@ impl MetaBuilder {
                     .map(|(_, v)| v.clone())
                     .collect();
 
-                let interaction = Interaction {
-                    with: other_vars,
-                    order,
-                    context: "fixed_effects".to_string(),
-                    grouping_variable: None,
-                };
-                self.add_interaction(var, interaction);
+                // The variables of an `I()` term all take part in its interactions
+                for target in self.factor_variables(var) {
+                    let interaction = Interaction {
+                        with: other_vars.clone(),
+                        order,
+                        context: "fixed_effects".to_string(),
+                        grouping_variable: None,
+                    };
+                    self.add_interaction(&target, interaction);
+                }
             }
         }
     }
 
     /// Adds a function/transformation term
     pub fn push_function_term(&mut self, fname: &str, args: &[Argument]) {
+        let is_nested = args.iter().any(|a| matches!(a, Argument::Function { .. }));
+
         // Special handling for categorical functions
-        if fname == "c" || fname == "factor" {
+        if (fname == "c" || fname == "factor") && !is_nested {
             self.push_categorical_term_with_name(fname, args);
             return;
         }
 
-        let base_ident = args.iter().find_map(|a| match a {
+        if let Some(base_col) = Self::base_variable(args) {
+            let is_main_effect = self.has_main_effect_column(base_col);
+            self.ensure_variable(base_col);
+            // Add FixedEffect role for the transformed version
+            self.add_role(base_col, VariableRole::FixedEffect);
+
+            // Nested calls such as offset(log(x)) add one transformation per
+            // call, innermost first
+            for transformation in self.transformation_chain(fname, args) {
+                self.add_transformation(base_col, transformation);
+            }
+            // A main effect from `x*z` keeps its column next to `poly(x, 2)`
+            if is_main_effect {
+                self.add_main_effect_column(base_col);
+            }
+
+            // The other variables of a smooth (`z` and `g` in `s(x, z, by = g)`)
+            // are read by the transformation of `x`
+            for covariate in Self::smooth_covariates(fname, args) {
+                if covariate != base_col {
+                    self.push_covariate(covariate);
+                }
+            }
+        }
+    }
+
+    /// Registers a variable that a term of another variable reads
+    ///
+    /// The variable gets the `FixedEffect` role but no column of its own, since
+    /// its values only enter the model through the term that reads it. A
+    /// variable that already generates a column keeps it.
+    fn push_covariate(&mut self, name: &str) {
+        let is_new = !self.name_to_id.contains_key(name);
+        self.ensure_variable(name);
+        if is_new {
+            if let Some(var_info) = self.columns.get_mut(name) {
+                var_info.generated_columns.clear();
+            }
+        }
+        self.add_role(name, VariableRole::FixedEffect);
+    }
+
+    /// Finds the variable a function call is applied to
+    ///
+    /// This is the first identifier argument, looking inside nested calls, so
+    /// both `log(x)` and `offset(log(x))` are applied to `x`.
+    fn base_variable(args: &[Argument]) -> Option<&str> {
+        args.iter().find_map(|a| match a {
             Argument::Ident(s) => Some(s.as_str()),
+            Argument::Function { args, .. } => Self::base_variable(args),
             _ => None,
+        })
+    }
+
+    /// Builds the transformations for a possibly nested function call, innermost first
+    ///
+    /// Each step is applied to the column generated by the step before it, and
+    /// the generated column names are composed along the chain:
+    /// `offset(log(exposure))` gives `log` → `exposure_log`, then `offset` →
+    /// `exposure_log_offset`. A step that generates several columns passes on
+    /// `<base>_<function>`, e.g. `x_poly` for `log(poly(x, 2))`.
+    fn transformation_chain(&self, fname: &str, args: &[Argument]) -> Vec<Transformation> {
+        let mut chain = Vec::new();
+        let mut args = args.to_vec();
+        let mut input = None;
+
+        let nested = args.iter().position(|a| matches!(a, Argument::Function { .. }));
+        if let Some(index) = nested {
+            if let Argument::Function {
+                name,
+                args: inner_args,
+            } = &args[index]
+            {
+                chain = self.transformation_chain(name, inner_args);
+                let inner = chain.last().unwrap();
+                let column = match inner.generates_columns.as_slice() {
+                    [column] => column.clone(),
+                    _ => format!(
+                        "{}_{}",
+                        Self::base_variable(inner_args).unwrap_or("unknown"),
+                        name
+                    ),
+                };
+                input = Some(column);
+            }
+            // The outer call sees the inner result as a plain column
+            args[index] = Argument::Ident(input.clone().unwrap());
+        }
+
+        chain.push(Transformation {
+            function: fname.to_string(),
+            parameters: self.extract_function_parameters(fname, &args),
+            generates_columns: self.generate_transformation_columns(fname, &args),
+            input,
         });
+        chain
+    }
 
-        if let Some(base_col) = base_ident {
-            self.ensure_variable(base_col);
-            // Add FixedEffect role for the transformed version
-            self.add_role(base_col, VariableRole::FixedEffect);
+    /// Adds an arithmetic `I()` term such as `I(x^2)`
+    ///
+    /// The expression is recorded as a transformation of its first variable.
+    /// The transformation parameters hold the expression tree as JSON together
+    /// with every variable the expression reads, and the generated column is
+    /// named after the expression (`I(x^2)`). The other variables are
+    /// registered without columns of their own, and an expression that reads
+    /// no variable, such as `I(2)`, is ignored with a warning.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fiasto::internal::meta_builder::MetaBuilder;
+    /// use fiasto::internal::ast::{BinaryOperator, Expression};
+    ///
+    /// let mut builder = MetaBuilder::new();
+    /// builder.push_as_is_term(&Expression::Binary {
+    ///     operator: BinaryOperator::Power,
+    ///     left: Box::new(Expression::Column("x".to_string())),
+    ///     right: Box::new(Expression::Number(2.0)),
+    /// });
+    /// let metadata = builder.build("~ I(x^2)", false, None);
+    /// assert_eq!(metadata.all_generated_columns, vec!["I(x^2)"]);
+    /// ```
+    pub fn push_as_is_term(&mut self, expression: &Expression) {
+        let variables = Self::expression_variables(expression);
+        let Some(base_col) = variables.first() else {
+            self.warnings.push(format!(
+                "term '{}' does not use any variable and is ignored",
+                Self::as_is_column(expression)
+            ));
+            return;
+        };
 
-            // Create transformation info
-            let parameters = self.extract_function_parameters(fname, args);
-            let generates_columns = self.generate_transformation_columns(fname, args);
+        let is_main_effect = self.has_main_effect_column(base_col);
+        self.ensure_variable(base_col);
+        self.add_role(base_col, VariableRole::FixedEffect);
 
-            let transformation = Transformation {
-                function: fname.to_string(),
-                parameters,
-                generates_columns,
-            };
+        let parameters = serde_json::json!({
+            "expression": Self::expression_to_json(expression),
+            "variables": variables,
+        });
+        let transformation = Transformation {
+            function: "I".to_string(),
+            parameters,
+            generates_columns: vec![Self::as_is_column(expression)],
+            input: None,
+        };
+        self.add_transformation(base_col, transformation);
+        if is_main_effect {
+            self.add_main_effect_column(base_col);
+        }
+        for covariate in &variables[1..] {
+            self.push_covariate(covariate);
+        }
+    }
+
+    /// The column generated by an `I()` term, named after its expression
+    fn as_is_column(expression: &Expression) -> String {
+        format!("I({})", Self::render_expression(expression))
+    }
+
+    /// Adds the expression of a non-linear formula such as `a1 - a2^x`
+    ///
+    /// Names listed in `parameters` are non-linear parameters with their own
+    /// sub-formula; every other name in the expression is a covariate and gets
+    /// a column with the `NonLinearCovariate` role.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fiasto::internal::meta_builder::MetaBuilder;
+    /// use fiasto::internal::ast::{BinaryOperator, Expression};
+    ///
+    /// // a1 * x with the parameter a1
+    /// let mut builder = MetaBuilder::new();
+    /// builder.push_nonlinear_expression(
+    ///     &Expression::Binary {
+    ///         operator: BinaryOperator::Multiply,
+    ///         left: Box::new(Expression::Column("a1".to_string())),
+    ///         right: Box::new(Expression::Column("x".to_string())),
+    ///     },
+    ///     &["a1".to_string()],
+    /// );
+    /// let metadata = builder.build("y ~ a1 * x, a1 ~ 1, nl = TRUE", false, None);
+    /// let nonlinear = metadata.nonlinear.unwrap();
+    /// assert_eq!(nonlinear.parameters, vec!["a1"]);
+    /// assert_eq!(nonlinear.covariates, vec!["x"]);
+    /// assert_eq!(metadata.all_generated_columns, vec!["x"]);
+    /// ```
+    pub fn push_nonlinear_expression(&mut self, expression: &Expression, parameters: &[String]) {
+        let (parameters, covariates): (Vec<String>, Vec<String>) =
+            Self::expression_variables(expression)
+                .into_iter()
+                .partition(|name| parameters.contains(name));
+
+        for covariate in &covariates {
+            self.ensure_variable(covariate);
+            self.add_role(covariate, VariableRole::NonLinearCovariate);
+            self.add_main_effect_column(covariate);
+        }
+
+        self.nonlinear = Some(NonLinearMetaData {
+            expression: Self::expression_to_json(expression),
+            parameters,
+            covariates,
+        });
+    }
+
+    /// Collects the distinct column names used in an expression, in order
+    fn expression_variables(expression: &Expression) -> Vec<String> {
+        fn collect(expression: &Expression, variables: &mut Vec<String>) {
+            match expression {
+                Expression::Column(name) => {
+                    if !variables.contains(name) {
+                        variables.push(name.clone());
+                    }
+                }
+                Expression::Number(_) => {}
+                Expression::Negate(operand) => collect(operand, variables),
+                Expression::Binary { left, right, .. } => {
+                    collect(left, variables);
+                    collect(right, variables);
+                }
+                Expression::Call { args, .. } => {
+                    for arg in args {
+                        collect(arg, variables);
+                    }
+                }
+            }
+        }
+
+        let mut variables = Vec::new();
+        collect(expression, &mut variables);
+        variables
+    }
+
+    /// Converts an expression tree to JSON for the transformation parameters
+    ///
+    /// # Examples
+    /// - `x^2` → `{"type": "binary", "operator": "^", "left": {"type": "column", "name": "x"}, "right": {"type": "number", "value": 2.0}}`
+    fn expression_to_json(expression: &Expression) -> serde_json::Value {
+        match expression {
+            Expression::Column(name) => serde_json::json!({ "type": "column", "name": name }),
+            Expression::Number(value) => serde_json::json!({ "type": "number", "value": value }),
+            Expression::Negate(operand) => serde_json::json!({
+                "type": "negate",
+                "operand": Self::expression_to_json(operand),
+            }),
+            Expression::Binary {
+                operator,
+                left,
+                right,
+            } => serde_json::json!({
+                "type": "binary",
+                "operator": Self::operator_symbol(*operator),
+                "left": Self::expression_to_json(left),
+                "right": Self::expression_to_json(right),
+            }),
+            Expression::Call { name, args } => serde_json::json!({
+                "type": "call",
+                "function": name,
+                "args": args.iter().map(Self::expression_to_json).collect::<Vec<_>>(),
+            }),
+        }
+    }
+
+    /// Returns the symbol of an arithmetic operator
+    fn operator_symbol(operator: BinaryOperator) -> &'static str {
+        match operator {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Power => "^",
+        }
+    }
+
+    /// Renders an expression back to R syntax, adding only the parentheses it needs
+    ///
+    /// # Examples
+    /// - `x^2` → `"x^2"`
+    /// - `(x + z) / 2` → `"(x + z) / 2"`
+    fn render_expression(expression: &Expression) -> String {
+        fn precedence(expression: &Expression) -> u8 {
+            match expression {
+                Expression::Binary { operator, .. } => match operator {
+                    BinaryOperator::Add | BinaryOperator::Subtract => 1,
+                    BinaryOperator::Multiply | BinaryOperator::Divide => 2,
+                    BinaryOperator::Power => 4,
+                },
+                Expression::Negate(_) => 3,
+                _ => 5,
+            }
+        }
+        fn wrap(expression: &Expression, needs_parens: bool) -> String {
+            let rendered = MetaBuilder::render_expression(expression);
+            if needs_parens {
+                format!("({})", rendered)
+            } else {
+                rendered
+            }
+        }
 
-            self.add_transformation(base_col, transformation);
+        match expression {
+            Expression::Column(name) => name.clone(),
+            Expression::Number(value) => value.to_string(),
+            Expression::Negate(operand) => {
+                format!("-{}", wrap(operand, precedence(operand) < 3))
+            }
+            Expression::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let own = precedence(expression);
+                if *operator == BinaryOperator::Power {
+                    // Right-associative: only the left side needs parentheses at equal precedence
+                    format!(
+                        "{}^{}",
+                        wrap(left, precedence(left) <= own),
+                        wrap(right, precedence(right) < 3)
+                    )
+                } else {
+                    format!(
+                        "{} {} {}",
+                        wrap(left, precedence(left) < own),
+                        Self::operator_symbol(*operator),
+                        wrap(right, precedence(right) <= own)
+                    )
+                }
+            }
+            Expression::Call { name, args } => {
+                let args: Vec<String> = args.iter().map(Self::render_expression).collect();
+                format!("{}({})", name, args.join(", "))
+            }
         }
     }
 
@@ -533,7 +1381,7 @@ impl MetaBuilder {
 
             // Extract reference level from named arguments
             let ref_level = args.iter().find_map(|a| match a {
-                Argument::Named(key, value) if key == "ref" => Some(value.clone()),
+                Argument::Named(key, value) if key == "ref" => Some(Self::argument_to_json(value)),
                 _ => None,
             });
 
@@ -541,7 +1389,7 @@ impl MetaBuilder {
             let mut parameters = self.extract_function_parameters(fname, args);
             if let Some(ref_level) = ref_level {
                 if let serde_json::Value::Object(ref mut params_map) = parameters {
-                    params_map.insert("ref".to_string(), serde_json::Value::String(ref_level));
+                    params_map.insert("ref".to_string(), ref_level);
                 }
             }
 
@@ -551,6 +1399,7 @@ impl MetaBuilder {
                 function: fname.to_string(),
                 parameters,
                 generates_columns,
+                input: None,
             };
 
             self.add_transformation(var_name, transformation);
@@ -594,6 +1443,17 @@ impl MetaBuilder {
             crate::internal::ast::CorrelationType::Uncorrelated
         );
 
+        // Cross-parameter correlation ID: `(1 |ID1| g)` or `(1 | gr(g, id = 2))`
+        let correlation_id = random_effect.correlation_id.clone().or_else(|| {
+            match &random_effect.grouping {
+                Grouping::Gr { options, .. } => options.iter().find_map(|option| match option {
+                    GrOption::Id(id) => Some(id.clone()),
+                    _ => None,
+                }),
+                _ => None,
+            }
+        });
+
         // Process each term in the random effect
         let mut variables_in_random_effect = Vec::new();
         let mut interactions_in_random_effect = Vec::new();
@@ -614,6 +1474,7 @@ impl MetaBuilder {
                             correlated,
                             includes_interactions: Vec::new(),
                             variables: None,
+                            correlation_id: correlation_id.clone(),
                         };
                         self.add_random_effect(name, random_effect_info);
                     }
@@ -641,6 +1502,7 @@ impl MetaBuilder {
                             function: func_name.clone(),
                             parameters,
                             generates_columns,
+                            input: None,
                         };
                         self.add_transformation(base_col, transformation);
 
@@ -652,6 +1514,7 @@ impl MetaBuilder {
                             correlated,
                             includes_interactions: Vec::new(),
                             variables: None,
+                            correlation_id: correlation_id.clone(),
                         };
                         self.add_random_effect(base_col, random_effect_info);
                     }
@@ -700,10 +1563,371 @@ impl MetaBuilder {
             correlated,
             includes_interactions: interactions_in_random_effect,
             variables: Some(variables_in_random_effect),
+            correlation_id,
         };
         self.add_random_effect(&grouping_var, grouping_random_effect);
     }
 
+    /// Removes a term from the expanded model (the `-` operator)
+    ///
+    /// Removal operates on what the earlier `push_*` calls produced, so
+    /// `- a:b:c` after `a*b*c` drops only the `a_b_c` column and leaves the
+    /// main effects and lower-order interactions in place. Variables that are
+    /// no longer used anywhere are dropped from the columns map. Removing a
+    /// term that is not in the model records a warning instead of failing.
+    ///
+    /// # Arguments
+    ///
+    /// * `term` - The term that follows the `-` operator
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fiasto::internal::meta_builder::MetaBuilder;
+    /// use fiasto::internal::ast::{Response, Term};
+    ///
+    /// let mut builder = MetaBuilder::new();
+    /// builder.push_response(&Response::Single("y".to_string()));
+    /// builder.push_plain_term("x");
+    /// builder.push_plain_term("w");
+    /// builder.remove_term(&Term::Column("w".to_string()));
+    /// let metadata = builder.build("y ~ x + w - w", true, None);
+    /// assert!(!metadata.columns.contains_key("w"));
+    /// assert!(metadata.metadata.warnings.is_empty());
+    /// ```
+    pub fn remove_term(&mut self, term: &crate::internal::ast::Term) {
+        let removed = match term {
+            crate::internal::ast::Term::Column(name) => self.remove_main_effect(name),
+            crate::internal::ast::Term::Function { name, args } => {
+                self.remove_function_term(name, args)
+            }
+            crate::internal::ast::Term::AsIs(expression) => self.remove_as_is_term(expression),
+            crate::internal::ast::Term::Interaction { .. }
+            | crate::internal::ast::Term::Power { .. } => {
+                // `- a:b` removes only the product, `- a*b` also removes a and b
+                self.record_as_is_factors(term);
+                let mut removed = false;
+                for set in Self::expand_term(term) {
+                    removed |= if set.len() == 1 {
+                        match self.as_is_factors.get(&set[0]).cloned() {
+                            Some(expression) => self.remove_as_is_term(&expression),
+                            None => self.remove_main_effect(&set[0]),
+                        }
+                    } else {
+                        self.remove_interaction_term(&set)
+                    };
+                }
+                removed
+            }
+            crate::internal::ast::Term::Group(terms) => {
+                for term in terms {
+                    self.remove_term(term);
+                }
+                return;
+            }
+            crate::internal::ast::Term::RandomEffect(_) => {
+                self.warnings
+                    .push("removing random effects with '-' is not supported".to_string());
+                return;
+            }
+            crate::internal::ast::Term::Autocorrelation(_) => {
+                self.warnings.push(
+                    "removing autocorrelation structures with '-' is not supported".to_string(),
+                );
+                return;
+            }
+            _ => false,
+        };
+
+        if !removed {
+            self.warnings.push(format!(
+                "term '{}' is not in the model and cannot be removed",
+                Self::describe_term(term)
+            ));
+        }
+    }
+
+    /// Removes the main effect of a plain variable, returning whether it was present
+    fn remove_main_effect(&mut self, name: &str) -> bool {
+        let is_read = self.is_read_by_transformation(name);
+        let Some(var_info) = self.columns.get_mut(name) else {
+            return false;
+        };
+        if var_info.roles.contains(&VariableRole::Response)
+            || var_info.roles.contains(&VariableRole::InteractionTerm)
+            || !var_info.generated_columns.iter().any(|col| col == name)
+        {
+            return false;
+        }
+
+        var_info.generated_columns.retain(|col| col != name);
+        var_info.roles.retain(|role| *role != VariableRole::Identity);
+        if var_info.transformations.is_empty() && !is_read {
+            var_info.roles.retain(|role| *role != VariableRole::FixedEffect);
+        }
+        self.drop_if_unused(name);
+        true
+    }
+
+    /// Removes a transformation such as `log(x)`, returning whether it was present
+    ///
+    /// Every argument has to match, so `- poly(x, 3)` does not remove
+    /// `poly(x, 2)` and `- lag(x, 2)` only removes the second lag.
+    fn remove_function_term(&mut self, fname: &str, args: &[Argument]) -> bool {
+        let Some(base_col) = Self::base_variable(args).map(str::to_string) else {
+            return false;
+        };
+        let chain = self.transformation_chain(fname, args);
+        self.remove_transformation(&base_col, chain.len(), |steps| {
+            steps.iter().zip(&chain).all(|(step, expected)| {
+                step.function == expected.function
+                    && step.input == expected.input
+                    && step.parameters == expected.parameters
+            })
+        })
+    }
+
+    /// Removes an `I()` term, returning whether it was present
+    fn remove_as_is_term(&mut self, expression: &Expression) -> bool {
+        let Some(base_col) = Self::expression_variables(expression).into_iter().next() else {
+            return false;
+        };
+        let column = Self::as_is_column(expression);
+        self.remove_transformation(&base_col, 1, |steps| {
+            steps[0].function == "I" && steps[0].generates_columns == [column.as_str()]
+        })
+    }
+
+    /// Removes the first run of `count` consecutive transformations of a variable
+    /// that matches a predicate
+    ///
+    /// A nested call such as `offset(log(x))` is stored as a chain of
+    /// transformations, so it is removed as a run of `count` steps.
+    fn remove_transformation(
+        &mut self,
+        base_col: &str,
+        count: usize,
+        predicate: impl Fn(&[Transformation]) -> bool,
+    ) -> bool {
+        let base_col = base_col.to_string();
+        let is_read = self.is_read_by_transformation(&base_col);
+        let Some(var_info) = self.columns.get_mut(&base_col) else {
+            return false;
+        };
+        // A run that feeds a later step belongs to a longer chain and is left alone
+        let transformations = &var_info.transformations;
+        let Some(index) = (0..=transformations.len().saturating_sub(count)).find(|&i| {
+            transformations.len() >= i + count
+                && predicate(&transformations[i..i + count])
+                && transformations.get(i + count).is_none_or(|next| next.input.is_none())
+        }) else {
+            return false;
+        };
+
+        let removed: Vec<Transformation> =
+            var_info.transformations.drain(index..index + count).collect();
+        let remaining = Self::transformation_columns(&var_info.transformations);
+        var_info.generated_columns.retain(|col| {
+            !removed.iter().any(|t| t.generates_columns.contains(col)) || remaining.contains(col)
+        });
+        if var_info.transformations.is_empty() {
+            var_info.roles.retain(|role| *role != VariableRole::Categorical);
+            // A plain term (`poly(x, 2) + x - poly(x, 2)`) only has the Identity role
+            let is_main_effect = var_info.generated_columns.contains(&base_col)
+                && !var_info.roles.contains(&VariableRole::Identity);
+            if !is_main_effect && !is_read {
+                var_info.roles.retain(|role| *role != VariableRole::FixedEffect);
+            }
+        }
+        self.drop_if_unused(&base_col);
+        // Variables read by the removed terms may no longer be used
+        let covariates: Vec<String> = removed
+            .iter()
+            .flat_map(Self::transformation_covariates)
+            .map(str::to_string)
+            .collect();
+        for name in covariates {
+            if name != base_col {
+                self.drop_if_unused(&name);
+            }
+        }
+        true
+    }
+
+    /// Removes a fixed-effects interaction column, returning whether it was present
+    fn remove_interaction_term(&mut self, variables: &[String]) -> bool {
+        let Some(interaction_name) = self.find_interaction_name(variables) else {
+            return false;
+        };
+
+        self.columns.remove(&interaction_name);
+        self.name_to_id.remove(&interaction_name);
+
+        let participants: Vec<String> =
+            variables.iter().flat_map(|v| self.factor_variables(v)).collect();
+        for var in participants {
+            if let Some(var_info) = self.columns.get_mut(&var) {
+                var_info.interactions.retain(|interaction| {
+                    let same_participants = interaction.with.len() + 1 == variables.len()
+                        && interaction.with.iter().all(|w| variables.contains(w));
+                    !(interaction.context == "fixed_effects" && same_participants)
+                });
+            }
+            self.drop_if_unused(&var);
+        }
+        true
+    }
+
+    /// Finds the column of a fixed-effects interaction regardless of the order
+    /// in which its variables are given (`b:a` finds `a_b`)
+    fn find_interaction_name(&self, variables: &[String]) -> Option<String> {
+        let is_interaction = |name: &String| {
+            self.columns
+                .get(name)
+                .is_some_and(|v| v.roles.contains(&VariableRole::InteractionTerm))
+        };
+
+        let name = Self::create_interaction_name(variables);
+        if is_interaction(&name) {
+            return Some(name);
+        }
+
+        // The first variable of an interaction records the others in column order
+        variables.iter().find_map(|var| {
+            let first = self.factor_variables(var).into_iter().next()?;
+            self.columns.get(&first)?.interactions.iter().find_map(|interaction| {
+                let same_participants = interaction.context == "fixed_effects"
+                    && interaction.with.len() + 1 == variables.len()
+                    && interaction.with.iter().all(|w| variables.contains(w));
+                if !same_participants {
+                    return None;
+                }
+                let mut ordered = vec![var.clone()];
+                ordered.extend(interaction.with.iter().cloned());
+                Some(Self::create_interaction_name(&ordered)).filter(is_interaction)
+            })
+        })
+    }
+
+    /// The variables a transformation reads, as listed under `variables` and
+    /// `by` in its parameters (`s(x, z, by = g)` reads `x`, `z` and `g`)
+    fn transformation_covariates(transformation: &Transformation) -> Vec<&str> {
+        ["variables", "by"]
+            .iter()
+            .flat_map(|key| match transformation.parameters.get(key) {
+                Some(serde_json::Value::Array(names)) => names.iter().collect(),
+                Some(value) => vec![value],
+                None => Vec::new(),
+            })
+            .filter_map(|name| name.as_str())
+            .collect()
+    }
+
+    /// Checks whether a transformation in the model reads a variable
+    fn is_read_by_transformation(&self, name: &str) -> bool {
+        self.columns
+            .values()
+            .flat_map(|v| &v.transformations)
+            .any(|t| Self::transformation_covariates(t).contains(&name))
+    }
+
+    /// Drops a variable that no longer contributes anything to the model
+    ///
+    /// A variable that only a transformation of another variable reads, such
+    /// as `z` in `s(x, z)`, is kept while that transformation is in the model.
+    fn drop_if_unused(&mut self, name: &str) {
+        let unused = !self.is_read_by_transformation(name)
+            && self.columns.get(name).is_some_and(|v| {
+                v.generated_columns.is_empty()
+                    && v.transformations.is_empty()
+                    && v.interactions.is_empty()
+                    && v.random_effects.is_empty()
+            });
+        if unused {
+            self.columns.remove(name);
+            self.name_to_id.remove(name);
+        }
+    }
+
+    /// Renders a term back to formula syntax for warning messages
+    fn describe_term(term: &crate::internal::ast::Term) -> String {
+        match term {
+            crate::internal::ast::Term::Column(name) => name.clone(),
+            crate::internal::ast::Term::Function { name, args } => {
+                let args: Vec<String> = args.iter().map(Self::describe_argument).collect();
+                format!("{}({})", name, args.join(", "))
+            }
+            crate::internal::ast::Term::Interaction {
+                left,
+                right,
+                operator,
+            } => {
+                let symbol = match operator {
+                    InteractionOperator::InteractionAndEffect => "*",
+                    InteractionOperator::InteractionOnly => ":",
+                    InteractionOperator::Nesting => "/",
+                    InteractionOperator::NestedWithin => " %in% ",
+                };
+                format!(
+                    "{}{}{}",
+                    Self::describe_term(left),
+                    symbol,
+                    Self::describe_term(right)
+                )
+            }
+            crate::internal::ast::Term::Intercept => "1".to_string(),
+            crate::internal::ast::Term::Zero => "0".to_string(),
+            crate::internal::ast::Term::RandomEffect(_) => "random effect".to_string(),
+            crate::internal::ast::Term::Group(terms) => {
+                let terms: Vec<String> = terms.iter().map(Self::describe_term).collect();
+                format!("({})", terms.join(" + "))
+            }
+            crate::internal::ast::Term::Power { base, order } => {
+                format!("{}^{}", Self::describe_term(base), order)
+            }
+            crate::internal::ast::Term::AsIs(expression) => {
+                Self::as_is_column(expression)
+            }
+            crate::internal::ast::Term::Autocorrelation(autocorrelation) => autocorrelation.term(),
+            crate::internal::ast::Term::Removal(inner) => Self::describe_term(inner),
+        }
+    }
+
+    /// Renders a function argument back to formula syntax
+    fn describe_argument(arg: &Argument) -> String {
+        match arg {
+            Argument::Ident(s) => s.clone(),
+            Argument::Integer(n) => n.to_string(),
+            Argument::Number(n) => n.to_string(),
+            Argument::String(s) => format!("\"{}\"", s),
+            Argument::Boolean(b) => b.to_string().to_uppercase(),
+            Argument::Named(key, value) => format!("{} = {}", key, Self::describe_argument(value)),
+            Argument::Function { name, args } => {
+                let args: Vec<String> = args.iter().map(Self::describe_argument).collect();
+                format!("{}({})", name, args.join(", "))
+            }
+        }
+    }
+
+    /// Converts an argument value to JSON
+    ///
+    /// Whole numbers are written as JSON integers, so `lag(x, -1)` gives `-1`
+    /// rather than `-1.0`.
+    pub(crate) fn argument_to_json(arg: &Argument) -> serde_json::Value {
+        match arg {
+            Argument::Integer(n) => serde_json::Value::Number((*n).into()),
+            Argument::Number(n) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => {
+                serde_json::Value::Number((*n as i64).into())
+            }
+            Argument::Number(n) => serde_json::json!(n),
+            Argument::String(s) => serde_json::Value::String(s.clone()),
+            Argument::Boolean(b) => serde_json::Value::Bool(*b),
+            Argument::Ident(s) => serde_json::Value::String(s.clone()),
+            Argument::Named(_, value) => Self::argument_to_json(value),
+            Argument::Function { .. } => serde_json::Value::String(Self::describe_argument(arg)),
+        }
+    }
+
     /// Extracts function parameters into a JSON value
     fn extract_function_parameters(&self, fname: &str, args: &[Argument]) -> serde_json::Value {
         let mut params = serde_json::Map::new();
@@ -721,6 +1945,56 @@ impl MetaBuilder {
             "log" => {
                 // No additional parameters for log
             }
+            "bs" | "ns" => {
+                params.insert(
+                    "df".to_string(),
+                    serde_json::json!(Self::spline_dimension(fname, args)),
+                );
+                if fname == "bs" {
+                    params.insert("degree".to_string(), serde_json::json!(3));
+                }
+                params.insert("intercept".to_string(), serde_json::Value::Bool(false));
+                // `knots = c(1, 2)` and `Boundary.knots = c(0, 3)` are lists of numbers
+                for arg in args {
+                    if let Argument::Named(key, value) = arg {
+                        let value = match value.as_ref() {
+                            Argument::Function { name, args } if name == "c" => serde_json::Value::Array(
+                                args.iter().map(Self::argument_to_json).collect(),
+                            ),
+                            Argument::Integer(_) | Argument::Number(_) if key.ends_with("knots") => {
+                                serde_json::json!([Self::argument_to_json(value)])
+                            }
+                            value => Self::argument_to_json(value),
+                        };
+                        if key != "df" {
+                            params.insert(key.clone(), value);
+                        }
+                    }
+                }
+            }
+            "s" | "te" | "ti" | "t2" => {
+                let (bs, k): (Vec<String>, Vec<u32>) =
+                    Self::smooth_margins(fname, args).into_iter().unzip();
+                params.insert(
+                    "variables".to_string(),
+                    serde_json::json!(Self::smooth_variables(args)),
+                );
+                // s() has one basis; tensor products list one per variable
+                if fname == "s" {
+                    params.insert("bs".to_string(), serde_json::json!(bs[0]));
+                    params.insert("k".to_string(), serde_json::json!(k[0]));
+                } else {
+                    params.insert("bs".to_string(), serde_json::json!(bs));
+                    params.insert("k".to_string(), serde_json::json!(k));
+                }
+                for arg in args {
+                    if let Argument::Named(key, value) = arg {
+                        if key != "bs" && key != "k" {
+                            params.insert(key.clone(), Self::argument_to_json(value));
+                        }
+                    }
+                }
+            }
             "factor" => {
                 // Handle factor function parameters (same as c function)
                 // Parameters are handled by the generic case below
@@ -729,18 +2003,12 @@ impl MetaBuilder {
                 // Generic parameter handling
                 for (i, arg) in args.iter().enumerate() {
                     let key = format!("arg_{}", i);
-                    let value = match arg {
-                        Argument::Integer(n) => serde_json::Value::Number((*n).into()),
-                        Argument::String(s) => serde_json::Value::String(s.clone()),
-                        Argument::Boolean(b) => serde_json::Value::Bool(*b),
-                        Argument::Ident(s) => serde_json::Value::String(s.clone()),
-                        Argument::Named(key, value) => {
-                            // For named arguments, use the key directly
-                            params.insert(key.clone(), serde_json::Value::String(value.clone()));
-                            continue; // Skip the generic arg_N handling
-                        }
-                    };
-                    params.insert(key, value);
+                    if let Argument::Named(key, value) = arg {
+                        // For named arguments, use the key directly
+                        params.insert(key.clone(), Self::argument_to_json(value));
+                        continue; // Skip the generic arg_N handling
+                    }
+                    params.insert(key, Self::argument_to_json(arg));
                 }
             }
         }
@@ -748,6 +2016,125 @@ impl MetaBuilder {
         serde_json::Value::Object(params)
     }
 
+    /// The number of basis columns of a `bs()` or `ns()` spline
+    ///
+    /// Without explicit knots this is `df`. With knots, `bs()` has one column
+    /// per knot and per polynomial degree, `ns()` one per knot plus one, and
+    /// both one more with `intercept = TRUE`. Without either, the basis has no
+    /// interior knots.
+    fn spline_dimension(fname: &str, args: &[Argument]) -> u32 {
+        let value = |key: &str| {
+            args.iter().find_map(|a| match a {
+                Argument::Named(k, value) if k == key => Some(value.as_ref()),
+                _ => None,
+            })
+        };
+        if let Some(Argument::Integer(df)) = value("df") {
+            return *df;
+        }
+        let knots = match value("knots") {
+            Some(Argument::Function { args, .. }) => args.len() as u32,
+            Some(_) => 1,
+            None => 0,
+        };
+        let degree = match value("degree") {
+            Some(Argument::Integer(degree)) => *degree,
+            _ if fname == "bs" => 3,
+            _ => 1,
+        };
+        let intercept = matches!(value("intercept"), Some(Argument::Boolean(true))) as u32;
+        knots + degree + intercept
+    }
+
+    /// The variables of a smooth such as `te(x, z)`, which come before its named arguments
+    fn smooth_variables(args: &[Argument]) -> Vec<&str> {
+        args.iter()
+            .map_while(|a| match a {
+                Argument::Ident(s) => Some(s.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Every variable a smooth reads: its variables followed by its `by` variable
+    fn smooth_covariates<'a>(fname: &str, args: &'a [Argument]) -> Vec<&'a str> {
+        if !matches!(fname, "s" | "te" | "ti" | "t2") {
+            return Vec::new();
+        }
+        let mut covariates = Self::smooth_variables(args);
+        if let Some(Argument::Ident(by)) = args.iter().find_map(|a| match a {
+            Argument::Named(key, value) if key == "by" => Some(value.as_ref()),
+            _ => None,
+        }) {
+            if !covariates.contains(&by.as_str()) {
+                covariates.push(by);
+            }
+        }
+        covariates
+    }
+
+    /// The basis type and dimension of each margin of a smooth
+    ///
+    /// `s()` has a single basis, by default the mgcv thin plate spline (`"tp"`)
+    /// of dimension 10 for one variable, 30 for two and so on. The tensor
+    /// products `te()`, `ti()` and `t2()` have one basis per variable, by
+    /// default `"cr"` of dimension 5. `k = c(5, 7)` and `bs = c("cr", "ps")`
+    /// give one value per margin, `k = 5` the same value for every margin.
+    fn smooth_margins(fname: &str, args: &[Argument]) -> Vec<(String, u32)> {
+        let variables = Self::smooth_variables(args).len() as u32;
+        let values = |key: &str| -> Vec<&Argument> {
+            match args.iter().find_map(|a| match a {
+                Argument::Named(k, value) if k == key => Some(value.as_ref()),
+                _ => None,
+            }) {
+                Some(Argument::Function { name, args }) if name == "c" => args.iter().collect(),
+                Some(value) => vec![value],
+                None => Vec::new(),
+            }
+        };
+        let (k, bs) = (values("k"), values("bs"));
+        let margins = if fname == "s" { 1 } else { variables as usize };
+        (0..margins)
+            .map(|i| {
+                let k = match k.get(i).or(k.first()) {
+                    Some(Argument::Integer(k)) => *k,
+                    _ if fname == "s" => 3u32
+                        .saturating_pow(variables.saturating_sub(1))
+                        .saturating_mul(10),
+                    _ => 5,
+                };
+                let bs = match bs.get(i).or(bs.first()) {
+                    Some(Argument::String(bs)) => bs.clone(),
+                    _ if fname == "s" => "tp".to_string(),
+                    _ => "cr".to_string(),
+                };
+                (bs, k)
+            })
+            .collect()
+    }
+
+    /// The function part of the column names of a basis, numbered from the
+    /// second basis of the same variables on
+    ///
+    /// `s(x, k = 5) + s(x, k = 6)` gives `x_s_1` .. `x_s_5` for the first
+    /// smooth and `x_s2_1` .. `x_s2_6` for the second, so that no two bases
+    /// share a column. Splines are numbered the same way: `x_bs2_1` ..
+    fn basis_tag(&self, base_name: &str, fname: &str) -> String {
+        let is_taken = |tag: &str| {
+            let first = format!("{}_{}_1", base_name, tag);
+            self.columns
+                .values()
+                .any(|v| v.generated_columns.contains(&first))
+        };
+        if !is_taken(fname) {
+            return fname.to_string();
+        }
+        (2..)
+            .map(|n| format!("{}{}", fname, n))
+            .find(|tag| !is_taken(tag))
+            .unwrap()
+    }
+
     /// Generates column names for transformations
     fn generate_transformation_columns(&self, fname: &str, args: &[Argument]) -> Vec<String> {
         let base_name = args
@@ -769,6 +2156,30 @@ impl MetaBuilder {
                 }
             }
             "log" => vec![format!("{}_log", base_name)],
+            "bs" | "ns" => {
+                let tag = self.basis_tag(base_name, fname);
+                (1..=Self::spline_dimension(fname, args))
+                    .map(|i| format!("{}_{}_{}", base_name, tag, i))
+                    .collect()
+            }
+            "s" | "te" | "ti" | "t2" => {
+                // One column per basis function: s(x, k = 10) gives x_s_1 .. x_s_10
+                // and a second smooth of x gives x_s2_1 ..
+                let mut base_name = Self::smooth_variables(args).join("_");
+                if let Some(Argument::Ident(by)) = args.iter().find_map(|a| match a {
+                    Argument::Named(key, value) if key == "by" => Some(value.as_ref()),
+                    _ => None,
+                }) {
+                    base_name = format!("{}_by_{}", base_name, by);
+                }
+                let dimension = Self::smooth_margins(fname, args)
+                    .iter()
+                    .fold(1u32, |dimension, (_, k)| dimension.saturating_mul(*k));
+                let tag = self.basis_tag(&base_name, fname);
+                (1..=dimension)
+                    .map(|i| format!("{}_{}_{}", base_name, tag, i))
+                    .collect()
+            }
             "c" | "factor" => {
                 // For categorical variables, we generate dummy variables for each level
                 // The reference level is excluded (handled by the ref parameter)
@@ -815,26 +2226,47 @@ impl MetaBuilder {
         has_intercept: bool,
         family: Option<String>,
     ) -> crate::internal::data_structures::FormulaMetaData {
+        let mut columns = self.columns;
+
+        // Re-number IDs so they stay contiguous after terms were removed
+        let mut ids: Vec<u32> = columns.values().map(|v| v.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        for var in columns.values_mut() {
+            var.id = ids.iter().position(|&id| id == var.id).unwrap() as u32 + 1;
+        }
+
         // Generate all_generated_columns ordered by ID
         let mut all_generated_columns = Vec::new();
-        let mut sorted_vars: Vec<_> = self.columns.values().collect();
-        sorted_vars.sort_by_key(|v| v.id);
+        // Columns that share an ID (cbind responses) keep their order in the formula
+        let mut sorted_vars: Vec<_> = columns.iter().collect();
+        sorted_vars.sort_by_key(|(name, v)| (v.id, self.responses.iter().position(|r| r == *name)));
+        let sorted_vars: Vec<&VariableInfo> = sorted_vars.into_iter().map(|(_, v)| v).collect();
 
         for var in &sorted_vars {
             all_generated_columns.extend(var.generated_columns.clone());
         }
 
+        // Response variables have the lowest IDs; parameter sub-formulas have none
+        let is_response = |v: &VariableInfo| v.roles.contains(&VariableRole::Response);
+        let response_column_count: usize = sorted_vars
+            .iter()
+            .filter(|v| is_response(v))
+            .map(|v| v.generated_columns.len())
+            .sum();
+
         // Add intercept column if has_intercept is true
         if has_intercept {
-            all_generated_columns.insert(1, "intercept".to_string()); // Insert after response (index 1)
+            // Insert after the response columns
+            all_generated_columns.insert(response_column_count, "intercept".to_string());
         }
 
         // Generate all_generated_columns_formula_order mapping
         let mut all_generated_columns_formula_order = std::collections::HashMap::new();
         let mut order_index = 1;
 
-        // Add all response variables (always first, all have id == 1)
-        for response_var in sorted_vars.iter().filter(|v| v.id == 1) {
+        // Add all response variables (always first)
+        for response_var in sorted_vars.iter().filter(|v| is_response(v)) {
             for response_col in &response_var.generated_columns {
                 all_generated_columns_formula_order
                     .insert(order_index.to_string(), response_col.clone());
@@ -851,7 +2283,7 @@ impl MetaBuilder {
 
         // Add all other variables in order
         for var in &sorted_vars {
-            if var.id != 1 {
+            if !is_response(var) {
                 // Skip response (already added)
                 for col in &var.generated_columns {
                     all_generated_columns_formula_order
@@ -861,8 +2293,16 @@ impl MetaBuilder {
             }
         }
 
-        // Count response variables (all variables with ID 1)
-        let response_variable_count = self.columns.values().filter(|v| v.id == 1).count() as u32;
+        // Count response variables
+        // The two columns of cbind(successes, failures) share an ID and are a single response
+        let mut response_ids: Vec<u32> = columns
+            .values()
+            .filter(|v| is_response(v))
+            .map(|v| v.id)
+            .collect();
+        response_ids.sort_unstable();
+        response_ids.dedup();
+        let response_variable_count = response_ids.len() as u32;
 
         crate::internal::data_structures::FormulaMetaData {
             formula: input.to_string(),
@@ -872,10 +2312,23 @@ impl MetaBuilder {
                 has_uncorrelated_slopes_and_intercepts: self.has_uncorrelated_slopes_and_intercepts,
                 family,
                 response_variable_count,
+                warnings: self.warnings,
+                rescor: None,
+                options: HashMap::new(),
             },
-            columns: self.columns,
+            columns,
             all_generated_columns,
             all_generated_columns_formula_order,
+            dpars: HashMap::new(),
+            correlation_ids: HashMap::new(),
+            nonlinear: self.nonlinear,
+            nlpars: HashMap::new(),
+            response: self.response_info,
+            family: None,
+            fixed_parameters: HashMap::new(),
+            autocorrelation: self.autocorrelation,
+            formulas: Vec::new(),
+            responses: HashMap::new(),
         }
     }
 }
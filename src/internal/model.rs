@@ -0,0 +1,381 @@
+//! # Model Assembly
+//!
+//! Gathers the parsed pieces of a formula or of a brms-style model specification
+//! into a `Model`: one `ResponseModel` per response, with its parameter formulas,
+//! its family and the formula listing of the specification.
+
+use crate::internal::{
+    ast::{
+        Argument, Autocorrelation, Expression, FamilySpec, ModelComponent, ParameterFormula,
+        ParsedFormula, Response, Term,
+    },
+    data_structures::{FormulaRole, ModelFormula},
+    errors::ParseError,
+};
+
+/// A parsed response: its formula with every parameter formula merged in
+#[derive(Clone)]
+pub(crate) struct ResponseModel {
+    pub(crate) parsed: ParsedFormula,
+    /// Parameters given by `nlf()`, with their expressions
+    pub(crate) nonlinear_dpars: Vec<(String, Expression)>,
+}
+
+/// A parsed model: one formula per response
+pub(crate) struct Model {
+    pub(crate) responses: Vec<ResponseModel>,
+    /// The formula listing of a model specification
+    pub(crate) formulas: Vec<ModelFormula>,
+    /// Whether residual correlations between responses are modelled (`set_rescor()`)
+    pub(crate) rescor: Option<bool>,
+    pub(crate) warnings: Vec<String>,
+}
+
+impl Model {
+    /// A model with the single response of a plain formula such as `y ~ x, sigma ~ z`
+    pub(crate) fn from_formula(parsed: ParsedFormula) -> Self {
+        Model {
+            responses: vec![ResponseModel {
+                parsed,
+                nonlinear_dpars: Vec::new(),
+            }],
+            formulas: Vec::new(),
+            rescor: None,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// An `lf()` or `nlf()` component waiting for the formula it belongs to
+struct ParameterComponent {
+    resp: Option<String>,
+    formulas: Vec<ParameterFormula>,
+    nonlinear: Option<(String, Expression)>,
+}
+
+/// Merges the components of a model specification into one model per response
+///
+/// Each `bf()` formula is a response. The `lf()` and `nlf()` formulas join the
+/// parameter formulas of the response named by `resp =` (or of the only
+/// response). A `gaussian()` component sets the family of every formula before
+/// it that has none yet, or of the next formula when none comes before it, so
+/// `bf(y1 ~ x) + bf(y2 ~ z) + gaussian()` gives both responses a family. A
+/// `cor_ar()` component likewise adds its autocorrelation structure to every
+/// formula since the previous `cor_ar()`, or to the next formula.
+pub(crate) fn assemble_model(components: Vec<ModelComponent>) -> Result<Model, ParseError> {
+    let mut mains: Vec<(Option<String>, ParsedFormula, Vec<FamilySpec>)> = Vec::new();
+    let mut pending_families = Vec::new();
+    let mut pending_autocorrelations: Vec<Autocorrelation> = Vec::new();
+    // The first formula the next autocorrelation component applies to
+    let mut autocorrelation_start = 0;
+    let mut extra = Vec::new();
+    let mut formulas = Vec::new();
+    let mut rescor = None;
+    let mut add_formula =
+        |formula: &str, role, parameter: &str, dpar: Option<String>, response: Option<String>| {
+            formulas.push(ModelFormula {
+                formula: formula.to_string(),
+                role,
+                parameter: parameter.to_string(),
+                dpar,
+                response,
+            })
+        };
+
+    for component in components {
+        match component {
+            ModelComponent::Formula { mut formula, texts } => {
+                let key = formula.response.as_ref().and_then(response_name);
+                let is_bound = matches!(formula.response, Some(Response::Multivariate(_)));
+                if !mains.is_empty() && (is_bound || mains.iter().any(|(key, ..)| key.is_none())) {
+                    return Err(ParseError::Syntax(
+                        "formulas without a single response cannot be combined with other formulas"
+                            .into(),
+                    ));
+                }
+                if let Some(response) = &formula.response {
+                    let variables = response_variables(response);
+                    let repeated = mains.iter().find_map(|(_, parsed, _)| {
+                        let others = response_variables(parsed.response.as_ref()?);
+                        variables.iter().find(|v| others.contains(v)).copied()
+                    });
+                    if let Some(name) = repeated {
+                        return Err(ParseError::Syntax(format!(
+                            "response '{}' has more than one formula",
+                            name
+                        )));
+                    }
+                }
+                add_formula(&texts[0], FormulaRole::Main, "mu", None, key.clone());
+                for (parameter, text) in formula.parameters.iter().zip(&texts[1..]) {
+                    let role = FormulaRole::LinearPredictor;
+                    add_formula(text, role, &parameter.parameter, None, key.clone());
+                }
+                if !pending_autocorrelations.is_empty() {
+                    autocorrelation_start = mains.len() + 1;
+                }
+                for autocorrelation in pending_autocorrelations.drain(..) {
+                    let text = format!("autocor ~ {}", autocorrelation.term());
+                    add_formula(
+                        &text,
+                        FormulaRole::Autocorrelation,
+                        "autocor",
+                        None,
+                        key.clone(),
+                    );
+                    formula.terms.push(Term::Autocorrelation(autocorrelation));
+                }
+                mains.push((key, formula, std::mem::take(&mut pending_families)));
+            }
+            ModelComponent::Linear {
+                formulas,
+                dpar,
+                resp,
+                texts,
+            } => {
+                for (parameter, text) in formulas.iter().zip(&texts) {
+                    let role = FormulaRole::LinearPredictor;
+                    add_formula(text, role, &parameter.parameter, dpar.clone(), resp.clone());
+                }
+                extra.push(ParameterComponent {
+                    resp,
+                    formulas,
+                    nonlinear: None,
+                });
+            }
+            ModelComponent::NonLinear {
+                parameter,
+                expression,
+                formulas,
+                dpar,
+                resp,
+                texts,
+            } => {
+                let role = FormulaRole::NonLinearPredictor;
+                add_formula(&texts[0], role, &parameter, dpar, resp.clone());
+                for (nlpar, text) in formulas.iter().zip(&texts[1..]) {
+                    let role = FormulaRole::LinearPredictor;
+                    add_formula(
+                        text,
+                        role,
+                        &nlpar.parameter,
+                        Some(parameter.clone()),
+                        resp.clone(),
+                    );
+                }
+                extra.push(ParameterComponent {
+                    resp,
+                    formulas,
+                    nonlinear: Some((parameter, expression)),
+                });
+            }
+            // A family applies to the formulas before it without one, or to the next one
+            ModelComponent::Family(family) => {
+                let mut without: Vec<_> = mains
+                    .iter_mut()
+                    .filter(|(_, parsed, families)| families.is_empty() && parsed.family.is_none())
+                    .collect();
+                if without.is_empty() {
+                    // A second family for the formula before it, reported below
+                    match mains.last_mut() {
+                        Some((.., families)) => families.push(family),
+                        None => pending_families.push(family),
+                    }
+                } else {
+                    for (.., families) in without.iter_mut() {
+                        families.push(family.clone());
+                    }
+                }
+            }
+            ModelComponent::Rescor(value) => rescor = Some(value),
+            // An autocorrelation structure applies to the formulas since the
+            // previous one, or to the formula before it
+            ModelComponent::Autocorrelation(autocorrelation) => {
+                if mains.is_empty() {
+                    pending_autocorrelations.push(autocorrelation);
+                    continue;
+                }
+                let start = autocorrelation_start.min(mains.len() - 1);
+                for (key, parsed, _) in &mut mains[start..] {
+                    let text = format!("autocor ~ {}", autocorrelation.term());
+                    add_formula(
+                        &text,
+                        FormulaRole::Autocorrelation,
+                        "autocor",
+                        None,
+                        key.clone(),
+                    );
+                    parsed.terms.push(Term::Autocorrelation(autocorrelation.clone()));
+                }
+                autocorrelation_start = mains.len();
+            }
+        }
+    }
+
+    if mains.is_empty() {
+        return Err(ParseError::Syntax(
+            "model specification has no main formula; add one with bf()".into(),
+        ));
+    }
+    let several = mains.len() > 1;
+    let mut responses = Vec::new();
+    let mut extra_parameters: Vec<Vec<ParameterFormula>> = Vec::new();
+    let mut keys = Vec::new();
+    for (key, mut parsed, mut families) in mains {
+        families.extend(parsed.family.take());
+        if families.len() > 1 {
+            return Err(ParseError::Syntax(match &key {
+                Some(name) if several => format!("response '{}' has more than one family", name),
+                _ => "the model specification has more than one family".into(),
+            }));
+        }
+        parsed.family = families.pop();
+        responses.push(ResponseModel {
+            parsed,
+            nonlinear_dpars: Vec::new(),
+        });
+        extra_parameters.push(Vec::new());
+        keys.push(key);
+    }
+    for component in extra {
+        let index =
+            match &component.resp {
+                Some(name) => keys
+                    .iter()
+                    .position(|key| key.as_ref() == Some(name))
+                    .ok_or_else(|| {
+                        ParseError::Syntax(format!("the model has no response named '{}'", name))
+                    })?,
+                None if responses.len() == 1 => 0,
+                None => return Err(ParseError::Syntax(
+                    "lf() and nlf() need resp = \"<response>\" in a model with several responses"
+                        .into(),
+                )),
+            };
+        extra_parameters[index].extend(component.formulas);
+        responses[index].nonlinear_dpars.extend(component.nonlinear);
+    }
+
+    for (model, extra) in responses.iter_mut().zip(extra_parameters) {
+        let parsed = &mut model.parsed;
+        let mut names: Vec<&str> = parsed
+            .parameters
+            .iter()
+            .map(|p| p.parameter.as_str())
+            .collect();
+        names.extend(extra.iter().map(|p| p.parameter.as_str()));
+        names.extend(model.nonlinear_dpars.iter().map(|(p, _)| p.as_str()));
+        if let Some(name) = names
+            .iter()
+            .enumerate()
+            .find_map(|(i, name)| names[..i].contains(name).then_some(name))
+        {
+            return Err(ParseError::Syntax(format!(
+                "parameter '{}' has more than one formula",
+                name
+            )));
+        }
+        if let Some(fixed) = parsed
+            .fixed
+            .iter()
+            .find(|f| names.contains(&f.parameter.as_str()))
+        {
+            return Err(ParseError::Syntax(format!(
+                "parameter '{}' already has a formula or a fixed value",
+                fixed.parameter
+            )));
+        }
+        parsed.parameters.extend(extra);
+    }
+
+    let mut warnings = Vec::new();
+    if responses.len() == 1 {
+        // A single formula needs no response labels in the listing
+        for formula in &mut formulas {
+            formula.response = None;
+        }
+        if rescor.is_some()
+            && !matches!(
+                responses[0].parsed.response,
+                Some(Response::Multivariate(_))
+            )
+        {
+            warnings
+                .push("set_rescor() has no effect on a model with a single response".to_string());
+        }
+    }
+
+    Ok(Model {
+        responses,
+        formulas,
+        rescor,
+        warnings,
+    })
+}
+
+/// Every column a response reads: `y` for `log(y)`, both columns of `cbind(s, f)`
+pub(crate) fn response_variables(response: &Response) -> Vec<&str> {
+    fn argument_columns<'a>(args: &'a [Argument], columns: &mut Vec<&'a str>) {
+        for arg in args {
+            match arg {
+                Argument::Ident(name) => columns.push(name),
+                Argument::Function { args, .. } => argument_columns(args, columns),
+                _ => {}
+            }
+        }
+    }
+    fn expression_columns<'a>(expression: &'a Expression, columns: &mut Vec<&'a str>) {
+        match expression {
+            Expression::Column(name) => columns.push(name),
+            Expression::Number(_) => {}
+            Expression::Negate(operand) => expression_columns(operand, columns),
+            Expression::Binary { left, right, .. } => {
+                expression_columns(left, columns);
+                expression_columns(right, columns);
+            }
+            Expression::Call { args, .. } => args.iter().for_each(|a| expression_columns(a, columns)),
+        }
+    }
+
+    let mut columns = Vec::new();
+    match response {
+        Response::Single(name) => columns.push(name.as_str()),
+        Response::Multivariate(names) => columns.extend(names.iter().map(|n| n.as_str())),
+        Response::Binomial { successes, failures } => {
+            columns.extend([successes.as_str(), failures.as_str()])
+        }
+        Response::Function { args, .. } => argument_columns(args, &mut columns),
+        Response::AsIs(expression) => expression_columns(expression, &mut columns),
+    }
+    columns
+}
+
+/// The name a response's formula is known by: `y` for `y`, `log(y)`, `I(y / 2)`
+/// and `cbind(y, n)`; none for one-sided formulas and `mvbind()` responses
+pub(crate) fn response_name(response: &Response) -> Option<String> {
+    fn first_column(args: &[Argument]) -> Option<String> {
+        args.iter().find_map(|arg| match arg {
+            Argument::Ident(name) => Some(name.clone()),
+            Argument::Function { args, .. } => first_column(args),
+            _ => None,
+        })
+    }
+    fn first_variable(expression: &Expression) -> Option<String> {
+        match expression {
+            Expression::Column(name) => Some(name.clone()),
+            Expression::Number(_) => None,
+            Expression::Negate(operand) => first_variable(operand),
+            Expression::Binary { left, right, .. } => {
+                first_variable(left).or_else(|| first_variable(right))
+            }
+            Expression::Call { args, .. } => args.iter().find_map(first_variable),
+        }
+    }
+    match response {
+        Response::Single(name) => Some(name.clone()),
+        Response::Function { args, .. } => first_column(args),
+        Response::Binomial { successes, .. } => Some(successes.clone()),
+        Response::AsIs(expression) => first_variable(expression),
+        Response::Multivariate(_) => None,
+    }
+}
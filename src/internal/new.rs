@@ -28,7 +28,10 @@ use logos::Logos;
 /// # How it works
 /// 1. Creates a Logos lexer from the input string
 /// 2. Iterates through all tokens, collecting them into a vector
-/// 3. Returns a Parser struct with the tokens and initial position
+///    (backtick-quoted column names are stored without their backticks)
+/// 3. Resolves contextual keywords: `lag`, `id`, `family`, etc. are column names
+///    unless they head a function call, name a `gr()` option or start the family clause
+/// 4. Returns a Parser struct with the tokens and initial position
 ///
 /// # Tokenization Process
 /// The input "y ~ x + z" would be tokenized as:
@@ -44,7 +47,7 @@ pub fn new<'a>(input: &'a str) -> Result<crate::internal::parser::Parser<'a>, Pa
     while let Some(item) = lex.next() {
         match item {
             Ok(tok) => {
-                let slice = lex.slice();
+                let slice = unquote(lex.slice());
                 tokens.push((tok, slice));
             }
             Err(()) => {
@@ -53,6 +56,8 @@ pub fn new<'a>(input: &'a str) -> Result<crate::internal::parser::Parser<'a>, Pa
         }
     }
 
+    resolve_keywords(&mut tokens);
+
     Ok(crate::internal::parser::Parser {
         input,
         tokens,
@@ -60,6 +65,98 @@ pub fn new<'a>(input: &'a str) -> Result<crate::internal::parser::Parser<'a>, Pa
     })
 }
 
+/// Turns keyword tokens that are not in a keyword position back into column names.
+///
+/// Words such as `id`, `lag` or `family` are only keywords where they have
+/// meaning: as a function-call head (`lag(x)`), as a `gr()` option name
+/// (`gr(g, id = "a")`), in the `family = gaussian` clause, which may also sit
+/// inside `bf()`, and as a family component of a model specification
+/// (`bf(y ~ x) + gaussian()`). Everywhere else, e.g. `y ~ id + by` or
+/// `lag ~ x`, they are ordinary column names.
+fn resolve_keywords(tokens: &mut [(Token, &str)]) {
+    // For each open parenthesis, the name of the call it belongs to (`None` for grouping)
+    let mut open_calls: Vec<Option<&str>> = Vec::new();
+
+    for i in 0..tokens.len() {
+        let next = tokens.get(i + 1).map(|(t, _)| t.clone());
+        let keep = match tokens[i].0 {
+            Token::Poly
+            | Token::Offset
+            | Token::Factor
+            | Token::C
+            | Token::Scale
+            | Token::Standardize
+            | Token::Center
+            | Token::Log
+            | Token::BSplines
+            | Token::NaturalSplines
+            | Token::GaussianProcess
+            | Token::Smooth
+            | Token::TensorSmooth
+            | Token::TensorInteraction
+            | Token::T2Smooth
+            | Token::Monotonic
+            | Token::MeasurementError
+            | Token::MissingValues
+            | Token::ForwardFill
+            | Token::BackwardFill
+            | Token::Diff
+            | Token::Lag
+            | Token::Lead
+            | Token::Trunc
+            | Token::Weights
+            | Token::Trials
+            | Token::Censored
+            | Token::Bind
+            | Token::Gr
+            | Token::Mm
+            | Token::Mmc
+            | Token::Cs => next == Some(Token::FunctionStart),
+            Token::Cor | Token::Id | Token::By | Token::Cov | Token::Dist => {
+                next == Some(Token::Equal) && open_calls.last() == Some(&Some("gr"))
+            }
+            Token::Family => {
+                next == Some(Token::Equal)
+                    && open_calls
+                        .iter()
+                        .all(|call| matches!(call, None | Some("bf" | "brmsformula")))
+            }
+            Token::Gaussian | Token::Binomial | Token::Poisson => {
+                let is_clause =
+                    i >= 2 && tokens[i - 1].0 == Token::Equal && tokens[i - 2].0 == Token::Family;
+                let is_component = next == Some(Token::FunctionStart)
+                    && open_calls.is_empty()
+                    && (i == 0 || tokens[i - 1].0 == Token::Plus);
+                is_clause || is_component
+            }
+            Token::FunctionStart => {
+                let name = i
+                    .checked_sub(1)
+                    .filter(|&j| crate::internal::is_call::is_call(tokens, j))
+                    .map(|j| tokens[j].1);
+                open_calls.push(name);
+                true
+            }
+            Token::FunctionEnd => {
+                open_calls.pop();
+                true
+            }
+            _ => true,
+        };
+        if !keep {
+            tokens[i].0 = Token::ColumnName;
+        }
+    }
+}
+
+/// Strips the backticks from a quoted column name such as `` `revenue 2024` ``.
+fn unquote(slice: &str) -> &str {
+    slice
+        .strip_prefix('`')
+        .and_then(|s| s.strip_suffix('`'))
+        .unwrap_or(slice)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,4 +205,37 @@ mod tests {
         assert_eq!(parser.pos, 0);
         assert_eq!(parser.tokens.len(), 0);
     }
+
+    #[test]
+    fn test_new_backtick_quoted_name() {
+        let parser = new("y ~ `revenue 2024` + Sepal.Length").unwrap();
+
+        assert_eq!(parser.tokens[2], (Token::ColumnName, "revenue 2024"));
+        assert_eq!(parser.tokens[4], (Token::ColumnName, "Sepal.Length"));
+    }
+
+    #[test]
+    fn test_new_keywords_are_contextual() {
+        let parser = new("lag ~ id + lag(x) + (1 | gr(g, by = by)), family = gaussian").unwrap();
+        let kinds: Vec<Token> = parser.tokens.iter().map(|(t, _)| t.clone()).collect();
+
+        assert_eq!(kinds[0], Token::ColumnName); // lag ~
+        assert_eq!(kinds[2], Token::ColumnName); // id
+        assert_eq!(kinds[4], Token::Lag); // lag(
+        assert_eq!(kinds[16], Token::By); // by =
+        assert_eq!(kinds[18], Token::ColumnName); // = by
+        assert_eq!(kinds[22], Token::Family);
+        assert_eq!(kinds[24], Token::Gaussian);
+    }
+
+    #[test]
+    fn test_new_model_specification_keywords() {
+        let parser = new("bf(y ~ x, family = gaussian) + poisson() + lf(gaussian ~ 1)").unwrap();
+        let kinds: Vec<Token> = parser.tokens.iter().map(|(t, _)| t.clone()).collect();
+
+        assert_eq!(kinds[6], Token::Family); // family = inside bf()
+        assert_eq!(kinds[8], Token::Gaussian);
+        assert_eq!(kinds[11], Token::Poisson); // + poisson()
+        assert_eq!(kinds[17], Token::ColumnName); // lf(gaussian ~
+    }
 }
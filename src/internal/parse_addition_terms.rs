@@ -0,0 +1,386 @@
+use crate::internal::{
+    ast::{AdditionTerm, Argument},
+    errors::ParseError,
+    lexer::Token,
+};
+
+/// Parses the response addition terms that follow `|` on the left-hand side.
+///
+/// brms-style formulas attach extra information to the response with addition
+/// terms such as `y | trials(n) ~ x` or `y | weights(w) + cens(c) ~ x`. The
+/// position should be just after the `|`.
+///
+/// # Arguments
+/// * `tokens` - Reference to the vector of tokens
+/// * `pos` - Mutable reference to the current position (will be advanced)
+///
+/// # Returns
+/// * `Result<Vec<AdditionTerm>, ParseError>` - The addition terms in formula order, or an error
+///
+/// # Example
+/// ```
+/// use fiasto::internal::parse_addition_terms::parse_addition_terms;
+/// use fiasto::internal::lexer::Token;
+/// use fiasto::internal::ast::{AdditionTerm, Argument};
+///
+/// // Parse trials(n) in y | trials(n) ~ x
+/// let tokens = vec![
+///     (Token::Trials, "trials"),
+///     (Token::FunctionStart, "("),
+///     (Token::ColumnName, "n"),
+///     (Token::FunctionEnd, ")"),
+///     (Token::Tilde, "~")
+/// ];
+/// let mut pos = 0;
+///
+/// let additions = parse_addition_terms(&tokens, &mut pos).unwrap();
+/// assert_eq!(additions, vec![AdditionTerm::Trials(Argument::Ident("n".to_string()))]);
+/// assert_eq!(pos, 4);
+/// ```
+///
+/// # How it works
+/// 1. Parses an addition function name and its argument list
+/// 2. Checks the arguments against the function (`trunc` takes `lb`/`ub`, `se` takes `sigma`, ...)
+/// 3. Repeats while the terms are joined with `+`
+/// 4. Rejects a function that appears more than once
+///
+/// # Grammar Rule
+/// ```text
+/// additions = addition ("+" addition)*
+/// addition = addition_name "(" [argument ("," argument)*] ")"
+/// addition_name = "trials" | "cens" | "weights" | "se" | "trunc" | "mi" | "dec" | "rate"
+/// ```
+///
+/// # Examples of Valid Inputs
+/// - `"trials(n)"` → [Trials(n)]
+/// - `"cens(censored, y2)"` → [Censored { censored, y2 }]
+/// - `"se(s, sigma = TRUE)"` → [Se { s, sigma: true }]
+/// - `"weights(w) + trunc(lb = 0, ub = 100)"` → [Weights { w, false }, Trunc { 0, 100 }]
+/// - `"weights(w, scale = TRUE)"` → [Weights { w, true }]
+/// - `"mi()"` → [Mi(None)]
+pub fn parse_addition_terms<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Vec<AdditionTerm>, ParseError> {
+    let mut additions: Vec<AdditionTerm> = Vec::new();
+    loop {
+        let name = crate::internal::peek::peek(tokens, *pos).map(|(_, slice)| *slice);
+        let addition = parse_addition_term(tokens, pos)?;
+        if additions
+            .iter()
+            .any(|a| std::mem::discriminant(a) == std::mem::discriminant(&addition))
+        {
+            return Err(ParseError::Syntax(format!(
+                "response addition term {}() is used more than once",
+                name.unwrap_or_default()
+            )));
+        }
+        additions.push(addition);
+        if !crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Plus)) {
+            break;
+        }
+    }
+    Ok(additions)
+}
+
+/// Parses a single addition term such as `trials(n)`.
+fn parse_addition_term<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<AdditionTerm, ParseError> {
+    let (tok, name) = crate::internal::peek::peek(tokens, *pos)
+        .cloned()
+        .ok_or(ParseError::Eoi)?;
+    if !matches!(
+        name,
+        "trials" | "cens" | "weights" | "se" | "trunc" | "mi" | "dec" | "rate"
+    ) {
+        return Err(ParseError::Unexpected {
+            expected: "response addition term (trials, cens, weights, se, trunc, mi, dec or rate)",
+            found: Some(tok),
+        });
+    }
+    *pos += 1;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionStart), "(")?;
+    let args = crate::internal::parse_arg_list::parse_arg_list(tokens, pos)?;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+
+    let (positional, named): (Vec<&Argument>, Vec<&Argument>) =
+        args.iter().partition(|a| !matches!(a, Argument::Named(..)));
+    let named_value = |key: &str| {
+        named.iter().find_map(|a| match a {
+            Argument::Named(k, value) if k == key => Some(value.as_ref().clone()),
+            _ => None,
+        })
+    };
+    if let Some(Argument::Named(key, _)) = named
+        .iter()
+        .find(|a| matches!(a, Argument::Named(k, _) if !allowed_names(name).contains(&k.as_str())))
+    {
+        return Err(ParseError::Syntax(format!(
+            "{}() has no argument named '{}'",
+            name, key
+        )));
+    }
+
+    match name {
+        "trials" => match positional.as_slice() {
+            [value @ (Argument::Ident(_) | Argument::Integer(_))] => {
+                Ok(AdditionTerm::Trials((*value).clone()))
+            }
+            _ => Err(ParseError::Syntax(
+                "trials() takes a column name or a whole number".into(),
+            )),
+        },
+        "cens" => match positional.as_slice() {
+            [Argument::Ident(variable)] => Ok(AdditionTerm::Censored {
+                variable: variable.clone(),
+                upper: None,
+            }),
+            [Argument::Ident(variable), Argument::Ident(upper)] => Ok(AdditionTerm::Censored {
+                variable: variable.clone(),
+                upper: Some(upper.clone()),
+            }),
+            _ => Err(ParseError::Syntax(
+                "cens() takes a censoring column and an optional upper bound column".into(),
+            )),
+        },
+        "se" => {
+            let sigma = match named_value("sigma") {
+                None => false,
+                Some(Argument::Boolean(sigma)) => sigma,
+                Some(_) => {
+                    return Err(ParseError::Syntax("se() option sigma must be TRUE or FALSE".into()))
+                }
+            };
+            Ok(AdditionTerm::Se {
+                variable: single_column(name, &positional)?,
+                sigma,
+            })
+        }
+        "trunc" => {
+            let mut bounds = positional.into_iter().cloned();
+            let lower = named_value("lb").or_else(|| bounds.next());
+            let upper = named_value("ub").or_else(|| bounds.next());
+            if bounds.next().is_some() || (lower.is_none() && upper.is_none()) {
+                return Err(ParseError::Syntax(
+                    "trunc() takes a lower bound lb and an upper bound ub".into(),
+                ));
+            }
+            for bound in lower.iter().chain(upper.iter()) {
+                if !matches!(
+                    bound,
+                    Argument::Ident(_) | Argument::Integer(_) | Argument::Number(_)
+                ) {
+                    return Err(ParseError::Syntax(
+                        "trunc() bounds must be numbers or column names".into(),
+                    ));
+                }
+            }
+            let number = |bound: &Option<Argument>| match bound {
+                Some(Argument::Integer(n)) => Some(f64::from(*n)),
+                Some(Argument::Number(n)) => Some(*n),
+                _ => None,
+            };
+            if let (Some(lb), Some(ub)) = (number(&lower), number(&upper)) {
+                if lb >= ub {
+                    return Err(ParseError::Syntax(format!(
+                        "trunc() lower bound {} must be below the upper bound {}",
+                        lb, ub
+                    )));
+                }
+            }
+            Ok(AdditionTerm::Trunc { lower, upper })
+        }
+        "mi" => match positional.as_slice() {
+            [] => Ok(AdditionTerm::Mi(None)),
+            _ => Ok(AdditionTerm::Mi(Some(single_column(name, &positional)?))),
+        },
+        "weights" => {
+            let scale = match named_value("scale") {
+                None => false,
+                Some(Argument::Boolean(scale)) => scale,
+                Some(_) => {
+                    return Err(ParseError::Syntax(
+                        "weights() option scale must be TRUE or FALSE".into(),
+                    ))
+                }
+            };
+            Ok(AdditionTerm::Weights {
+                variable: single_column(name, &positional)?,
+                scale,
+            })
+        }
+        "dec" => Ok(AdditionTerm::Dec(single_column(name, &positional)?)),
+        "rate" => Ok(AdditionTerm::Rate(single_column(name, &positional)?)),
+        _ => unreachable!(),
+    }
+}
+
+/// The named arguments each addition function accepts.
+fn allowed_names(name: &str) -> &'static [&'static str] {
+    match name {
+        "se" => &["sigma"],
+        "trunc" => &["lb", "ub"],
+        "weights" => &["scale"],
+        _ => &[],
+    }
+}
+
+/// Extracts the single column argument of `weights(w)`, `se(s)`, `dec(d)`, etc.
+fn single_column(name: &str, positional: &[&Argument]) -> Result<String, ParseError> {
+    match positional {
+        [Argument::Ident(variable)] => Ok(variable.clone()),
+        _ => Err(ParseError::Syntax(format!(
+            "{}() takes a single column name",
+            name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::lexer::Token;
+
+    #[test]
+    fn test_parse_addition_terms_joined_with_plus() {
+        // cens(c, y2) + weights(w)
+        let tokens = vec![
+            (Token::Censored, "cens"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "c"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "y2"),
+            (Token::FunctionEnd, ")"),
+            (Token::Plus, "+"),
+            (Token::Weights, "weights"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "w"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let additions = parse_addition_terms(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            additions,
+            vec![
+                AdditionTerm::Censored {
+                    variable: "c".to_string(),
+                    upper: Some("y2".to_string()),
+                },
+                AdditionTerm::Weights {
+                    variable: "w".to_string(),
+                    scale: false,
+                },
+            ]
+        );
+        assert_eq!(pos, 11);
+    }
+
+    #[test]
+    fn test_parse_addition_terms_scaled_weights() {
+        // weights(w, scale = TRUE)
+        let tokens = vec![
+            (Token::Weights, "weights"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "w"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "scale"),
+            (Token::Equal, "="),
+            (Token::TrueUpper, "TRUE"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let additions = parse_addition_terms(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            additions,
+            vec![AdditionTerm::Weights {
+                variable: "w".to_string(),
+                scale: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_addition_terms_named_arguments() {
+        // trunc(lb = 0, ub = 1.5)
+        let tokens = vec![
+            (Token::Trunc, "trunc"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "lb"),
+            (Token::Equal, "="),
+            (Token::Zero, "0"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "ub"),
+            (Token::Equal, "="),
+            (Token::Number, "1.5"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let additions = parse_addition_terms(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            additions,
+            vec![AdditionTerm::Trunc {
+                lower: Some(Argument::Integer(0)),
+                upper: Some(Argument::Number(1.5)),
+            }]
+        );
+
+        // se(s, sigma = TRUE)
+        let tokens = vec![
+            (Token::ColumnName, "se"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "s"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "sigma"),
+            (Token::Equal, "="),
+            (Token::TrueUpper, "TRUE"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let additions = parse_addition_terms(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            additions,
+            vec![AdditionTerm::Se {
+                variable: "s".to_string(),
+                sigma: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_addition_terms_unknown_function() {
+        let tokens = vec![
+            (Token::ColumnName, "foo"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "n"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_addition_terms(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Unexpected { .. })));
+    }
+
+    #[test]
+    fn test_parse_addition_terms_duplicate() {
+        // mi() + mi()
+        let tokens = vec![
+            (Token::MissingValues, "mi"),
+            (Token::FunctionStart, "("),
+            (Token::FunctionEnd, ")"),
+            (Token::Plus, "+"),
+            (Token::MissingValues, "mi"),
+            (Token::FunctionStart, "("),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_addition_terms(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Syntax(_))));
+    }
+}
@@ -3,7 +3,8 @@ use crate::internal::{ast::Argument, errors::ParseError, lexer::Token};
 /// Parses a single argument within a function call.
 ///
 /// This function handles individual arguments that can appear in function calls.
-/// Arguments can be column names (identifiers), integers, or the literal "1".
+/// Arguments can be column names (identifiers), numbers, strings, booleans, or
+/// named `key = value` pairs whose value is any of these.
 ///
 /// # Arguments
 /// * `tokens` - Reference to the vector of tokens
@@ -53,9 +54,11 @@ use crate::internal::{ast::Argument, errors::ParseError, lexer::Token};
 ///
 /// # Grammar Rule
 /// ```text
-/// argument = column_name | integer | "1"
+/// argument = [column_name "="] value
+/// value = function_call | column_name | number | "-" number | string | boolean
+/// function_call = name "(" [argument ("," argument)*] ")"
 /// column_name = identifier
-/// integer = [0-9]+
+/// number = [0-9]+ | decimal | scientific
 /// ```
 ///
 /// # Use Cases
@@ -68,58 +71,83 @@ use crate::internal::{ast::Argument, errors::ParseError, lexer::Token};
 /// - `"x"` → Argument::Ident("x")
 /// - `"42"` → Argument::Integer(42)
 /// - `"1"` → Argument::Integer(1)
+/// - `"0.5"` → Argument::Number(0.5)
+/// - `"-1"` → Argument::Number(-1.0)
+/// - `"lb = 0.5"` → Argument::Named("lb", Number(0.5))
+/// - `"log(x)"` → Argument::Function { name: "log", args: [Ident("x")] }
 /// - `"variable_name"` → Argument::Ident("variable_name")
 pub fn parse_arg<'a>(
     tokens: &'a [(Token, &'a str)],
     pos: &mut usize,
+) -> Result<Argument, ParseError> {
+    // Check if this is a named argument (key=value)
+    let is_named = matches!(
+        crate::internal::peek::peek(tokens, *pos),
+        Some((Token::ColumnName, _))
+    ) && matches!(
+            crate::internal::peek::peek(tokens, *pos + 1),
+            Some((Token::Equal, _))
+        );
+    if is_named {
+        let key = tokens[*pos].1.to_string();
+        *pos += 2; // Skip the key and the equals sign
+        let value = parse_value(tokens, pos)?;
+        return Ok(Argument::Named(key, Box::new(value)));
+    }
+    parse_value(tokens, pos)
+}
+
+/// Parses a positional argument or the value of a named argument.
+fn parse_value<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
 ) -> Result<Argument, ParseError> {
     if let Some((tok, slice)) = crate::internal::peek::peek(tokens, *pos).cloned() {
+        if crate::internal::is_call::is_call(tokens, *pos) {
+            // Nested function call: `log(exposure)` in `offset(log(exposure))`
+            *pos += 2; // Skip the function name and the opening parenthesis
+            let args = crate::internal::parse_arg_list::parse_arg_list(tokens, pos)?;
+            crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+            return Ok(Argument::Function {
+                name: slice.to_string(),
+                args,
+            });
+        }
         match tok {
             Token::ColumnName => {
                 crate::internal::next::next(tokens, pos);
-
-                // Check if this is a named argument (key=value)
-                if crate::internal::peek::peek(tokens, *pos)
-                    .map(|(t, _)| matches!(t, Token::Equal))
-                    .unwrap_or(false)
-                {
-                    let key = slice.to_string();
-                    crate::internal::next::next(tokens, pos); // Skip the equals sign
-
-                    // Parse the value
-                    if let Some((value_tok, value_slice)) =
-                        crate::internal::peek::peek(tokens, *pos).cloned()
-                    {
-                        match value_tok {
-                            Token::ColumnName => {
-                                crate::internal::next::next(tokens, pos);
-                                Ok(Argument::Named(key, value_slice.to_string()))
-                            }
-                            Token::StringLiteral => {
-                                crate::internal::next::next(tokens, pos);
-                                // Remove quotes from string literal
-                                let value = value_slice.trim_matches('"').to_string();
-                                Ok(Argument::Named(key, value))
-                            }
-                            _ => Err(ParseError::Unexpected {
-                                expected: "column name or string literal",
-                                found: Some(value_tok),
-                            }),
-                        }
-                    } else {
-                        Err(ParseError::Eoi)
-                    }
-                } else {
-                    Ok(Argument::Ident(slice.to_string()))
-                }
+                Ok(Argument::Ident(slice.to_string()))
             }
-            Token::Integer => {
+            Token::Integer | Token::One | Token::Zero => {
                 crate::internal::next::next(tokens, pos);
-                Ok(Argument::Integer(slice.parse().unwrap()))
+                let value = slice.parse().map_err(|_| {
+                    ParseError::Syntax(format!("integer '{}' is too large", slice))
+                })?;
+                Ok(Argument::Integer(value))
             }
-            Token::One => {
+            Token::Number => {
                 crate::internal::next::next(tokens, pos);
-                Ok(Argument::Integer(1))
+                Ok(Argument::Number(slice.parse().unwrap()))
+            }
+            Token::Minus => {
+                // Negative numbers: `-1`, `-0.5`
+                let mut number_pos = *pos + 1;
+                let (_, number) = crate::internal::expect::expect(
+                    tokens,
+                    &mut number_pos,
+                    |t| matches!(t, Token::Integer | Token::One | Token::Zero | Token::Number),
+                    "number after '-'",
+                )?;
+                *pos = number_pos;
+                Ok(Argument::Number(-number.parse::<f64>().unwrap()))
+            }
+            Token::True | Token::TrueUpper => {
+                crate::internal::next::next(tokens, pos);
+                Ok(Argument::Boolean(true))
+            }
+            Token::False | Token::FalseUpper => {
+                crate::internal::next::next(tokens, pos);
+                Ok(Argument::Boolean(false))
             }
             Token::StringLiteral => {
                 crate::internal::next::next(tokens, pos);
@@ -300,4 +328,86 @@ mod tests {
             _ => panic!("Expected integer argument"),
         }
     }
+
+    #[test]
+    fn test_parse_arg_decimal_number() {
+        let tokens = vec![(Token::Number, "0.5")];
+        let mut pos = 0;
+
+        let result = parse_arg(&tokens, &mut pos);
+        assert_eq!(result.unwrap(), Argument::Number(0.5));
+        assert_eq!(pos, 1);
+    }
+
+    #[test]
+    fn test_parse_arg_negative_number() {
+        let tokens = vec![(Token::Minus, "-"), (Token::One, "1")];
+        let mut pos = 0;
+
+        let result = parse_arg(&tokens, &mut pos);
+        assert_eq!(result.unwrap(), Argument::Number(-1.0));
+        assert_eq!(pos, 2);
+    }
+
+    #[test]
+    fn test_parse_arg_minus_without_number() {
+        let tokens = vec![(Token::Minus, "-"), (Token::ColumnName, "x")];
+        let mut pos = 0;
+
+        let result = parse_arg(&tokens, &mut pos);
+        assert!(result.is_err());
+        assert_eq!(pos, 0); // Position unchanged
+    }
+
+    #[test]
+    fn test_parse_arg_named_number() {
+        let tokens = vec![
+            (Token::ColumnName, "lb"),
+            (Token::Equal, "="),
+            (Token::Number, "1e-3"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_arg(&tokens, &mut pos);
+        assert_eq!(
+            result.unwrap(),
+            Argument::Named("lb".to_string(), Box::new(Argument::Number(0.001)))
+        );
+        assert_eq!(pos, 3);
+    }
+
+    #[test]
+    fn test_parse_arg_nested_function_call() {
+        // offset(log(exposure)) → the argument log(exposure)
+        let tokens = vec![
+            (Token::Log, "log"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "exposure"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_arg(&tokens, &mut pos);
+        assert_eq!(
+            result.unwrap(),
+            Argument::Function {
+                name: "log".to_string(),
+                args: vec![Argument::Ident("exposure".to_string())],
+            }
+        );
+        assert_eq!(pos, 4);
+    }
+
+    #[test]
+    fn test_parse_arg_unclosed_nested_call() {
+        let tokens = vec![
+            (Token::Scale, "scale"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_arg(&tokens, &mut pos);
+        assert!(result.is_err());
+    }
 }
@@ -42,7 +42,7 @@ use crate::internal::{ast::Argument, errors::ParseError, lexer::Token};
 /// # Grammar Rule
 /// ```text
 /// arg_list = [argument ("," argument)*]
-/// argument = column_name | integer | "1"
+/// argument = [column_name "="] value   (see parse_arg)
 /// ```
 ///
 /// # Use Cases
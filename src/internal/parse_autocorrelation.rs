@@ -0,0 +1,400 @@
+use crate::internal::{
+    ast::{Argument, Autocorrelation},
+    errors::ParseError,
+    lexer::Token,
+};
+
+/// Parses an autocorrelation structure such as `ar(time, g)` or `cor_ar(~ 1 | g)`.
+///
+/// The structures are the brms autocorrelation terms `ar`, `ma`, `arma`,
+/// `cosy`, `car` and `sar`, written on the right-hand side of a formula, and
+/// the older `cor_ar`, `cor_ma`, `cor_arma`, `cor_cosy`, `cor_car` and `cor_sar`
+/// components that give the time and grouping variables as a one-sided formula.
+///
+/// # Arguments
+/// * `tokens` - Reference to the vector of tokens
+/// * `pos` - Mutable reference to the current position (will be advanced)
+///
+/// # Returns
+/// * `Result<Autocorrelation, ParseError>` - The parsed structure with every argument resolved, or an error
+///
+/// # Example
+/// ```
+/// use fiasto::internal::parse_autocorrelation::parse_autocorrelation;
+/// use fiasto::internal::lexer::Token;
+/// use fiasto::internal::ast::Autocorrelation;
+///
+/// // Parse cor_ar(~1|g)
+/// let tokens = vec![
+///     (Token::ColumnName, "cor_ar"),
+///     (Token::FunctionStart, "("),
+///     (Token::Tilde, "~"),
+///     (Token::One, "1"),
+///     (Token::Pipe, "|"),
+///     (Token::ColumnName, "g"),
+///     (Token::FunctionEnd, ")")
+/// ];
+/// let mut pos = 0;
+///
+/// let autocorrelation = parse_autocorrelation(&tokens, &mut pos).unwrap();
+/// assert_eq!(
+///     autocorrelation,
+///     Autocorrelation::Arma { time: None, gr: Some("g".to_string()), p: 1, q: 0, cov: false }
+/// );
+/// assert_eq!(pos, 7);
+/// ```
+///
+/// # How it works
+/// 1. Expects a structure name followed by `(`
+/// 2. Parses the arguments, positional or named; a `~ time | group` formula
+///    gives the time and grouping variables of the `cor_` forms
+/// 3. Matches positional arguments to the structure's arguments in order
+/// 4. Checks each value and fills in the brms defaults (`p = 1` for `ar()`,
+///    `type = "escar"` for `car()`, ...)
+///
+/// # Grammar Rule
+/// ```text
+/// autocorrelation = name "(" [argument ("," argument)*] ")"
+/// name = "ar" | "ma" | "arma" | "cosy" | "car" | "sar"
+///      | "cor_ar" | "cor_ma" | "cor_arma" | "cor_cosy" | "cor_car" | "cor_sar"
+/// argument = [argument_name "="] (value | "~" ("1" | column_name) ["|" column_name])
+/// ```
+///
+/// # Examples of Valid Inputs
+/// - `"ar(time, g)"` → Arma { time: Some(time), gr: Some(g), p: 1, q: 0, cov: false }
+/// - `"arma(gr = g, p = 2, q = 1)"` → Arma { time: None, gr: Some(g), p: 2, q: 1, cov: false }
+/// - `"cosy(time = t)"` → Cosy { time: Some(t), gr: None }
+/// - `"car(M, type = \"icar\")"` → Car { matrix: M, gr: None, kind: icar }
+/// - `"cor_ma(~ t | g, q = 2)"` → Arma { time: Some(t), gr: Some(g), p: 0, q: 2, cov: false }
+pub fn parse_autocorrelation<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Autocorrelation, ParseError> {
+    let (tok, name) = crate::internal::peek::peek(tokens, *pos)
+        .cloned()
+        .ok_or(ParseError::Eoi)?;
+    let Some(names) = argument_names(name) else {
+        return Err(ParseError::Unexpected {
+            expected: "autocorrelation structure (ar, ma, arma, cosy, car or sar)",
+            found: Some(tok),
+        });
+    };
+    *pos += 1;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionStart), "(")?;
+
+    // Each argument is matched to its name, by position unless it is named
+    let mut values: Vec<(&str, Value)> = Vec::new();
+    let mut next_positional = 0;
+    if !matches!(
+        crate::internal::peek::peek(tokens, *pos),
+        Some((Token::FunctionEnd, _))
+    ) {
+        loop {
+            let is_named = matches!(
+                crate::internal::peek::peek(tokens, *pos + 1),
+                Some((Token::Equal, _))
+            );
+            let argument = if is_named {
+                let key = tokens[*pos].1;
+                let Some(argument) = names.iter().find(|n| **n == key) else {
+                    return Err(ParseError::Syntax(format!(
+                        "{}() has no argument named '{}'",
+                        name, key
+                    )));
+                };
+                *pos += 2;
+                *argument
+            } else {
+                let Some(argument) = names.get(next_positional) else {
+                    return Err(ParseError::Syntax(format!(
+                        "{}() takes at most {} arguments",
+                        name,
+                        names.len()
+                    )));
+                };
+                next_positional += 1;
+                *argument
+            };
+            if values.iter().any(|(n, _)| *n == argument) {
+                return Err(ParseError::Syntax(format!(
+                    "{}() argument '{}' is given more than once",
+                    name, argument
+                )));
+            }
+            values.push((argument, parse_value(tokens, pos)?));
+            if !crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Comma)) {
+                break;
+            }
+        }
+    }
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+
+    let value = |argument: &str| values.iter().find(|(n, _)| *n == argument).map(|(_, v)| v);
+    // The `cor_` forms give the time and grouping variables as `~ time | gr`
+    let (time, gr) = match value("formula") {
+        Some(Value::Formula { time, gr }) => (time.clone(), gr.clone()),
+        Some(Value::Argument(_)) => {
+            return Err(ParseError::Syntax(format!(
+                "{}() argument formula must be a formula such as ~ 1 | g",
+                name
+            )))
+        }
+        None => (
+            variable(name, "time", value("time"))?,
+            variable(name, "gr", value("gr"))?,
+        ),
+    };
+    let order = |argument: &str, default: u32| match value(argument) {
+        None => Ok(default),
+        Some(Value::Argument(Argument::Integer(n))) => Ok(*n),
+        Some(_) => Err(ParseError::Syntax(format!(
+            "{}() argument {} must be a whole number",
+            name, argument
+        ))),
+    };
+    let cov = match value("cov") {
+        None => false,
+        Some(Value::Argument(Argument::Boolean(cov))) => *cov,
+        Some(_) => {
+            return Err(ParseError::Syntax(format!(
+                "{}() argument cov must be TRUE or FALSE",
+                name
+            )))
+        }
+    };
+
+    match name.trim_start_matches("cor_") {
+        "ar" | "ma" | "arma" => {
+            let (p, q) = match name {
+                "ar" | "cor_ar" => (order("p", 1)?, 0),
+                "ma" | "cor_ma" => (0, order("q", 1)?),
+                "arma" => (order("p", 1)?, order("q", 1)?),
+                _ => (order("p", 0)?, order("q", 0)?),
+            };
+            if p == 0 && q == 0 {
+                return Err(ParseError::Syntax(format!(
+                    "{}() needs an order p or q greater than zero",
+                    name
+                )));
+            }
+            Ok(Autocorrelation::Arma { time, gr, p, q, cov })
+        }
+        "cosy" => Ok(Autocorrelation::Cosy { time, gr }),
+        "car" => Ok(Autocorrelation::Car {
+            matrix: matrix(name, value(names[0]))?,
+            gr,
+            kind: kind(name, value("type"), &["escar", "esicar", "icar", "bym2"])?,
+        }),
+        "sar" => Ok(Autocorrelation::Sar {
+            matrix: matrix(name, value(names[0]))?,
+            kind: kind(name, value("type"), &["lag", "error"])?,
+        }),
+        _ => unreachable!(),
+    }
+}
+
+/// An argument value: a plain argument or the `~ time | gr` formula of a `cor_` form
+enum Value {
+    Argument(Argument),
+    Formula {
+        time: Option<String>,
+        gr: Option<String>,
+    },
+}
+
+/// The arguments of each structure in positional order, `None` for other names.
+fn argument_names(name: &str) -> Option<&'static [&'static str]> {
+    let names: &[&str] = match name {
+        "ar" => &["time", "gr", "p", "cov"],
+        "ma" => &["time", "gr", "q", "cov"],
+        "arma" => &["time", "gr", "p", "q", "cov"],
+        "cosy" => &["time", "gr"],
+        "car" => &["M", "gr", "type"],
+        "sar" => &["M", "type"],
+        "cor_ar" => &["formula", "p", "cov"],
+        "cor_ma" => &["formula", "q", "cov"],
+        "cor_arma" => &["formula", "p", "q", "cov"],
+        "cor_cosy" => &["formula"],
+        "cor_car" => &["W", "formula", "type"],
+        "cor_sar" => &["W", "type"],
+        _ => return None,
+    };
+    Some(names)
+}
+
+/// Parses an argument value, or a `~ time | gr` formula.
+fn parse_value<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result<Value, ParseError> {
+    if !crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Tilde)) {
+        return Ok(Value::Argument(crate::internal::parse_arg::parse_arg(tokens, pos)?));
+    }
+    let (tok, slice) = crate::internal::expect::expect(
+        tokens,
+        pos,
+        |t| matches!(t, Token::One | Token::ColumnName),
+        "1 or a time variable",
+    )?;
+    let time = matches!(tok, Token::ColumnName).then(|| slice.to_string());
+    let gr = if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Pipe)) {
+        let (_, slice) = crate::internal::expect::expect(
+            tokens,
+            pos,
+            |t| matches!(t, Token::ColumnName),
+            "grouping variable",
+        )?;
+        Some(slice.to_string())
+    } else {
+        None
+    };
+    Ok(Value::Formula { time, gr })
+}
+
+/// Resolves the `time` or `gr` argument: a column name, or `NA` for none.
+fn variable(name: &str, argument: &str, value: Option<&Value>) -> Result<Option<String>, ParseError> {
+    match value {
+        None => Ok(None),
+        Some(Value::Argument(Argument::Ident(column))) if column == "NA" => Ok(None),
+        Some(Value::Argument(Argument::Ident(column))) => Ok(Some(column.clone())),
+        Some(_) => Err(ParseError::Syntax(format!(
+            "{}() argument {} must be a column name or NA",
+            name, argument
+        ))),
+    }
+}
+
+/// Resolves the required matrix argument of `car()` and `sar()`.
+fn matrix(name: &str, value: Option<&Value>) -> Result<String, ParseError> {
+    match value {
+        Some(Value::Argument(Argument::Ident(matrix))) => Ok(matrix.clone()),
+        _ => Err(ParseError::Syntax(format!(
+            "{}() needs the name of a matrix as its first argument",
+            name
+        ))),
+    }
+}
+
+/// Resolves the `type` argument against the kinds a structure supports; the first is the default.
+fn kind(name: &str, value: Option<&Value>, kinds: &[&str]) -> Result<String, ParseError> {
+    match value {
+        None => Ok(kinds[0].to_string()),
+        Some(Value::Argument(Argument::String(kind))) if kinds.contains(&kind.as_str()) => {
+            Ok(kind.clone())
+        }
+        Some(_) => Err(ParseError::Syntax(format!(
+            "{}() argument type must be one of {}",
+            name,
+            kinds
+                .iter()
+                .map(|k| format!("\"{}\"", k))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::lexer::Token;
+
+    #[test]
+    fn test_parse_autocorrelation_positional_and_named() {
+        // arma(t, g, q = 2)
+        let tokens = vec![
+            (Token::ColumnName, "arma"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "t"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "g"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "q"),
+            (Token::Equal, "="),
+            (Token::Integer, "2"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let autocorrelation = parse_autocorrelation(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            autocorrelation,
+            Autocorrelation::Arma {
+                time: Some("t".to_string()),
+                gr: Some("g".to_string()),
+                p: 1,
+                q: 2,
+                cov: false,
+            }
+        );
+        assert_eq!(pos, 10);
+    }
+
+    #[test]
+    fn test_parse_autocorrelation_spatial() {
+        // car(M, type = "icar")
+        let tokens = vec![
+            (Token::ColumnName, "car"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "M"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "type"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"icar\""),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let autocorrelation = parse_autocorrelation(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            autocorrelation,
+            Autocorrelation::Car {
+                matrix: "M".to_string(),
+                gr: None,
+                kind: "icar".to_string(),
+            }
+        );
+
+        // sar() needs a weights matrix
+        let tokens = vec![
+            (Token::ColumnName, "sar"),
+            (Token::FunctionStart, "("),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(
+            parse_autocorrelation(&tokens, &mut pos),
+            Err(ParseError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_autocorrelation_invalid_arguments() {
+        // ar(p = 0.5): the order must be a whole number
+        let tokens = vec![
+            (Token::ColumnName, "ar"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "p"),
+            (Token::Equal, "="),
+            (Token::Number, "0.5"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(
+            parse_autocorrelation(&tokens, &mut pos),
+            Err(ParseError::Syntax(_))
+        ));
+
+        // cosy(lag = 1): cosy() has no lag argument
+        let tokens = vec![
+            (Token::ColumnName, "cosy"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "lag"),
+            (Token::Equal, "="),
+            (Token::One, "1"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(
+            parse_autocorrelation(&tokens, &mut pos),
+            Err(ParseError::Syntax(_))
+        ));
+    }
+}
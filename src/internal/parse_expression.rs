@@ -0,0 +1,313 @@
+use crate::internal::{
+    ast::{BinaryOperator, Expression},
+    errors::ParseError,
+    lexer::Token,
+};
+
+/// Parses an arithmetic expression, the sub-grammar used inside `I()`.
+///
+/// Within `I()` the usual formula operators are arithmetic: `+` adds, `*`
+/// multiplies and `^` raises to a power. Expressions may contain column names,
+/// numeric literals, unary minus, parentheses and nested function calls.
+///
+/// # Arguments
+/// * `tokens` - Reference to the vector of tokens
+/// * `pos` - Mutable reference to the current position (will be advanced)
+///
+/// # Returns
+/// * `Result<Expression, ParseError>` - The expression tree, or an error
+///
+/// # Example
+/// ```
+/// use fiasto::internal::parse_expression::parse_expression;
+/// use fiasto::internal::lexer::Token;
+/// use fiasto::internal::ast::{BinaryOperator, Expression};
+///
+/// // Parse x^2
+/// let tokens = vec![
+///     (Token::ColumnName, "x"),
+///     (Token::Caret, "^"),
+///     (Token::Integer, "2")
+/// ];
+/// let mut pos = 0;
+///
+/// let result = parse_expression(&tokens, &mut pos);
+/// assert!(result.is_ok());
+/// match result.unwrap() {
+///     Expression::Binary { operator, .. } => assert_eq!(operator, BinaryOperator::Power),
+///     _ => panic!("Expected binary expression")
+/// }
+/// assert_eq!(pos, 3);
+/// ```
+///
+/// # How it works
+/// 1. Parses sums and differences, which bind loosest
+/// 2. Parses products and quotients
+/// 3. Parses unary minus
+/// 4. Parses powers, which are right-associative (`2^3^2` is `2^(3^2)`)
+/// 5. Parses numbers, columns, function calls and parenthesised expressions
+///
+/// # Grammar Rule
+/// ```text
+/// expression = product (("+" | "-") product)*
+/// product = unary (("*" | "/") unary)*
+/// unary = "-" unary | power
+/// power = primary ["^" unary]
+/// primary = number | column_name | function_call | "(" expression ")"
+/// function_call = name "(" [expression ("," expression)*] ")"
+/// ```
+///
+/// # Examples of Valid Inputs
+/// - `"x^2"` → Binary { Power, x, 2 }
+/// - `"x + z"` → Binary { Add, x, z }
+/// - `"price / 2000"` → Binary { Divide, price, 2000 }
+/// - `"-log(x)"` → Negate(Call { log, [x] })
+pub fn parse_expression<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Expression, ParseError> {
+    let mut expression = parse_product(tokens, pos)?;
+    loop {
+        let operator = match crate::internal::peek::peek(tokens, *pos) {
+            Some((Token::Plus, _)) => BinaryOperator::Add,
+            Some((Token::Minus, _)) => BinaryOperator::Subtract,
+            _ => break,
+        };
+        *pos += 1;
+        let right = parse_product(tokens, pos)?;
+        expression = Expression::Binary {
+            operator,
+            left: Box::new(expression),
+            right: Box::new(right),
+        };
+    }
+    Ok(expression)
+}
+
+/// Parses a left-associative chain of `*` and `/`.
+fn parse_product<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Expression, ParseError> {
+    let mut expression = parse_unary(tokens, pos)?;
+    loop {
+        let operator = match crate::internal::peek::peek(tokens, *pos) {
+            Some((Token::InteractionAndEffect, _)) => BinaryOperator::Multiply,
+            Some((Token::Slash, _)) => BinaryOperator::Divide,
+            _ => break,
+        };
+        *pos += 1;
+        let right = parse_unary(tokens, pos)?;
+        expression = Expression::Binary {
+            operator,
+            left: Box::new(expression),
+            right: Box::new(right),
+        };
+    }
+    Ok(expression)
+}
+
+/// Parses unary minus, which binds looser than `^` (`-x^2` is `-(x^2)`).
+fn parse_unary<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Expression, ParseError> {
+    if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Minus)) {
+        let operand = parse_unary(tokens, pos)?;
+        return Ok(Expression::Negate(Box::new(operand)));
+    }
+    parse_power(tokens, pos)
+}
+
+/// Parses a right-associative `^`.
+fn parse_power<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Expression, ParseError> {
+    let base = parse_primary(tokens, pos)?;
+    if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Caret)) {
+        let exponent = parse_unary(tokens, pos)?;
+        return Ok(Expression::Binary {
+            operator: BinaryOperator::Power,
+            left: Box::new(base),
+            right: Box::new(exponent),
+        });
+    }
+    Ok(base)
+}
+
+/// Parses a number, column, function call or parenthesised expression.
+fn parse_primary<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Expression, ParseError> {
+    let (tok, slice) = crate::internal::peek::peek(tokens, *pos)
+        .cloned()
+        .ok_or(ParseError::Eoi)?;
+
+    match tok {
+        Token::Integer | Token::Number | Token::One | Token::Zero => {
+            *pos += 1;
+            slice
+                .parse()
+                .map(Expression::Number)
+                .map_err(|_| ParseError::Syntax(format!("invalid number '{}'", slice)))
+        }
+        Token::FunctionStart => {
+            *pos += 1;
+            let expression = parse_expression(tokens, pos)?;
+            crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+            Ok(expression)
+        }
+        _ if crate::internal::is_call::is_call(tokens, *pos) => {
+            *pos += 2;
+            let mut args = Vec::new();
+            if !crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::FunctionEnd))
+            {
+                args.push(parse_expression(tokens, pos)?);
+                while crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Comma)) {
+                    args.push(parse_expression(tokens, pos)?);
+                }
+                crate::internal::expect::expect(
+                    tokens,
+                    pos,
+                    |t| matches!(t, Token::FunctionEnd),
+                    ")",
+                )?;
+            }
+            Ok(Expression::Call {
+                name: slice.to_string(),
+                args,
+            })
+        }
+        Token::ColumnName => {
+            *pos += 1;
+            Ok(Expression::Column(slice.to_string()))
+        }
+        _ => Err(ParseError::Unexpected {
+            expected: "number, column name, function call or '('",
+            found: Some(tok),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::lexer::Token;
+
+    #[test]
+    fn test_parse_expression_power() {
+        let tokens = vec![
+            (Token::ColumnName, "x"),
+            (Token::Caret, "^"),
+            (Token::Integer, "2"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_expression(&tokens, &mut pos);
+        assert_eq!(
+            result.unwrap(),
+            Expression::Binary {
+                operator: BinaryOperator::Power,
+                left: Box::new(Expression::Column("x".to_string())),
+                right: Box::new(Expression::Number(2.0)),
+            }
+        );
+        assert_eq!(pos, 3);
+    }
+
+    #[test]
+    fn test_parse_expression_precedence() {
+        // x + z * 2 → x + (z * 2)
+        let tokens = vec![
+            (Token::ColumnName, "x"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "z"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::Integer, "2"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_expression(&tokens, &mut pos).unwrap();
+        match result {
+            Expression::Binary {
+                operator, right, ..
+            } => {
+                assert_eq!(operator, BinaryOperator::Add);
+                assert!(matches!(
+                    *right,
+                    Expression::Binary {
+                        operator: BinaryOperator::Multiply,
+                        ..
+                    }
+                ));
+            }
+            _ => panic!("Expected binary expression"),
+        }
+        assert_eq!(pos, 5);
+    }
+
+    #[test]
+    fn test_parse_expression_unary_minus_and_call() {
+        // -log(x)
+        let tokens = vec![
+            (Token::Minus, "-"),
+            (Token::Log, "log"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_expression(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            result,
+            Expression::Negate(Box::new(Expression::Call {
+                name: "log".to_string(),
+                args: vec![Expression::Column("x".to_string())],
+            }))
+        );
+        assert_eq!(pos, 5);
+    }
+
+    #[test]
+    fn test_parse_expression_parentheses() {
+        // (x + z) / 2
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "z"),
+            (Token::FunctionEnd, ")"),
+            (Token::Slash, "/"),
+            (Token::Integer, "2"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_expression(&tokens, &mut pos).unwrap();
+        match result {
+            Expression::Binary { operator, left, .. } => {
+                assert_eq!(operator, BinaryOperator::Divide);
+                assert!(matches!(
+                    *left,
+                    Expression::Binary {
+                        operator: BinaryOperator::Add,
+                        ..
+                    }
+                ));
+            }
+            _ => panic!("Expected binary expression"),
+        }
+        assert_eq!(pos, 7);
+    }
+
+    #[test]
+    fn test_parse_expression_missing_operand() {
+        let tokens = vec![(Token::ColumnName, "x"), (Token::Plus, "+")];
+        let mut pos = 0;
+
+        let result = parse_expression(&tokens, &mut pos);
+        assert!(result.is_err());
+    }
+}
@@ -1,79 +1,425 @@
-use crate::internal::{ast::Family, errors::ParseError, lexer::Token};
+use crate::internal::{
+    ast::{Argument, Family, FamilySpec},
+    errors::ParseError,
+    lexer::Token,
+};
 
 /// Parses a family specification for statistical models.
-/// 
-/// This function handles the family parameter that specifies the distribution
-/// family for generalized linear models. It supports the three standard families:
-/// Gaussian (normal), Binomial, and Poisson.
-/// 
+///
+/// This function handles the family that specifies the response distribution,
+/// either as a bare name (`family = poisson`) or as a call with link
+/// functions (`family = binomial(link = "probit")`, `+ student()`). It supports
+/// the brms families, from `gaussian`, `binomial` and `poisson` to `Gamma`,
+/// `beta`, the ordinal families and the zero-inflated and hurdle families, and
+/// mixtures of them such as `mixture(gaussian, gaussian)`.
+///
 /// # Arguments
 /// * `tokens` - Reference to the vector of tokens
 /// * `pos` - Mutable reference to the current position (will be advanced)
-/// 
+///
 /// # Returns
-/// * `Result<Family, ParseError>` - The parsed family, or an error
-/// 
+/// * `Result<FamilySpec, ParseError>` - The parsed family with the link of each parameter, or an error
+///
 /// # Example
 /// ```
 /// use fiasto::internal::parse_family::parse_family;
 /// use fiasto::internal::lexer::Token;
 /// use fiasto::internal::ast::Family;
-/// 
-/// // Parse Gaussian family
+///
+/// // Parse binomial(link = "probit")
 /// let tokens = vec![
-///     (Token::Gaussian, "gaussian")
+///     (Token::Binomial, "binomial"),
+///     (Token::FunctionStart, "("),
+///     (Token::ColumnName, "link"),
+///     (Token::Equal, "="),
+///     (Token::StringLiteral, "\"probit\""),
+///     (Token::FunctionEnd, ")")
 /// ];
 /// let mut pos = 0;
-/// 
-/// let result = parse_family(&tokens, &mut pos);
-/// assert!(result.is_ok());
-/// assert_eq!(result.unwrap(), Family::Gaussian);
-/// assert_eq!(pos, 1);
+///
+/// let spec = parse_family(&tokens, &mut pos).unwrap();
+/// assert_eq!(spec.family, Family::Binomial);
+/// assert_eq!(spec.links, vec![("mu".to_string(), "probit".to_string())]);
+/// assert_eq!(pos, 6);
 /// ```
-/// 
+///
 /// # How it works
-/// 1. Expects one of the valid family tokens: Gaussian, Binomial, or Poisson
-/// 2. Maps the token to the corresponding Family enum variant
-/// 3. Advances position and returns the parsed family
-/// 4. Returns error for invalid family specifications
-/// 
+/// 1. Expects a family name: a `gaussian`, `binomial` or `poisson` token, or a
+///    column name such as `student` or `zero_inflated_poisson`
+/// 2. Parses the optional argument list: the `mu` link as the first positional
+///    argument or `link = "..."`, and `link_<dpar> = "..."` for other parameters
+/// 3. Checks each link against the links the parameter supports
+/// 4. Fills in the default link of every parameter not given a link
+/// 5. For `mixture(...)`, parses each component as a family and numbers its parameters
+///
 /// # Grammar Rule
 /// ```text
-/// family = "gaussian" | "binomial" | "poisson"
+/// family = family_name ["(" [link_arg ("," link_arg)*] ")"]
+///        | "mixture" "(" mixture_arg ("," mixture_arg)* ")"
+/// mixture_arg = family | "nmix" "=" integer
+/// link_arg = link | "link" "=" link | "link_" dpar "=" link
 /// ```
-/// 
+///
 /// # Use Cases
 /// - Specifying distribution families for GLMs
-/// - Supporting different model types (linear, logistic, count)
-/// - Validating family specifications in formulas
+/// - Supporting different model types (linear, logistic, count, ordinal, ...)
+/// - Validating family specifications and link functions in formulas
 /// - Building complete model specifications
-/// 
+///
 /// # Examples of Valid Inputs
-/// - `"gaussian"` → Family::Gaussian
-/// - `"binomial"` → Family::Binomial
-/// - `"poisson"` → Family::Poisson
-/// 
+/// - `"gaussian"` → Gaussian with mu: identity, sigma: log
+/// - `"binomial(link = \"probit\")"` → Binomial with mu: probit
+/// - `"student(link_sigma = \"identity\")"` → Student with mu: identity, sigma: identity, nu: logm1
+/// - `"zero_inflated_poisson()"` → ZeroInflatedPoisson with mu: log, zi: logit
+/// - `"mixture(gaussian, gaussian)"` → Mixture([Gaussian, Gaussian]) with mu1, sigma1, mu2, sigma2, theta1, theta2
+///
 /// # Statistical Context
 /// - **Gaussian**: Normal distribution, used for continuous response variables
 /// - **Binomial**: Used for binary/categorical response variables
 /// - **Poisson**: Used for count response variables
+/// - **Zero-inflated and hurdle families**: Count or positive data with excess zeros (`zi`, `hu`)
 pub fn parse_family<'a>(
     tokens: &'a [(Token, &'a str)],
     pos: &mut usize,
-) -> Result<Family, ParseError> {
-    let (tok, _) = crate::internal::expect::expect(
-        tokens,
-        pos,
-        |t| matches!(t, Token::Gaussian | Token::Binomial | Token::Poisson),
-        "gaussian | binomial | poisson",
-    )?;
-    let fam = match tok {
+) -> Result<FamilySpec, ParseError> {
+    let (tok, name) = crate::internal::peek::peek(tokens, *pos)
+        .cloned()
+        .ok_or(ParseError::Eoi)?;
+    if tok == Token::ColumnName && name == "mixture" {
+        *pos += 1;
+        return parse_mixture(tokens, pos);
+    }
+    let family = match tok {
         Token::Gaussian => Family::Gaussian,
         Token::Binomial => Family::Binomial,
         Token::Poisson => Family::Poisson,
-        _ => unreachable!(),
+        Token::ColumnName => family_by_name(name).ok_or(ParseError::Unexpected {
+            expected: "family name",
+            found: Some(Token::ColumnName),
+        })?,
+        _ => {
+            return Err(ParseError::Unexpected {
+                expected: "family name",
+                found: Some(tok),
+            })
+        }
     };
-    Ok(fam)
+    *pos += 1;
+
+    let parameters = parameters(&family);
+    let FamilySpec { family, mut links } = FamilySpec::new(family);
+    if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::FunctionStart)) {
+        let args = crate::internal::parse_arg_list::parse_arg_list(tokens, pos)?;
+        crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+        let mut linked: Vec<&str> = Vec::new();
+        for (i, arg) in args.iter().enumerate() {
+            let (dpar, value) = match arg {
+                Argument::Named(key, value) if key == "link" => ("mu", value.as_ref()),
+                Argument::Named(key, value) => match key.strip_prefix("link_") {
+                    Some(dpar) => (dpar, value.as_ref()),
+                    None => {
+                        return Err(ParseError::Syntax(format!(
+                            "{}() has no argument named '{}'",
+                            family.name(),
+                            key
+                        )))
+                    }
+                },
+                value if i == 0 => ("mu", value),
+                _ => {
+                    return Err(ParseError::Syntax(format!(
+                        "{}() takes link functions as link = \"...\" or link_<dpar> = \"...\"",
+                        family.name()
+                    )))
+                }
+            };
+            let link = match value {
+                Argument::String(link) | Argument::Ident(link) => link,
+                _ => {
+                    return Err(ParseError::Syntax(format!(
+                        "the link of {}() must be a name such as \"log\"",
+                        family.name()
+                    )))
+                }
+            };
+            let Some((_, allowed)) = parameters.iter().find(|(name, _)| *name == dpar) else {
+                return Err(ParseError::Syntax(format!(
+                    "{}() has no parameter '{}'",
+                    family.name(),
+                    dpar
+                )));
+            };
+            if !allowed.contains(&link.as_str()) {
+                return Err(ParseError::Syntax(format!(
+                    "{}() does not support the link '{}' for {}; use {}",
+                    family.name(),
+                    link,
+                    dpar,
+                    allowed.join(", ")
+                )));
+            }
+            if linked.contains(&dpar) {
+                return Err(ParseError::Syntax(format!(
+                    "{}() is given more than one link for {}",
+                    family.name(),
+                    dpar
+                )));
+            }
+            linked.push(dpar);
+            if let Some(entry) = links.iter_mut().find(|(name, _)| name == dpar) {
+                entry.1 = link.clone();
+            }
+        }
+    }
+    Ok(FamilySpec { family, links })
+}
+
+/// The largest number of components of a mixture family
+const MAX_MIXTURE_COMPONENTS: usize = 100;
+
+/// Parses the components of `mixture(gaussian, student)` after the name.
+///
+/// `nmix = k` repeats each component `k` times, so `mixture(gaussian, nmix = 3)`
+/// has three Gaussian components. A mixture has at most 100 components. The parameters of component `k` get the
+/// index `k` (`mu1`, `sigma1`, `mu2`, ...), followed by one mixing proportion
+/// per component (`theta1`, `theta2`, ...).
+fn parse_mixture<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<FamilySpec, ParseError> {
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionStart), "(")?;
+    let mut components = Vec::new();
+    let mut nmix = 1;
+    loop {
+        match crate::internal::peek::peek(tokens, *pos) {
+            Some((Token::ColumnName, "nmix"))
+                if matches!(tokens.get(*pos + 1), Some((Token::Equal, _))) =>
+            {
+                *pos += 2;
+                let (_, count) = crate::internal::expect::expect(
+                    tokens,
+                    pos,
+                    |t| matches!(t, Token::One | Token::Integer),
+                    "number of components",
+                )?;
+                nmix = count.parse().map_err(|_| {
+                    ParseError::Syntax(format!("mixture() nmix {} is too large", count))
+                })?;
+            }
+            _ => {
+                let component = parse_family(tokens, pos)?;
+                if matches!(component.family, Family::Mixture(_)) {
+                    return Err(ParseError::Syntax(
+                        "mixture() components cannot be mixtures".into(),
+                    ));
+                }
+                components.push(component);
+            }
+        }
+        if !crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Comma)) {
+            break;
+        }
+    }
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+
+    if components
+        .len()
+        .checked_mul(nmix)
+        .is_none_or(|count| count > MAX_MIXTURE_COMPONENTS)
+    {
+        return Err(ParseError::Syntax(format!(
+            "mixture() supports at most {} components",
+            MAX_MIXTURE_COMPONENTS
+        )));
+    }
+    let components: Vec<FamilySpec> = components
+        .into_iter()
+        .flat_map(|component| std::iter::repeat_n(component, nmix))
+        .collect();
+    if components.len() < 2 {
+        return Err(ParseError::Syntax(
+            "mixture() requires at least 2 components".into(),
+        ));
+    }
+    let links = mixture_links(&components);
+    Ok(FamilySpec {
+        family: Family::Mixture(components.into_iter().map(|c| c.family).collect()),
+        links,
+    })
+}
+
+impl FamilySpec {
+    /// The family with the default link of every parameter, as for `family = student`
+    ///
+    /// A mixture gets the default links of each component, indexed by component.
+    pub fn new(family: Family) -> Self {
+        let links = match &family {
+            Family::Mixture(families) => {
+                let components: Vec<FamilySpec> =
+                    families.iter().cloned().map(FamilySpec::new).collect();
+                mixture_links(&components)
+            }
+            _ => parameters(&family)
+                .iter()
+                .map(|(dpar, links)| (dpar.to_string(), links[0].to_string()))
+                .collect(),
+        };
+        FamilySpec { family, links }
+    }
+}
+
+/// The links of a mixture: each component's parameters with its index
+/// (`mu1`, `sigma1`, `mu2`, ...), then one mixing proportion per component.
+fn mixture_links(components: &[FamilySpec]) -> Vec<(String, String)> {
+    let mut links = Vec::new();
+    for (index, component) in components.iter().enumerate() {
+        for (dpar, link) in &component.links {
+            links.push((format!("{}{}", dpar, index + 1), link.clone()));
+        }
+    }
+    for index in 1..=components.len() {
+        links.push((format!("theta{}", index), "identity".to_string()));
+    }
+    links
+}
+
+/// Checks whether `name` is a distributional parameter of any family.
+///
+/// This covers the parameters of every family (`sigma`, `nu`, `zi`, `bias`, ...)
+/// and mixture parameters with a component index (`mu2`, `sigma1`, `theta1`).
+/// A `name = value` clause for such a name fixes the parameter; any other name
+/// is a formula option.
+///
+/// # Example
+/// ```
+/// use fiasto::internal::parse_family::is_distributional_parameter;
+///
+/// assert!(is_distributional_parameter("bias"));
+/// assert!(is_distributional_parameter("sigma2"));
+/// assert!(is_distributional_parameter("theta1"));
+/// assert!(!is_distributional_parameter("fill"));
+/// assert!(!is_distributional_parameter("theta"));
+/// ```
+pub fn is_distributional_parameter(name: &str) -> bool {
+    let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let is_indexed = base.len() < name.len();
+    (is_indexed && base == "theta")
+        || FAMILIES
+            .iter()
+            .any(|(_, family)| parameters(family).iter().any(|(dpar, _)| *dpar == base))
+}
+
+/// Looks up a family written as a column name, such as `student` or `Gamma`.
+fn family_by_name(name: &str) -> Option<Family> {
+    FAMILIES
+        .iter()
+        .find(|(family, _)| *family == name)
+        .map(|(_, family)| family.clone())
+}
+
+/// Every family other than `mixture()`, by the name it is written with
+const FAMILIES: &[(&str, Family)] = &[
+    ("gaussian", Family::Gaussian),
+    ("student", Family::Student),
+    ("skew_normal", Family::SkewNormal),
+    ("binomial", Family::Binomial),
+    ("bernoulli", Family::Bernoulli),
+    ("beta_binomial", Family::BetaBinomial),
+    ("poisson", Family::Poisson),
+    ("negbinomial", Family::NegBinomial),
+    ("geometric", Family::Geometric),
+    ("Gamma", Family::Gamma),
+    ("lognormal", Family::Lognormal),
+    ("shifted_lognormal", Family::ShiftedLognormal),
+    ("wiener", Family::Wiener),
+    ("exgaussian", Family::ExGaussian),
+    ("weibull", Family::Weibull),
+    ("exponential", Family::Exponential),
+    ("frechet", Family::Frechet),
+    ("beta", Family::Beta),
+    ("von_mises", Family::VonMises),
+    ("asym_laplace", Family::AsymLaplace),
+    ("cumulative", Family::Cumulative),
+    ("sratio", Family::Sratio),
+    ("cratio", Family::Cratio),
+    ("acat", Family::Acat),
+    ("categorical", Family::Categorical),
+    ("multinomial", Family::Multinomial),
+    ("zero_inflated_poisson", Family::ZeroInflatedPoisson),
+    ("zero_inflated_negbinomial", Family::ZeroInflatedNegBinomial),
+    ("zero_inflated_binomial", Family::ZeroInflatedBinomial),
+    ("zero_inflated_beta", Family::ZeroInflatedBeta),
+    ("zero_one_inflated_beta", Family::ZeroOneInflatedBeta),
+    ("hurdle_poisson", Family::HurdlePoisson),
+    ("hurdle_negbinomial", Family::HurdleNegBinomial),
+    ("hurdle_gamma", Family::HurdleGamma),
+    ("hurdle_lognormal", Family::HurdleLognormal),
+];
+
+// The links each kind of parameter supports; the first one is the default
+const IDENTITY: &[&str] = &["identity", "log", "inverse", "softplus", "squareplus"];
+const POSITIVE: &[&str] = &["log", "identity", "softplus", "squareplus"];
+const PROBABILITY: &[&str] = &["logit", "probit", "cauchit", "cloglog", "log", "identity"];
+const COUNT: &[&str] = &["log", "identity", "sqrt", "softplus", "squareplus"];
+const INVERSE: &[&str] = &["inverse", "log", "identity", "softplus", "squareplus"];
+const ORDINAL: &[&str] = &["logit", "probit", "probit_approx", "cloglog", "cauchit"];
+const CATEGORICAL: &[&str] = &["logit"];
+const LOGNORMAL: &[&str] = &["identity", "inverse"];
+const REAL: &[&str] = &["identity"];
+const DEGREES_OF_FREEDOM: &[&str] = &["logm1", "identity"];
+const INFLATION: &[&str] = &["logit", "identity"];
+const CIRCULAR: &[&str] = &["tan_half", "identity"];
+const FRECHET: &[&str] = &["softplus", "log"];
+
+/// The distributional parameters of a family with the links each supports.
+fn parameters(family: &Family) -> &'static [(&'static str, &'static [&'static str])] {
+    match family {
+        Family::Gaussian => &[("mu", IDENTITY), ("sigma", POSITIVE)],
+        Family::Student => &[("mu", IDENTITY), ("sigma", POSITIVE), ("nu", DEGREES_OF_FREEDOM)],
+        Family::SkewNormal => &[("mu", IDENTITY), ("sigma", POSITIVE), ("alpha", REAL)],
+        Family::Binomial | Family::Bernoulli => &[("mu", PROBABILITY)],
+        Family::BetaBinomial => &[("mu", PROBABILITY), ("phi", POSITIVE)],
+        Family::Poisson | Family::Geometric => &[("mu", COUNT)],
+        Family::NegBinomial => &[("mu", COUNT), ("shape", POSITIVE)],
+        Family::Gamma => &[("mu", INVERSE), ("shape", POSITIVE)],
+        Family::Lognormal => &[("mu", LOGNORMAL), ("sigma", POSITIVE)],
+        Family::ShiftedLognormal => &[("mu", LOGNORMAL), ("sigma", POSITIVE), ("ndt", POSITIVE)],
+        Family::Wiener => &[
+            ("mu", IDENTITY),
+            ("bs", POSITIVE),
+            ("ndt", POSITIVE),
+            ("bias", INFLATION),
+        ],
+        Family::ExGaussian => &[("mu", IDENTITY), ("sigma", POSITIVE), ("beta", POSITIVE)],
+        Family::Weibull => &[("mu", POSITIVE), ("shape", POSITIVE)],
+        Family::Exponential => &[("mu", POSITIVE)],
+        Family::Frechet => &[("mu", FRECHET), ("nu", DEGREES_OF_FREEDOM)],
+        Family::Beta => &[("mu", PROBABILITY), ("phi", POSITIVE)],
+        Family::VonMises => &[("mu", CIRCULAR), ("kappa", POSITIVE)],
+        Family::AsymLaplace => &[("mu", IDENTITY), ("sigma", POSITIVE), ("quantile", INFLATION)],
+        Family::Cumulative | Family::Sratio | Family::Cratio | Family::Acat => {
+            &[("mu", ORDINAL), ("disc", POSITIVE)]
+        }
+        Family::Categorical | Family::Multinomial => &[("mu", CATEGORICAL)],
+        Family::ZeroInflatedPoisson => &[("mu", COUNT), ("zi", INFLATION)],
+        Family::ZeroInflatedNegBinomial => {
+            &[("mu", COUNT), ("shape", POSITIVE), ("zi", INFLATION)]
+        }
+        Family::ZeroInflatedBinomial => &[("mu", PROBABILITY), ("zi", INFLATION)],
+        Family::ZeroInflatedBeta => &[("mu", PROBABILITY), ("phi", POSITIVE), ("zi", INFLATION)],
+        Family::ZeroOneInflatedBeta => &[
+            ("mu", PROBABILITY),
+            ("phi", POSITIVE),
+            ("zoi", INFLATION),
+            ("coi", INFLATION),
+        ],
+        Family::HurdlePoisson => &[("mu", COUNT), ("hu", INFLATION)],
+        Family::HurdleNegBinomial => &[("mu", COUNT), ("shape", POSITIVE), ("hu", INFLATION)],
+        Family::HurdleGamma => &[("mu", POSITIVE), ("shape", POSITIVE), ("hu", INFLATION)],
+        Family::HurdleLognormal => &[("mu", LOGNORMAL), ("sigma", POSITIVE), ("hu", INFLATION)],
+        // The parameters of a mixture are those of its components, see `parse_mixture`
+        Family::Mixture(_) => &[],
+    }
 }
 
 #[cfg(test)]
@@ -90,7 +436,7 @@ mod tests {
         
         let result = parse_family(&tokens, &mut pos);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), Family::Gaussian);
+        assert_eq!(result.unwrap().family, Family::Gaussian);
         assert_eq!(pos, 1);
     }
 
@@ -103,7 +449,7 @@ mod tests {
         
         let result = parse_family(&tokens, &mut pos);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), Family::Binomial);
+        assert_eq!(result.unwrap().family, Family::Binomial);
         assert_eq!(pos, 1);
     }
 
@@ -116,7 +462,7 @@ mod tests {
         
         let result = parse_family(&tokens, &mut pos);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), Family::Poisson);
+        assert_eq!(result.unwrap().family, Family::Poisson);
         assert_eq!(pos, 1);
     }
 
@@ -236,7 +582,7 @@ mod tests {
         
         let result = parse_family(&tokens, &mut pos);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), Family::Gaussian);
+        assert_eq!(result.unwrap().family, Family::Gaussian);
         assert_eq!(pos, 1); // Position advanced past family
     }
 
@@ -254,8 +600,183 @@ mod tests {
             
             let result = parse_family(&tokens, &mut pos);
             assert!(result.is_ok());
-            assert_eq!(result.unwrap(), expected_family);
+            assert_eq!(result.unwrap().family, expected_family);
             assert_eq!(pos, 1);
         }
     }
+
+    #[test]
+    fn test_parse_family_by_name_with_default_links() {
+        let tokens = vec![(Token::ColumnName, "zero_inflated_poisson")];
+        let mut pos = 0;
+
+        let spec = parse_family(&tokens, &mut pos).unwrap();
+        assert_eq!(spec.family, Family::ZeroInflatedPoisson);
+        assert_eq!(
+            spec.links,
+            vec![
+                ("mu".to_string(), "log".to_string()),
+                ("zi".to_string(), "logit".to_string())
+            ]
+        );
+        assert_eq!(pos, 1);
+    }
+
+    #[test]
+    fn test_parse_family_parameter_links() {
+        // student("log", link_sigma = "identity")
+        let tokens = vec![
+            (Token::ColumnName, "student"),
+            (Token::FunctionStart, "("),
+            (Token::StringLiteral, "\"log\""),
+            (Token::Comma, ","),
+            (Token::ColumnName, "link_sigma"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"identity\""),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let spec = parse_family(&tokens, &mut pos).unwrap();
+        assert_eq!(spec.family, Family::Student);
+        let links: Vec<(&str, &str)> = spec
+            .links
+            .iter()
+            .map(|(dpar, link)| (dpar.as_str(), link.as_str()))
+            .collect();
+        assert_eq!(links, vec![("mu", "log"), ("sigma", "identity"), ("nu", "logm1")]);
+        assert_eq!(pos, tokens.len());
+    }
+
+    #[test]
+    fn test_parse_family_invalid_links() {
+        // poisson(link = "logit"): not a link of poisson's mu
+        let tokens = vec![
+            (Token::Poisson, "poisson"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "link"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"logit\""),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(parse_family(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+
+        // gaussian(link_zi = "logit"): gaussian has no zi parameter
+        let tokens = vec![
+            (Token::Gaussian, "gaussian"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "link_zi"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"logit\""),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(parse_family(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+
+        // mixture(gaussian, nmix = 3000000) has too many components
+        let tokens = vec![
+            (Token::ColumnName, "mixture"),
+            (Token::FunctionStart, "("),
+            (Token::Gaussian, "gaussian"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "nmix"),
+            (Token::Equal, "="),
+            (Token::Integer, "3000000"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(parse_family(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_parse_family_unknown_name() {
+        let tokens = vec![(Token::ColumnName, "gamma_poisson")];
+        let mut pos = 0;
+
+        let result = parse_family(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Unexpected { .. })));
+        assert_eq!(pos, 0);
+    }
+
+    #[test]
+    fn test_parse_family_repeated_link() {
+        // gaussian(link = "log", link = "identity")
+        let tokens = vec![
+            (Token::Gaussian, "gaussian"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "link"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"log\""),
+            (Token::Comma, ","),
+            (Token::ColumnName, "link"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"identity\""),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(parse_family(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_parse_family_mixture() {
+        // mixture(gaussian, poisson)
+        let tokens = vec![
+            (Token::ColumnName, "mixture"),
+            (Token::FunctionStart, "("),
+            (Token::Gaussian, "gaussian"),
+            (Token::Comma, ","),
+            (Token::Poisson, "poisson"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let spec = parse_family(&tokens, &mut pos).unwrap();
+        assert_eq!(spec.family, Family::Mixture(vec![Family::Gaussian, Family::Poisson]));
+        let dpars: Vec<&str> = spec.links.iter().map(|(dpar, _)| dpar.as_str()).collect();
+        assert_eq!(dpars, vec!["mu1", "sigma1", "mu2", "theta1", "theta2"]);
+        assert_eq!(pos, tokens.len());
+
+        // mixture(gaussian, nmix = 3)
+        let tokens = vec![
+            (Token::ColumnName, "mixture"),
+            (Token::FunctionStart, "("),
+            (Token::Gaussian, "gaussian"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "nmix"),
+            (Token::Equal, "="),
+            (Token::Integer, "3"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let spec = parse_family(&tokens, &mut pos).unwrap();
+        assert_eq!(spec.family, Family::Mixture(vec![Family::Gaussian; 3]));
+
+        // mixture(gaussian) has a single component
+        let tokens = vec![
+            (Token::ColumnName, "mixture"),
+            (Token::FunctionStart, "("),
+            (Token::Gaussian, "gaussian"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(parse_family(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_family_spec_new_default_links() {
+        let spec = FamilySpec::new(Family::Student);
+        let links: Vec<(&str, &str)> =
+            spec.links.iter().map(|(d, l)| (d.as_str(), l.as_str())).collect();
+        assert_eq!(links, vec![("mu", "identity"), ("sigma", "log"), ("nu", "logm1")]);
+
+        // Parsing a bare family gives the same links
+        let tokens = vec![(Token::ColumnName, "student")];
+        assert_eq!(parse_family(&tokens, &mut 0).unwrap(), spec);
+
+        let spec = FamilySpec::new(Family::Mixture(vec![Family::Poisson, Family::Poisson]));
+        let dpars: Vec<&str> = spec.links.iter().map(|(d, _)| d.as_str()).collect();
+        assert_eq!(dpars, vec!["mu1", "mu2", "theta1", "theta2"]);
+    }
 }
@@ -1,5 +1,5 @@
 use crate::internal::{
-    ast::{Family, Response, Term},
+    ast::{Argument, FixedParameter, FormulaOption, ParameterFormula, ParsedFormula, Response},
     errors::ParseError,
     lexer::Token,
 };
@@ -15,17 +15,14 @@ use crate::internal::{
 /// * `pos` - Mutable reference to the current position (will be advanced)
 ///
 /// # Returns
-/// * `Result<(Response, Vec<Term>, bool, Option<Family>), ParseError>` - A tuple containing:
-///   - Response variable(s) specification
-///   - Vector of terms from the right-hand side
-///   - Boolean indicating whether intercept is included
-///   - Optional family specification
+/// * `Result<ParsedFormula, ParseError>` - The response, right-hand side terms,
+///   intercept flag and every clause of the formula (see `ParsedFormula`)
 ///
 /// # Example
 /// ```
 /// use fiasto::internal::parse_formula::parse_formula;
 /// use fiasto::internal::lexer::Token;
-/// use fiasto::internal::ast::Response;
+/// use fiasto::internal::ast::{ParsedFormula, Response};
 ///
 /// let tokens = vec![
 ///     (Token::ColumnName, "y"),
@@ -42,9 +39,9 @@ use crate::internal::{
 ///
 /// let result = parse_formula(&tokens, &mut pos);
 /// assert!(result.is_ok());
-/// let (response, terms, has_intercept, family) = result.unwrap();
+/// let ParsedFormula { response, terms, has_intercept, family, .. } = result.unwrap();
 /// match response {
-///     Response::Single(name) => assert_eq!(name, "y"),
+///     Some(Response::Single(name)) => assert_eq!(name, "y"),
 ///     _ => panic!("Expected single response")
 /// }
 /// assert_eq!(terms.len(), 2);
@@ -53,17 +50,27 @@ use crate::internal::{
 /// ```
 ///
 /// # How it works
-/// 1. Parses the response variable using `parse_response`
+/// 1. Parses the response variable using `parse_response`, followed by any
+///    addition terms after `|` using `parse_addition_terms`. One-sided formulas
+///    start directly with the tilde and have no response
 /// 2. Expects and consumes a tilde (`~`) symbol
-/// 3. Parses the right-hand side using `parse_rhs`
-/// 4. Optionally parses family specification if comma is present
+/// 3. Parses the right-hand side using `parse_rhs`, or as an arithmetic expression
+///    using `parse_expression` when the formula has an `nl = TRUE` clause
+/// 4. Parses comma-separated clauses: the family specification, formula options,
+///    fixed parameters and any number of parameter sub-formulas, in any order.
+///    Known options (`nl`, `center`, `sparse`, `fill`, `decomp`) have their values
+///    checked here; any other `name = value` clause is kept as a fixed parameter
 ///
 /// # Grammar Rule
 /// ```text
-/// formula = response "~" rhs ["," family_spec]
+/// formula = [response ["|" additions]] "~" (rhs | expression) ("," clause)*
 /// response = column_name | bind(column_name, ...)
+/// additions = addition ("+" addition)*
 /// rhs = term_list [intercept_spec]
-/// family_spec = "family" "=" family_name
+/// clause = family_spec | option | parameter_formula
+/// family_spec = "family" "=" family   (see parse_family)
+/// option = column_name "=" (boolean | number | string | column_name)
+/// parameter_formula = column_name ("+" column_name)* "~" rhs
 /// ```
 ///
 /// # Use Cases
@@ -74,30 +81,223 @@ use crate::internal::{
 ///
 /// # Examples of Valid Inputs
 /// - `"y ~ x"` → response=Single("y"), terms=["x"], intercept=true, family=None
+/// - `"~ x + z"` → response=None, terms=["x", "z"], intercept=true, family=None
 /// - `"bind(y1, y2) ~ x"` → response=Multivariate(["y1", "y2"]), terms=["x"], intercept=true, family=None
 /// - `"y ~ x + z - 1"` → response=Single("y"), terms=["x", "z"], intercept=false, family=None
 /// - `"y ~ x, family=gaussian"` → response=Single("y"), terms=["x"], intercept=true, family=Gaussian
+/// - `"y ~ x, family = binomial(link = \"probit\")"` → response=Single("y"), terms=["x"], intercept=true, family=Binomial (mu: probit)
+/// - `"y ~ x, sigma ~ z"` → response=Single("y"), terms=["x"], intercept=true, parameters=[sigma ~ z]
+/// - `"y | trials(n) ~ x"` → response=Single("y"), terms=["x"], intercept=true, additions=[Trials(n)]
+/// - `"rt | dec(d) ~ x, bias = 0.5, family = wiener"` → additions=[Dec(d)], family=Wiener, fixed=[bias = 0.5]
+/// - `"y ~ x, sigma2 = \"sigma1\", family = mixture(gaussian, gaussian)"` → family=Mixture, fixed=[sigma2 = "sigma1"]
+/// - `"y ~ x + (1 | g), fill = \"mean\""` → terms=["x", (1 | g)], options=[fill = "mean"]
+/// - `"y ~ a1 - a2^x, a1 ~ 1, a2 ~ x, nl = TRUE"` → response=Single("y"), terms=[], intercept=false,
+///   parameters=[a1 ~ 1, a2 ~ x], nonlinear=Some(a1 - a2^x)
 pub fn parse_formula<'a>(
     tokens: &'a [(Token, &'a str)],
     pos: &mut usize,
-) -> Result<(Response, Vec<Term>, bool, Option<Family>), ParseError> {
-    let response = crate::internal::parse_response::parse_response(tokens, pos)?;
+) -> Result<ParsedFormula, ParseError> {
+    // One-sided formulas (`~ x + z`) have no response
+    let response = match crate::internal::peek::peek(tokens, *pos) {
+        Some((Token::Tilde, _)) => None,
+        _ => Some(crate::internal::parse_response::parse_response(tokens, pos)?),
+    };
+    let mut additions = Vec::new();
+    if response.is_some()
+        && crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Pipe))
+    {
+        if matches!(response, Some(Response::Multivariate(_))) {
+            return Err(ParseError::Syntax(
+                "response addition terms require a single response".into(),
+            ));
+        }
+        additions = crate::internal::parse_addition_terms::parse_addition_terms(tokens, pos)?;
+    }
     crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::Tilde), "~")?;
-    let (terms, has_intercept) = crate::internal::parse_rhs::parse_rhs(tokens, pos)?;
+    // In a non-linear formula the right-hand side is an expression over parameters
+    let (terms, has_intercept, nonlinear) = if is_nonlinear(tokens, *pos) {
+        let expression = crate::internal::parse_expression::parse_expression(tokens, pos)?;
+        (Vec::new(), false, Some(expression))
+    } else {
+        let (terms, has_intercept) = crate::internal::parse_rhs::parse_rhs(tokens, pos)?;
+        (terms, has_intercept, None)
+    };
 
     let mut family = None;
-    if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Comma)) {
-        crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::Family), "family")?;
-        crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::Equal), "=")?;
-        family = Some(crate::internal::parse_family::parse_family(tokens, pos)?);
+    let mut parameters: Vec<ParameterFormula> = Vec::new();
+    let mut fixed: Vec<FixedParameter> = Vec::new();
+    let mut options: Vec<FormulaOption> = Vec::new();
+    while crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Comma)) {
+        match crate::internal::peek::peek(tokens, *pos) {
+            Some((Token::Family, _)) => {
+                if family.is_some() {
+                    return Err(ParseError::Syntax("family is given more than once".into()));
+                }
+                *pos += 1;
+                crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::Equal), "=")?;
+                family = Some(crate::internal::parse_family::parse_family(tokens, pos)?);
+            }
+            Some((Token::ColumnName, name)) if is_option(tokens, *pos) => {
+                let name = name.to_string();
+                *pos += 2;
+                let value = parse_option_value(tokens, pos, &name)?;
+                if let Some((_, expected)) = OPTIONS.iter().find(|(option, _)| *option == name) {
+                    if !is_valid_option(&name, &value) {
+                        return Err(ParseError::Syntax(format!(
+                            "formula option '{}' must be {}",
+                            name, expected
+                        )));
+                    }
+                    if options.iter().any(|o: &FormulaOption| o.name == name) {
+                        return Err(ParseError::Syntax(format!(
+                            "formula option '{}' is given more than once",
+                            name
+                        )));
+                    }
+                    options.push(FormulaOption { name, value });
+                    continue;
+                }
+                // bias = 0.5 fixes the parameter to a constant, sigma2 = "sigma1"
+                // sets it equal to another parameter. Names that are not
+                // distributional parameters become options when the model is checked.
+                if parameters.iter().any(|p| p.parameter == name)
+                    || fixed.iter().any(|f| f.parameter == name)
+                {
+                    return Err(ParseError::Syntax(format!(
+                        "parameter '{}' already has a formula or a fixed value",
+                        name
+                    )));
+                }
+                fixed.push(FixedParameter {
+                    parameter: name,
+                    value,
+                });
+            }
+            Some((Token::ColumnName, _)) => {
+                let formulas =
+                    crate::internal::parse_parameter_formula::parse_parameter_formula(tokens, pos)?;
+                for parameter in formulas {
+                    if parameters.iter().any(|p| p.parameter == parameter.parameter) {
+                        return Err(ParseError::Syntax(format!(
+                            "parameter '{}' has more than one formula",
+                            parameter.parameter
+                        )));
+                    }
+                    if fixed.iter().any(|f| f.parameter == parameter.parameter) {
+                        return Err(ParseError::Syntax(format!(
+                            "parameter '{}' already has a formula or a fixed value",
+                            parameter.parameter
+                        )));
+                    }
+                    parameters.push(parameter);
+                }
+            }
+            found => {
+                return Err(ParseError::Unexpected {
+                    expected: "family or parameter formula",
+                    found: found.map(|(t, _)| t.clone()),
+                })
+            }
+        }
+    }
+
+    Ok(ParsedFormula {
+        response,
+        terms,
+        has_intercept,
+        family,
+        parameters,
+        nonlinear,
+        additions,
+        fixed,
+        options,
+    })
+}
+
+/// Checks whether the token at `pos` starts a `name = value` option clause.
+fn is_option(tokens: &[(Token, &str)], pos: usize) -> bool {
+    crate::internal::peek::peek(tokens, pos + 1)
+        .map(|(t, _)| matches!(t, Token::Equal))
+        .unwrap_or(false)
+}
+
+/// The formula options the parser knows, with the values each accepts
+const OPTIONS: &[(&str, &str)] = &[
+    ("nl", "TRUE or FALSE"),
+    ("center", "TRUE or FALSE"),
+    ("sparse", "TRUE or FALSE"),
+    ("fill", "one of \"mean\", \"median\", \"mode\", \"zero\", \"locf\" or \"nocb\""),
+    ("decomp", "\"QR\""),
+];
+
+/// The methods `fill` accepts for filling in missing values
+const FILL_METHODS: &[&str] = &["mean", "median", "mode", "zero", "locf", "nocb"];
+
+/// Checks the value of a known formula option.
+fn is_valid_option(name: &str, value: &Argument) -> bool {
+    match name {
+        "nl" | "center" | "sparse" => matches!(value, Argument::Boolean(_)),
+        "fill" => {
+            matches!(value, Argument::String(s) | Argument::Ident(s) if FILL_METHODS.contains(&s.as_str()))
+        }
+        "decomp" => matches!(value, Argument::String(s) | Argument::Ident(s) if s == "QR"),
+        _ => true,
     }
+}
 
-    Ok((response, terms, has_intercept, family))
+/// Parses the value of a `name = value` clause: a boolean, a number, a string or a name.
+fn parse_option_value<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+    name: &str,
+) -> Result<Argument, ParseError> {
+    let value = crate::internal::parse_arg::parse_arg(tokens, pos)?;
+    match value {
+        Argument::Boolean(_)
+        | Argument::Integer(_)
+        | Argument::Number(_)
+        | Argument::String(_)
+        | Argument::Ident(_) => Ok(value),
+        _ => Err(ParseError::Syntax(format!(
+            "formula option '{}' takes TRUE or FALSE, a number, a string or a name",
+            name
+        ))),
+    }
+}
+
+/// Checks whether the formula starting at `start` has an `nl = TRUE` clause
+/// outside any parentheses.
+///
+/// The flag changes how the main right-hand side is parsed, so it is looked up
+/// before parsing starts even though it is written at the end of the formula.
+/// The scan stops at the `)` that closes an enclosing `bf()`.
+fn is_nonlinear(tokens: &[(Token, &str)], start: usize) -> bool {
+    let mut depth = 0usize;
+    for (i, (tok, slice)) in tokens.iter().enumerate().skip(start) {
+        match tok {
+            Token::FunctionStart => depth += 1,
+            Token::FunctionEnd if depth == 0 => break,
+            Token::FunctionEnd => depth -= 1,
+            Token::ColumnName if depth == 0 && *slice == "nl" && i > 0 => {
+                let is_clause = tokens[i - 1].0 == Token::Comma && is_option(tokens, i);
+                let is_true = matches!(
+                    tokens.get(i + 2),
+                    Some((Token::True | Token::TrueUpper, _))
+                );
+                if is_clause && is_true {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::internal::ast::{Expression, Family};
     use crate::internal::lexer::Token;
 
     #[test]
@@ -111,9 +311,9 @@ mod tests {
 
         let result = parse_formula(&tokens, &mut pos);
         assert!(result.is_ok());
-        let (response, terms, has_intercept, family) = result.unwrap();
+        let ParsedFormula { response, terms, has_intercept, family, .. } = result.unwrap();
         match response {
-            Response::Single(name) => assert_eq!(name, "y"),
+            Some(Response::Single(name)) => assert_eq!(name, "y"),
             _ => panic!("Expected single response"),
         }
         assert_eq!(terms.len(), 1);
@@ -134,9 +334,9 @@ mod tests {
 
         let result = parse_formula(&tokens, &mut pos);
         assert!(result.is_ok());
-        let (response, terms, has_intercept, family) = result.unwrap();
+        let ParsedFormula { response, terms, has_intercept, family, .. } = result.unwrap();
         match response {
-            Response::Single(name) => assert_eq!(name, "y"),
+            Some(Response::Single(name)) => assert_eq!(name, "y"),
             _ => panic!("Expected single response"),
         }
         assert_eq!(terms.len(), 2);
@@ -157,9 +357,9 @@ mod tests {
 
         let result = parse_formula(&tokens, &mut pos);
         assert!(result.is_ok());
-        let (response, terms, has_intercept, family) = result.unwrap();
+        let ParsedFormula { response, terms, has_intercept, family, .. } = result.unwrap();
         match response {
-            Response::Single(name) => assert_eq!(name, "y"),
+            Some(Response::Single(name)) => assert_eq!(name, "y"),
             _ => panic!("Expected single response"),
         }
         assert_eq!(terms.len(), 1);
@@ -182,15 +382,37 @@ mod tests {
 
         let result = parse_formula(&tokens, &mut pos);
         assert!(result.is_ok());
-        let (response, terms, has_intercept, family) = result.unwrap();
+        let ParsedFormula { response, terms, has_intercept, family, .. } = result.unwrap();
         match response {
-            Response::Single(name) => assert_eq!(name, "y"),
+            Some(Response::Single(name)) => assert_eq!(name, "y"),
             _ => panic!("Expected single response"),
         }
         assert_eq!(terms.len(), 1);
         assert!(has_intercept);
         assert!(family.is_some());
-        assert_eq!(family.unwrap(), Family::Gaussian);
+        assert_eq!(family.unwrap().family, Family::Gaussian);
+    }
+
+    #[test]
+    fn test_parse_formula_duplicate_family() {
+        // y ~ x, family = gaussian, family = poisson
+        let tokens = vec![
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::Family, "family"),
+            (Token::Equal, "="),
+            (Token::Gaussian, "gaussian"),
+            (Token::Comma, ","),
+            (Token::Family, "family"),
+            (Token::Equal, "="),
+            (Token::Poisson, "poisson"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_formula(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Syntax(_))));
     }
 
     #[test]
@@ -234,9 +456,9 @@ mod tests {
 
         let result = parse_formula(&tokens, &mut pos);
         assert!(result.is_ok());
-        let (response, terms, has_intercept, family) = result.unwrap();
+        let ParsedFormula { response, terms, has_intercept, family, .. } = result.unwrap();
         match response {
-            Response::Single(name) => assert_eq!(name, "y"),
+            Some(Response::Single(name)) => assert_eq!(name, "y"),
             _ => panic!("Expected single response"),
         }
         assert_eq!(terms.len(), 1);
@@ -251,13 +473,241 @@ mod tests {
 
         let result = parse_formula(&tokens, &mut pos);
         assert!(result.is_ok());
-        let (response, terms, has_intercept, family) = result.unwrap();
+        let ParsedFormula { response, terms, has_intercept, family, .. } = result.unwrap();
         match response {
-            Response::Single(name) => assert_eq!(name, "y"),
+            Some(Response::Single(name)) => assert_eq!(name, "y"),
             _ => panic!("Expected single response"),
         }
         assert_eq!(terms.len(), 0);
         assert!(has_intercept);
         assert!(family.is_none());
     }
+
+    #[test]
+    fn test_parse_formula_with_parameter_formulas() {
+        // y ~ x, sigma ~ z, family = gaussian
+        let tokens = vec![
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "sigma"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "z"),
+            (Token::Comma, ","),
+            (Token::Family, "family"),
+            (Token::Equal, "="),
+            (Token::Gaussian, "gaussian"),
+        ];
+        let mut pos = 0;
+
+        let ParsedFormula { terms, family, parameters, .. } = parse_formula(&tokens, &mut pos).unwrap();
+        assert_eq!(terms.len(), 1);
+        assert_eq!(family.map(|spec| spec.family), Some(Family::Gaussian));
+        assert_eq!(parameters.len(), 1);
+        assert_eq!(parameters[0].parameter, "sigma");
+        assert_eq!(pos, 11);
+    }
+
+    #[test]
+    fn test_parse_formula_duplicate_parameter_formula() {
+        // y ~ x, sigma ~ z, sigma ~ w
+        let tokens = vec![
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "sigma"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "z"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "sigma"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "w"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_formula(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_parse_formula_nonlinear() {
+        // y ~ a * x, a ~ 1, nl = TRUE
+        let tokens = vec![
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "a"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "a"),
+            (Token::Tilde, "~"),
+            (Token::One, "1"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "nl"),
+            (Token::Equal, "="),
+            (Token::TrueUpper, "TRUE"),
+        ];
+        let mut pos = 0;
+
+        let ParsedFormula { terms, has_intercept, parameters, nonlinear, .. } =
+            parse_formula(&tokens, &mut pos).unwrap();
+        assert!(terms.is_empty());
+        assert!(!has_intercept);
+        assert_eq!(parameters.len(), 1);
+        assert!(matches!(nonlinear, Some(Expression::Binary { .. })));
+        assert_eq!(pos, 13);
+    }
+
+    #[test]
+    fn test_parse_formula_nonlinear_without_parameter_formulas() {
+        // y ~ a * x, nl = TRUE: the parameters may come from lf() components,
+        // so the model-level check rejects it, not the parser
+        let tokens = vec![
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "a"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "nl"),
+            (Token::Equal, "="),
+            (Token::TrueUpper, "TRUE"),
+        ];
+        let mut pos = 0;
+
+        let ParsedFormula { parameters, nonlinear, .. } = parse_formula(&tokens, &mut pos).unwrap();
+        assert!(parameters.is_empty());
+        assert!(nonlinear.is_some());
+    }
+
+    #[test]
+    fn test_parse_formula_with_response_additions() {
+        // y | trials(n) ~ x
+        let tokens = vec![
+            (Token::ColumnName, "y"),
+            (Token::Pipe, "|"),
+            (Token::Trials, "trials"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "n"),
+            (Token::FunctionEnd, ")"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+        ];
+        let mut pos = 0;
+
+        let ParsedFormula { response, terms, additions, .. } = parse_formula(&tokens, &mut pos).unwrap();
+        assert_eq!(response, Some(Response::Single("y".to_string())));
+        assert_eq!(terms.len(), 1);
+        assert_eq!(additions.len(), 1);
+        assert_eq!(pos, 8);
+    }
+
+    #[test]
+    fn test_parse_formula_one_sided() {
+        // ~ x + z
+        let tokens = vec![
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "z"),
+        ];
+        let mut pos = 0;
+
+        let ParsedFormula { response, terms, has_intercept, .. } =
+            parse_formula(&tokens, &mut pos).unwrap();
+        assert!(response.is_none());
+        assert_eq!(terms.len(), 2);
+        assert!(has_intercept);
+        assert_eq!(pos, 4);
+    }
+
+    #[test]
+    fn test_parse_formula_fixed_parameter() {
+        // y ~ x, sigma2 = "sigma1"
+        let tokens = vec![
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "sigma2"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"sigma1\""),
+        ];
+        let mut pos = 0;
+
+        let ParsedFormula { parameters, fixed, .. } = parse_formula(&tokens, &mut pos).unwrap();
+        assert!(parameters.is_empty());
+        assert_eq!(
+            fixed,
+            vec![FixedParameter {
+                parameter: "sigma2".to_string(),
+                value: Argument::String("sigma1".to_string()),
+            }]
+        );
+        assert_eq!(pos, 7);
+    }
+
+    #[test]
+    fn test_parse_formula_fixed_constant() {
+        // y ~ x, bias = 0.5, sigma = -1
+        let tokens = vec![
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "bias"),
+            (Token::Equal, "="),
+            (Token::Number, "0.5"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "sigma"),
+            (Token::Equal, "="),
+            (Token::Minus, "-"),
+            (Token::One, "1"),
+        ];
+        let mut pos = 0;
+
+        let ParsedFormula { fixed, .. } = parse_formula(&tokens, &mut pos).unwrap();
+        assert_eq!(fixed.len(), 2);
+        assert_eq!(fixed[0].value, Argument::Number(0.5));
+        assert_eq!(fixed[1].value, Argument::Number(-1.0));
+        assert_eq!(pos, 12);
+    }
+
+    #[test]
+    fn test_parse_formula_options() {
+        // y ~ x, fill = "mean", sparse = TRUE
+        let tokens = vec![
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "fill"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"mean\""),
+            (Token::Comma, ","),
+            (Token::ColumnName, "sparse"),
+            (Token::Equal, "="),
+            (Token::TrueUpper, "TRUE"),
+        ];
+        let mut pos = 0;
+
+        let ParsedFormula { fixed, options, .. } = parse_formula(&tokens, &mut pos).unwrap();
+        assert!(fixed.is_empty());
+        assert_eq!(
+            options,
+            vec![
+                FormulaOption {
+                    name: "fill".to_string(),
+                    value: Argument::String("mean".to_string()),
+                },
+                FormulaOption {
+                    name: "sparse".to_string(),
+                    value: Argument::Boolean(true),
+                },
+            ]
+        );
+        assert_eq!(pos, 11);
+    }
 }
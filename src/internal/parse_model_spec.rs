@@ -0,0 +1,541 @@
+use crate::internal::{
+    ast::{ModelComponent, ParameterFormula},
+    errors::ParseError,
+    lexer::Token,
+};
+
+/// Parses a brms-style model specification that adds components together.
+///
+/// Instead of a single formula with comma-separated clauses, a model can be
+/// assembled from parts joined with `+`: the main formula in `bf()` (or just in
+/// parentheses), linear parameter formulas in `lf()`, non-linear parameter
+/// formulas in `nlf()`, a family such as `gaussian()` or
+/// `binomial(link = "probit")`, `set_rescor()` and autocorrelation structures
+/// such as `cor_ar(~ 1 | g)`.
+///
+/// # Arguments
+/// * `tokens` - Reference to the vector of tokens
+/// * `pos` - Mutable reference to the current position (will be advanced)
+///
+/// # Returns
+/// * `Result<Vec<ModelComponent>, ParseError>` - The components in formula order, or an error
+///
+/// # Example
+/// ```
+/// use fiasto::internal::parse_model_spec::parse_model_spec;
+/// use fiasto::internal::lexer::Token;
+/// use fiasto::internal::ast::{Family, FamilySpec, ModelComponent};
+///
+/// // Parse (y ~ x) + gaussian()
+/// let tokens = vec![
+///     (Token::FunctionStart, "("),
+///     (Token::ColumnName, "y"),
+///     (Token::Tilde, "~"),
+///     (Token::ColumnName, "x"),
+///     (Token::FunctionEnd, ")"),
+///     (Token::Plus, "+"),
+///     (Token::Gaussian, "gaussian"),
+///     (Token::FunctionStart, "("),
+///     (Token::FunctionEnd, ")")
+/// ];
+/// let mut pos = 0;
+///
+/// let components = parse_model_spec(&tokens, &mut pos).unwrap();
+/// assert_eq!(components.len(), 2);
+/// match &components[0] {
+///     ModelComponent::Formula { texts, .. } => assert_eq!(texts, &vec!["y ~ x".to_string()]),
+///     _ => panic!("Expected the main formula")
+/// }
+/// assert!(matches!(
+///     components[1],
+///     ModelComponent::Family(FamilySpec { family: Family::Gaussian, .. })
+/// ));
+/// assert_eq!(pos, 9);
+/// ```
+///
+/// # How it works
+/// 1. Parses one component, chosen by its first token
+/// 2. Parses main formulas with `parse_formula`, so they accept every clause a
+///    plain formula does (`sigma ~ x`, `nl = TRUE`, `family = ...`)
+/// 3. Parses `lf()` and `nlf()` arguments as parameter formulas plus the `dpar`
+///    and `resp` options
+/// 4. Records the text of each formula for the formula listing
+/// 5. Repeats while the components are joined with `+`
+///
+/// # Grammar Rule
+/// ```text
+/// model_spec = component ("+" component)*
+/// component = "(" formula ")" | ("bf" | "brmsformula") "(" formula ")"
+///           | "lf" "(" parameter_formula ("," lf_clause)* ")"
+///           | "nlf" "(" column_name "~" expression ("," lf_clause)* ")"
+///           | family_name "(" [link_arg ("," link_arg)*] ")"   (see parse_family)
+///           | "set_rescor" "(" boolean ")"
+///           | cor_name "(" ... ")"   (see parse_autocorrelation)
+/// lf_clause = parameter_formula | ("dpar" | "resp") "=" (string | column_name)
+/// ```
+///
+/// # Examples of Valid Inputs
+/// - `"bf(y ~ x, sigma ~ z) + gaussian()"` → [Formula(y ~ x, sigma ~ z), Family(Gaussian)]
+/// - `"bf(y ~ a * x, nl = TRUE) + lf(a ~ 1 + (1 | g))"` → [Formula(y ~ a * x), Linear([a ~ 1 + (1 | g)])]
+/// - `"(y ~ 1) + nlf(sigma ~ a * exp(b * x), a ~ x)"` → [Formula(y ~ 1), NonLinear(sigma, [a ~ x])]
+/// - `"bf(y ~ x) + lf(b ~ z, dpar = \"sigma\")"` → [Formula(y ~ x), Linear([b ~ z], dpar="sigma")]
+/// - `"bf(y ~ x, zi ~ z) + zero_inflated_poisson()"` → [Formula(y ~ x, zi ~ z), Family(ZeroInflatedPoisson)]
+/// - `"(y ~ x) + cor_ar(~ 1 | g)"` → [Formula(y ~ x), Autocorrelation(Arma { gr: g, p: 1, q: 0 })]
+pub fn parse_model_spec<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Vec<ModelComponent>, ParseError> {
+    let mut components = vec![parse_component(tokens, pos)?];
+    while crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Plus)) {
+        components.push(parse_component(tokens, pos)?);
+    }
+    Ok(components)
+}
+
+/// Parses a single component such as `bf(y ~ x)` or `gaussian()`.
+fn parse_component<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<ModelComponent, ParseError> {
+    let (tok, name) = crate::internal::peek::peek(tokens, *pos)
+        .cloned()
+        .ok_or(ParseError::Eoi)?;
+    match (tok, name) {
+        (Token::FunctionStart, _) => {
+            *pos += 1;
+            parse_main(tokens, pos)
+        }
+        (Token::ColumnName, "bf" | "brmsformula") => {
+            open_call(tokens, pos)?;
+            parse_main(tokens, pos)
+        }
+        (Token::ColumnName, "lf") => {
+            open_call(tokens, pos)?;
+            let start = *pos;
+            let mut formulas =
+                crate::internal::parse_parameter_formula::parse_parameter_formula(tokens, pos)?;
+            let (dpar, resp) = parse_clauses(tokens, pos, "lf", &mut formulas)?;
+            let texts = formula_texts(&tokens[start..*pos], false);
+            crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+            Ok(ModelComponent::Linear {
+                formulas,
+                dpar,
+                resp,
+                texts,
+            })
+        }
+        (Token::ColumnName, "nlf") => {
+            open_call(tokens, pos)?;
+            let start = *pos;
+            let (_, parameter) = crate::internal::expect::expect(
+                tokens,
+                pos,
+                |t| matches!(t, Token::ColumnName),
+                "parameter name",
+            )?;
+            crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::Tilde), "~")?;
+            let expression = crate::internal::parse_expression::parse_expression(tokens, pos)?;
+            let mut formulas = Vec::new();
+            let (dpar, resp) = parse_clauses(tokens, pos, "nlf", &mut formulas)?;
+            let texts = formula_texts(&tokens[start..*pos], true);
+            crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+            Ok(ModelComponent::NonLinear {
+                parameter: parameter.to_string(),
+                expression,
+                formulas,
+                dpar,
+                resp,
+                texts,
+            })
+        }
+        (Token::ColumnName, "set_rescor") => {
+            open_call(tokens, pos)?;
+            let (value, _) = crate::internal::expect::expect(
+                tokens,
+                pos,
+                |t| {
+                    matches!(
+                        t,
+                        Token::True | Token::TrueUpper | Token::False | Token::FalseUpper
+                    )
+                },
+                "TRUE or FALSE",
+            )?;
+            crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+            Ok(ModelComponent::Rescor(matches!(
+                value,
+                Token::True | Token::TrueUpper
+            )))
+        }
+        (
+            Token::ColumnName,
+            "cor_ar" | "cor_ma" | "cor_arma" | "cor_cosy" | "cor_car" | "cor_sar",
+        ) => Ok(ModelComponent::Autocorrelation(
+            crate::internal::parse_autocorrelation::parse_autocorrelation(tokens, pos)?,
+        )),
+        (tok @ (Token::Gaussian | Token::Binomial | Token::Poisson | Token::ColumnName), _)
+            if matches!(tokens.get(*pos + 1), Some((Token::FunctionStart, _))) =>
+        {
+            let start = *pos;
+            match crate::internal::parse_family::parse_family(tokens, pos) {
+                Ok(family) => Ok(ModelComponent::Family(family)),
+                // Not a family name either
+                Err(ParseError::Unexpected { .. }) if *pos == start => Err(ParseError::Unexpected {
+                    expected: "bf(), lf(), nlf(), set_rescor() or a family",
+                    found: Some(tok),
+                }),
+                Err(e) => Err(e),
+            }
+        }
+        (tok, _) => Err(ParseError::Unexpected {
+            expected: "bf(), lf(), nlf(), set_rescor() or a family",
+            found: Some(tok),
+        }),
+    }
+}
+
+/// Consumes a component name and its opening parenthesis.
+fn open_call<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result<(), ParseError> {
+    *pos += 1;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionStart), "(")?;
+    Ok(())
+}
+
+/// Parses the main formula of `bf(...)` or `(...)` up to and including the `)`.
+fn parse_main<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<ModelComponent, ParseError> {
+    let start = *pos;
+    let formula = crate::internal::parse_formula::parse_formula(tokens, pos)?;
+    let texts = formula_texts(&tokens[start..*pos], true);
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+    Ok(ModelComponent::Formula { formula, texts })
+}
+
+/// Parses the `, clause` list of `lf()` and `nlf()` and returns the `dpar` and
+/// `resp` options.
+fn parse_clauses<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+    call: &str,
+    formulas: &mut Vec<ParameterFormula>,
+) -> Result<(Option<String>, Option<String>), ParseError> {
+    let (mut dpar, mut resp) = (None, None);
+    while crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Comma)) {
+        let is_option = crate::internal::peek::peek(tokens, *pos + 1)
+            .map(|(t, _)| matches!(t, Token::Equal))
+            .unwrap_or(false);
+        if !is_option {
+            formulas.extend(crate::internal::parse_parameter_formula::parse_parameter_formula(
+                tokens, pos,
+            )?);
+            continue;
+        }
+        let name = tokens[*pos].1;
+        let option = match name {
+            "dpar" => &mut dpar,
+            "resp" => &mut resp,
+            _ => {
+                return Err(ParseError::Syntax(format!(
+                    "{}() has no option '{}'",
+                    call, name
+                )))
+            }
+        };
+        *pos += 2;
+        let (_, value) = crate::internal::expect::expect(
+            tokens,
+            pos,
+            |t| matches!(t, Token::StringLiteral | Token::ColumnName),
+            "parameter or response name",
+        )?;
+        *option = Some(value.trim_matches('"').to_string());
+    }
+    Ok((dpar, resp))
+}
+
+/// Renders the text of each formula in a component's argument list.
+///
+/// With `has_head`, the first clause is kept whole (`y ~ x`); every other
+/// formula clause gets one text per parameter, so `a1 + a2 ~ 1` becomes
+/// `a1 ~ 1` and `a2 ~ 1`. Option clauses such as `nl = TRUE` are skipped.
+fn formula_texts(tokens: &[(Token, &str)], has_head: bool) -> Vec<String> {
+    let mut texts = Vec::new();
+    for (i, clause) in split_clauses(tokens).into_iter().enumerate() {
+        let Some(tilde) = clause.iter().position(|(t, _)| *t == Token::Tilde) else {
+            continue;
+        };
+        if has_head && i == 0 {
+            texts.push(render(clause));
+            continue;
+        }
+        let rhs = render(&clause[tilde + 1..]);
+        for (tok, name) in &clause[..tilde] {
+            if *tok == Token::ColumnName {
+                texts.push(format!("{} ~ {}", name, rhs));
+            }
+        }
+    }
+    texts
+}
+
+/// Splits an argument list at the commas outside any parentheses.
+fn split_clauses<'t, 'a>(tokens: &'t [(Token, &'a str)]) -> Vec<&'t [(Token, &'a str)]> {
+    let mut clauses = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, (tok, _)) in tokens.iter().enumerate() {
+        match tok {
+            Token::FunctionStart => depth += 1,
+            Token::FunctionEnd => depth = depth.saturating_sub(1),
+            Token::Comma if depth == 0 => {
+                clauses.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    clauses.push(&tokens[start..]);
+    clauses
+}
+
+/// Renders tokens as formula text with normalized spacing: `b ~ z + (1 | g)`.
+fn render(tokens: &[(Token, &str)]) -> String {
+    let is_unary_minus = |i: usize| {
+        tokens[i].0 == Token::Minus
+            && (i == 0
+                || matches!(
+                    tokens[i - 1].0,
+                    Token::FunctionStart
+                        | Token::Comma
+                        | Token::Equal
+                        | Token::Tilde
+                        | Token::Plus
+                        | Token::Minus
+                        | Token::InteractionAndEffect
+                        | Token::Slash
+                        | Token::Caret
+                ))
+    };
+    let mut text = String::new();
+    for (i, (tok, slice)) in tokens.iter().enumerate() {
+        if i > 0 {
+            let (prev, prev_slice) = &tokens[i - 1];
+            let is_call = *tok == Token::FunctionStart
+                && prev_slice
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '.');
+            let tight = is_call
+                || is_unary_minus(i - 1)
+                || matches!(prev, Token::FunctionStart | Token::Caret | Token::InteractionOnly)
+                || matches!(
+                    tok,
+                    Token::FunctionEnd | Token::Comma | Token::Caret | Token::InteractionOnly
+                );
+            if !tight {
+                text.push(' ');
+            }
+        }
+        let is_plain_name = slice
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        if *tok == Token::ColumnName && !is_plain_name {
+            text.push_str(&format!("`{}`", slice));
+        } else {
+            text.push_str(slice);
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::lexer::Token;
+
+    #[test]
+    fn test_parse_model_spec_linear_formula_with_dpar() {
+        // bf(y ~ x) + lf(b ~ z + (1|g), dpar = "sigma")
+        let tokens = vec![
+            (Token::ColumnName, "bf"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::FunctionEnd, ")"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "lf"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "b"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "z"),
+            (Token::Plus, "+"),
+            (Token::FunctionStart, "("),
+            (Token::One, "1"),
+            (Token::Pipe, "|"),
+            (Token::ColumnName, "g"),
+            (Token::FunctionEnd, ")"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "dpar"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"sigma\""),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let components = parse_model_spec(&tokens, &mut pos).unwrap();
+        assert_eq!(components.len(), 2);
+        match &components[1] {
+            ModelComponent::Linear {
+                formulas,
+                dpar,
+                texts,
+                ..
+            } => {
+                assert_eq!(formulas[0].parameter, "b");
+                assert_eq!(dpar.as_deref(), Some("sigma"));
+                assert_eq!(texts, &vec!["b ~ z + (1 | g)".to_string()]);
+            }
+            _ => panic!("Expected a linear formula"),
+        }
+        assert_eq!(pos, tokens.len());
+    }
+
+    #[test]
+    fn test_parse_model_spec_nonlinear_formula() {
+        // nlf(sigma ~ a * exp(b * x), a + b ~ 1)
+        let tokens = vec![
+            (Token::ColumnName, "nlf"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "sigma"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "a"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::ColumnName, "exp"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "b"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::ColumnName, "x"),
+            (Token::FunctionEnd, ")"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "a"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "b"),
+            (Token::Tilde, "~"),
+            (Token::One, "1"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let components = parse_model_spec(&tokens, &mut pos).unwrap();
+        match &components[0] {
+            ModelComponent::NonLinear {
+                parameter,
+                formulas,
+                texts,
+                ..
+            } => {
+                assert_eq!(parameter, "sigma");
+                assert_eq!(formulas.len(), 2);
+                assert_eq!(
+                    texts,
+                    &vec![
+                        "sigma ~ a * exp(b * x)".to_string(),
+                        "a ~ 1".to_string(),
+                        "b ~ 1".to_string()
+                    ]
+                );
+            }
+            _ => panic!("Expected a non-linear formula"),
+        }
+    }
+
+    #[test]
+    fn test_parse_model_spec_rescor_and_family() {
+        // (y ~ 1) + poisson() + set_rescor(FALSE)
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "y"),
+            (Token::Tilde, "~"),
+            (Token::One, "1"),
+            (Token::FunctionEnd, ")"),
+            (Token::Plus, "+"),
+            (Token::Poisson, "poisson"),
+            (Token::FunctionStart, "("),
+            (Token::FunctionEnd, ")"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "set_rescor"),
+            (Token::FunctionStart, "("),
+            (Token::FalseUpper, "FALSE"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let components = parse_model_spec(&tokens, &mut pos).unwrap();
+        assert_eq!(components.len(), 3);
+        assert!(matches!(components[2], ModelComponent::Rescor(false)));
+    }
+
+    #[test]
+    fn test_parse_model_spec_unknown_component() {
+        let tokens = vec![
+            (Token::ColumnName, "foo"),
+            (Token::FunctionStart, "("),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_model_spec(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Unexpected { .. })));
+    }
+
+    #[test]
+    fn test_parse_model_spec_unknown_option() {
+        // lf(b ~ z, foo = 1)
+        let tokens = vec![
+            (Token::ColumnName, "lf"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "b"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "z"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "foo"),
+            (Token::Equal, "="),
+            (Token::One, "1"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_model_spec(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_parse_model_spec_linear_formula_with_resp() {
+        // lf(sigma ~ x, resp = "y2")
+        let tokens = vec![
+            (Token::ColumnName, "lf"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "sigma"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "resp"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"y2\""),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let components = parse_model_spec(&tokens, &mut pos).unwrap();
+        match &components[0] {
+            ModelComponent::Linear { dpar, resp, .. } => {
+                assert_eq!(dpar, &None);
+                assert_eq!(resp.as_deref(), Some("y2"));
+            }
+            _ => panic!("Expected a linear formula"),
+        }
+    }
+}
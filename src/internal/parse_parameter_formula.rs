@@ -0,0 +1,155 @@
+use crate::internal::{ast::ParameterFormula, errors::ParseError, lexer::Token};
+
+/// Parses a sub-formula that predicts a model parameter, such as `sigma ~ x`.
+///
+/// brms-style models predict distributional parameters (`sigma`, `zi`, `disc`, ...)
+/// with their own right-hand side. These sub-formulas follow the main formula,
+/// separated by commas: `y ~ x, sigma ~ x + (1 | g)`. Several parameters may
+/// share one right-hand side: `a1 + a2 ~ 1` is short for `a1 ~ 1, a2 ~ 1`.
+///
+/// # Arguments
+/// * `tokens` - Reference to the vector of tokens
+/// * `pos` - Mutable reference to the current position (will be advanced)
+///
+/// # Returns
+/// * `Result<Vec<ParameterFormula>, ParseError>` - One formula per parameter name, or an error
+///
+/// # Example
+/// ```
+/// use fiasto::internal::parse_parameter_formula::parse_parameter_formula;
+/// use fiasto::internal::lexer::Token;
+///
+/// // Parse sigma ~ x
+/// let tokens = vec![
+///     (Token::ColumnName, "sigma"),
+///     (Token::Tilde, "~"),
+///     (Token::ColumnName, "x")
+/// ];
+/// let mut pos = 0;
+///
+/// let formulas = parse_parameter_formula(&tokens, &mut pos).unwrap();
+/// let formula = &formulas[0];
+/// assert_eq!(formula.parameter, "sigma");
+/// assert_eq!(formula.terms.len(), 1);
+/// assert!(formula.has_intercept);
+/// assert_eq!(pos, 3);
+/// ```
+///
+/// # How it works
+/// 1. Expects the parameter names, separated by `+`
+/// 2. Expects and consumes a tilde (`~`) symbol
+/// 3. Parses the right-hand side using `parse_rhs`
+/// 4. Gives each parameter its own copy of the right-hand side
+///
+/// # Grammar Rule
+/// ```text
+/// parameter_formula = column_name ("+" column_name)* "~" rhs
+/// ```
+///
+/// # Examples of Valid Inputs
+/// - `"sigma ~ x"` → parameter="sigma", terms=["x"], intercept=true
+/// - `"zi ~ x + (1 | g)"` → parameter="zi", terms=["x", (1 | g)], intercept=true
+/// - `"disc ~ 0 + item"` → parameter="disc", terms=["item"], intercept=false
+/// - `"a1 + a2 ~ 1"` → [parameter="a1", terms=[]], [parameter="a2", terms=[]]
+pub fn parse_parameter_formula<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Vec<ParameterFormula>, ParseError> {
+    let mut parameters = Vec::new();
+    loop {
+        let (_, parameter) = crate::internal::expect::expect(
+            tokens,
+            pos,
+            |t| matches!(t, Token::ColumnName),
+            "parameter name",
+        )?;
+        parameters.push(parameter.to_string());
+        if !crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Plus)) {
+            break;
+        }
+    }
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::Tilde), "~")?;
+    let (terms, has_intercept) = crate::internal::parse_rhs::parse_rhs(tokens, pos)?;
+
+    Ok(parameters
+        .into_iter()
+        .map(|parameter| ParameterFormula {
+            parameter,
+            terms: terms.clone(),
+            has_intercept,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::ast::Term;
+
+    #[test]
+    fn test_parse_parameter_formula_with_random_effect() {
+        // zi ~ x + (1 | g)
+        let tokens = vec![
+            (Token::ColumnName, "zi"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+            (Token::Plus, "+"),
+            (Token::FunctionStart, "("),
+            (Token::One, "1"),
+            (Token::Pipe, "|"),
+            (Token::ColumnName, "g"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let formula = parse_parameter_formula(&tokens, &mut pos).unwrap().remove(0);
+        assert_eq!(formula.parameter, "zi");
+        assert_eq!(formula.terms.len(), 2);
+        assert!(matches!(formula.terms[1], Term::RandomEffect(_)));
+        assert_eq!(pos, 9);
+    }
+
+    #[test]
+    fn test_parse_parameter_formula_without_intercept() {
+        let tokens = vec![
+            (Token::ColumnName, "disc"),
+            (Token::Tilde, "~"),
+            (Token::Zero, "0"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "item"),
+        ];
+        let mut pos = 0;
+
+        let formula = parse_parameter_formula(&tokens, &mut pos).unwrap().remove(0);
+        assert_eq!(formula.parameter, "disc");
+        assert!(!formula.has_intercept);
+    }
+
+    #[test]
+    fn test_parse_parameter_formula_missing_tilde() {
+        let tokens = vec![(Token::ColumnName, "sigma"), (Token::ColumnName, "x")];
+        let mut pos = 0;
+
+        let result = parse_parameter_formula(&tokens, &mut pos);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_parameter_formula_shared_right_hand_side() {
+        // a1 + a2 ~ 1
+        let tokens = vec![
+            (Token::ColumnName, "a1"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "a2"),
+            (Token::Tilde, "~"),
+            (Token::One, "1"),
+        ];
+        let mut pos = 0;
+
+        let formulas = parse_parameter_formula(&tokens, &mut pos).unwrap();
+        let names: Vec<&str> = formulas.iter().map(|f| f.parameter.as_str()).collect();
+        assert_eq!(names, vec!["a1", "a2"]);
+        assert!(formulas.iter().all(|f| f.has_intercept));
+        assert_eq!(pos, 5);
+    }
+}
@@ -216,19 +216,15 @@ fn parse_correlation_type<'a>(
     if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::DoublePipe)) {
         Ok((CorrelationType::Uncorrelated, None))
     } else if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Pipe)) {
-        // Check for cross-parameter correlation ID
-        if let Some((Token::Integer, id_slice)) = tokens.get(*pos) {
-            *pos += 1;
-            if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Pipe)) {
-                Ok((
-                    CorrelationType::CrossParameter(id_slice.to_string()),
-                    Some(id_slice.to_string()),
-                ))
-            } else {
-                Err(ParseError::Syntax(
-                    "expected second '|' after correlation ID".into(),
-                ))
-            }
+        // Check for cross-parameter correlation ID: `|2|` or `|ID1|`
+        let is_id = matches!(
+            tokens.get(*pos),
+            Some((Token::Integer | Token::One | Token::Zero | Token::ColumnName, _))
+        ) && matches!(tokens.get(*pos + 1), Some((Token::Pipe, _)));
+        if is_id {
+            let id = tokens[*pos].1.to_string();
+            *pos += 2; // Skip the ID and the second pipe
+            Ok((CorrelationType::CrossParameter(id.clone()), Some(id)))
         } else {
             Ok((CorrelationType::Correlated, None))
         }
@@ -369,7 +365,16 @@ fn parse_gr_option<'a>(
             let (value_tok, value_str) = crate::internal::expect::expect(
                 tokens,
                 pos,
-                |t| matches!(t, Token::ColumnName | Token::StringLiteral),
+                |t| {
+                    matches!(
+                        t,
+                        Token::ColumnName
+                            | Token::StringLiteral
+                            | Token::Integer
+                            | Token::One
+                            | Token::Zero
+                    )
+                },
                 "ID string",
             )?;
             let id_value = match value_tok {
@@ -537,4 +542,28 @@ mod tests {
             matches!(random_effect.grouping, Grouping::Gr { ref group, ref options } if group == "group" && options.len() == 1)
         );
     }
+
+    #[test]
+    fn test_parse_cross_parameter_random_effect() {
+        // (1 |ID1| group)
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::One, "1"),
+            (Token::Pipe, "|"),
+            (Token::ColumnName, "ID1"),
+            (Token::Pipe, "|"),
+            (Token::ColumnName, "group"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let random_effect = parse_random_effect(&tokens, &mut pos).unwrap();
+        assert!(matches!(
+            random_effect.correlation,
+            CorrelationType::CrossParameter(ref id) if id == "ID1"
+        ));
+        assert_eq!(random_effect.correlation_id, Some("ID1".to_string()));
+        assert!(matches!(random_effect.grouping, Grouping::Simple(ref name) if name == "group"));
+        assert_eq!(pos, 7);
+    }
 }
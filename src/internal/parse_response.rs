@@ -1,4 +1,19 @@
-use crate::internal::{ast::Response, errors::ParseError, lexer::Token};
+use crate::internal::{
+    ast::{Argument, Expression, Response},
+    errors::ParseError,
+    lexer::Token,
+};
+
+/// Functions a response can be transformed with
+///
+/// A response must stay one column with one value per observation, so only
+/// functions that transform each value on its own are allowed: `log(y)` is a
+/// response, while `poly(y, 2)` or `s(y)` would turn it into several columns.
+const RESPONSE_FUNCTIONS: &[&str] = &[
+    "log", "log1p", "log2", "log10", "exp", "expm1", "sqrt", "abs", "scale", "logit",
+    "inv_logit", "qlogis", "plogis", "sin", "cos", "tan", "asin", "acos", "atan", "sinh",
+    "cosh", "tanh", "round", "floor", "ceiling", "sign",
+];
 
 /// Parses the response variable from the beginning of a formula.
 ///
@@ -35,15 +50,20 @@ use crate::internal::{ast::Response, errors::ParseError, lexer::Token};
 /// ```
 ///
 /// # How it works
-/// 1. Expects either a ColumnName (single response) or Bind token (multivariate response)
+/// 1. Expects either a ColumnName (single response), a Bind token (multivariate response)
+///    or another function call (transformed or binomial response)
 /// 2. For single responses, returns the variable name
-/// 3. For multivariate responses, parses the bind() function call
-/// 4. Advances the position to prepare for parsing the tilde and right-hand side
+/// 3. For multivariate responses, parses the `bind()` or `mvbind()` function call
+/// 4. For `cbind(successes, failures)`, returns the two binomial columns; `I(y / 2)`
+///    is parsed as an arithmetic expression, and any other call such as `log(y)` is
+///    parsed like a function term and must transform each value on its own
+/// 5. Advances the position to prepare for parsing any addition terms, the tilde and right-hand side
 ///
 /// # Grammar Rule
 /// ```text
-/// formula = response "~" rhs ["," family_spec]
-/// response = column_name | bind(column_name, ...)
+/// formula = response ["|" additions] "~" rhs ["," family_spec]
+/// response = column_name | ("bind" | "mvbind")(column_name, ...) | cbind(column_name, column_name)
+///          | "I(" expression ")" | function_call
 /// ```
 ///
 /// # Use Cases
@@ -55,11 +75,28 @@ use crate::internal::{ast::Response, errors::ParseError, lexer::Token};
 /// # Examples of Valid Inputs
 /// - `"y ~ x"` → response = Response::Single("y")
 /// - `"bind(y1, y2) ~ x"` → response = Response::Multivariate(vec!["y1", "y2"])
+/// - `"mvbind(y1, y2) ~ x"` → response = Response::Multivariate(vec!["y1", "y2"])
 /// - `"response_var ~ predictor"` → response = Response::Single("response_var")
+/// - `"log(y) ~ x"` → response = Response::Function { name: "log", args: [Ident("y")] }
+/// - `"cbind(succ, fail) ~ x"` → response = Response::Binomial { successes: "succ", failures: "fail" }
+/// - `"I(y / 2) ~ x"` → response = Response::AsIs(Binary { Divide, y, 2 })
 pub fn parse_response<'a>(
     tokens: &'a [(Token, &'a str)],
     pos: &mut usize,
 ) -> Result<Response, ParseError> {
+    // Any call other than `bind(...)`: `log(y)`, `I(y / 2)`, `cbind(s, f)` or `mvbind(y1, y2)`
+    let is_bind = matches!(crate::internal::peek::peek(tokens, *pos), Some((Token::Bind, _)));
+    if !is_bind && crate::internal::is_call::is_call(tokens, *pos) {
+        let name = tokens[*pos].1;
+        *pos += 2; // Skip the function name and the opening parenthesis
+        return match name {
+            "cbind" => parse_binomial(tokens, pos),
+            "mvbind" => parse_bind(name, tokens, pos),
+            "I" => parse_as_is(tokens, pos),
+            _ => parse_transformed(name, tokens, pos),
+        };
+    }
+
     let (token, name) = crate::internal::expect::expect(
         tokens,
         pos,
@@ -80,41 +117,153 @@ pub fn parse_response<'a>(
                 |t| matches!(t, Token::FunctionStart),
                 "(",
             )?;
+            parse_bind(name, tokens, pos)
+        }
+        _ => unreachable!(),
+    }
+}
 
-            let mut variables = Vec::new();
+/// Parses the columns of a multivariate response: `bind(y1, y2)` or `mvbind(y1, y2)`.
+fn parse_bind<'a>(
+    name: &str,
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Response, ParseError> {
+    let mut variables = Vec::new();
 
-            // Parse first variable
-            let (_, first_var) = crate::internal::expect::expect(
-                tokens,
-                pos,
-                |t| matches!(t, Token::ColumnName),
-                "ColumnName",
-            )?;
-            variables.push(first_var.to_string());
-
-            // Parse additional variables separated by commas
-            while crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Comma)) {
-                let (_, var_name) = crate::internal::expect::expect(
-                    tokens,
-                    pos,
-                    |t| matches!(t, Token::ColumnName),
-                    "ColumnName",
-                )?;
-                variables.push(var_name.to_string());
-            }
+    // Parse first variable
+    let (_, first_var) = crate::internal::expect::expect(
+        tokens,
+        pos,
+        |t| matches!(t, Token::ColumnName),
+        "ColumnName",
+    )?;
+    variables.push(first_var.to_string());
+
+    // Parse additional variables separated by commas
+    while crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Comma)) {
+        let (_, var_name) = crate::internal::expect::expect(
+            tokens,
+            pos,
+            |t| matches!(t, Token::ColumnName),
+            "ColumnName",
+        )?;
+        if variables.iter().any(|v| v == var_name) {
+            return Err(ParseError::Syntax(format!(
+                "response '{}' appears more than once in {}()",
+                var_name, name
+            )));
+        }
+        variables.push(var_name.to_string());
+    }
 
-            crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
 
-            if variables.len() < 2 {
-                return Err(ParseError::Syntax(
-                    "bind() requires at least 2 variables".into(),
-                ));
-            }
+    if variables.len() < 2 {
+        return Err(ParseError::Syntax(format!(
+            "{}() requires at least 2 variables",
+            name
+        )));
+    }
+
+    Ok(Response::Multivariate(variables))
+}
+
+/// Parses the columns of a binomial response: `cbind(successes, failures)`.
+fn parse_binomial<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Response, ParseError> {
+    let (_, successes) = crate::internal::expect::expect(
+        tokens,
+        pos,
+        |t| matches!(t, Token::ColumnName),
+        "ColumnName",
+    )?;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::Comma), ",")?;
+    let (_, failures) = crate::internal::expect::expect(
+        tokens,
+        pos,
+        |t| matches!(t, Token::ColumnName),
+        "ColumnName",
+    )?;
+    if !crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::FunctionEnd)) {
+        return Err(ParseError::Syntax(
+            "cbind() takes exactly 2 columns: successes and failures".into(),
+        ));
+    }
+
+    Ok(Response::Binomial {
+        successes: successes.to_string(),
+        failures: failures.to_string(),
+    })
+}
+
+/// Parses the arguments of a transformed response such as `log(y)`.
+fn parse_transformed<'a>(
+    name: &str,
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Response, ParseError> {
+    let args = crate::internal::parse_arg_list::parse_arg_list(tokens, pos)?;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+
+    fn has_column(args: &[Argument]) -> bool {
+        args.iter().any(|a| match a {
+            Argument::Ident(_) => true,
+            Argument::Function { args, .. } => has_column(args),
+            _ => false,
+        })
+    }
+    if !has_column(&args) {
+        return Err(ParseError::Syntax(format!(
+            "response {}() is not applied to a column",
+            name
+        )));
+    }
 
-            Ok(Response::Multivariate(variables))
+    fn check_elementwise(name: &str, args: &[Argument]) -> Result<(), ParseError> {
+        if !RESPONSE_FUNCTIONS.contains(&name) {
+            return Err(ParseError::Syntax(format!(
+                "{}() cannot transform a response; use a function of each value such as log(), sqrt() or exp()",
+                name
+            )));
         }
-        _ => unreachable!(),
+        args.iter().try_for_each(|a| match a {
+            Argument::Function { name, args } => check_elementwise(name, args),
+            _ => Ok(()),
+        })
     }
+    check_elementwise(name, &args)?;
+
+    Ok(Response::Function {
+        name: name.to_string(),
+        args,
+    })
+}
+
+/// Parses an arithmetic response such as `I(y / 2)`.
+fn parse_as_is<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Response, ParseError> {
+    let expression = crate::internal::parse_expression::parse_expression(tokens, pos)?;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+
+    fn has_column(expression: &Expression) -> bool {
+        match expression {
+            Expression::Column(_) => true,
+            Expression::Number(_) => false,
+            Expression::Negate(operand) => has_column(operand),
+            Expression::Binary { left, right, .. } => has_column(left) || has_column(right),
+            Expression::Call { args, .. } => args.iter().any(has_column),
+        }
+    }
+    if !has_column(&expression) {
+        return Err(ParseError::Syntax("response I() is not applied to a column".into()));
+    }
+
+    Ok(Response::AsIs(expression))
 }
 
 #[cfg(test)]
@@ -237,4 +386,148 @@ mod tests {
         }
         assert_eq!(pos, 1);
     }
+
+    #[test]
+    fn test_parse_response_transformed() {
+        // log(y) ~ x
+        let tokens = vec![
+            (Token::Log, "log"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "y"),
+            (Token::FunctionEnd, ")"),
+            (Token::Tilde, "~"),
+            (Token::ColumnName, "x"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_response(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            result,
+            Response::Function {
+                name: "log".to_string(),
+                args: vec![Argument::Ident("y".to_string())],
+            }
+        );
+        assert_eq!(pos, 4);
+    }
+
+    #[test]
+    fn test_parse_response_as_is() {
+        // I(y / 2) ~ x
+        let tokens = vec![
+            (Token::ColumnName, "I"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "y"),
+            (Token::Slash, "/"),
+            (Token::Integer, "2"),
+            (Token::FunctionEnd, ")"),
+            (Token::Tilde, "~"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_response(&tokens, &mut pos).unwrap();
+        assert!(matches!(result, Response::AsIs(Expression::Binary { .. })));
+        assert_eq!(pos, 6);
+    }
+
+    #[test]
+    fn test_parse_response_not_elementwise() {
+        // poly(y, 2) ~ x
+        let tokens = vec![
+            (Token::Poly, "poly"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "y"),
+            (Token::Comma, ","),
+            (Token::Integer, "2"),
+            (Token::FunctionEnd, ")"),
+            (Token::Tilde, "~"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_response(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_parse_response_binomial() {
+        // cbind(succ, fail) ~ x
+        let tokens = vec![
+            (Token::ColumnName, "cbind"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "succ"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "fail"),
+            (Token::FunctionEnd, ")"),
+            (Token::Tilde, "~"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_response(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            result,
+            Response::Binomial {
+                successes: "succ".to_string(),
+                failures: "fail".to_string(),
+            }
+        );
+        assert_eq!(pos, 6);
+    }
+
+    #[test]
+    fn test_parse_response_binomial_wrong_column_count() {
+        // cbind(a, b, c)
+        let tokens = vec![
+            (Token::ColumnName, "cbind"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "a"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "b"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "c"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_response(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_parse_response_mvbind() {
+        let tokens = vec![
+            (Token::ColumnName, "mvbind"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "y1"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "y2"),
+            (Token::FunctionEnd, ")"),
+            (Token::Tilde, "~"),
+        ];
+        let mut pos = 0;
+
+        let response = parse_response(&tokens, &mut pos).unwrap();
+        assert_eq!(
+            response,
+            Response::Multivariate(vec!["y1".to_string(), "y2".to_string()])
+        );
+        assert_eq!(pos, 6);
+    }
+
+    #[test]
+    fn test_parse_response_mvbind_repeated_column() {
+        // mvbind(y1, y1) ~ x
+        let tokens = vec![
+            (Token::ColumnName, "mvbind"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "y1"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "y1"),
+            (Token::FunctionEnd, ")"),
+            (Token::Tilde, "~"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_response(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Syntax(_))));
+    }
 }
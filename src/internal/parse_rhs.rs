@@ -3,8 +3,9 @@ use crate::internal::{ast::Term, errors::ParseError, lexer::Token};
 /// Parses the right-hand side of a formula, including terms and intercept specification.
 ///
 /// This function handles the part of the formula that comes after the tilde (`~`).
-/// It parses a sequence of terms separated by plus signs and optionally handles
-/// intercept removal with `- 1`.
+/// It parses a sequence of terms separated by plus or minus signs and resolves the
+/// intercept from the `0`, `1`, `- 1` and `- 0` markers, which may appear in any position.
+/// Any other term preceded by `-` is returned as a `Term::Removal`.
 ///
 /// # Arguments
 /// * `tokens` - Reference to the vector of tokens
@@ -37,15 +38,17 @@ use crate::internal::{ast::Term, errors::ParseError, lexer::Token};
 /// ```
 ///
 /// # How it works
-/// 1. Parses the first term if it exists (no leading plus)
-/// 2. Parses additional terms separated by plus signs
-/// 3. Optionally handles intercept removal with `- 1`
+/// 1. Parses the first term if it exists (an optional leading `+` or `-` is allowed)
+/// 2. Parses additional terms separated by plus or minus signs; `- term` becomes
+///    `Term::Removal(term)` unless the term is an intercept marker
+/// 3. Tracks the intercept in order of appearance: `1`, `+ 1` and `- 0` add it,
+///    `0`, `+ 0` and `- 1` remove it, and the last marker wins (as in R)
 /// 4. Returns the collected terms and intercept flag
 ///
 /// # Grammar Rule
 /// ```text
-/// rhs = [term] ("+" term)* ["-" "1"]
-/// term = column_name | function_call
+/// rhs = [["+" | "-"] term] (("+" | "-") term)*
+/// term = column_name | function_call | interaction | "0" | "1"
 /// ```
 ///
 /// # Use Cases
@@ -58,6 +61,11 @@ use crate::internal::{ast::Term, errors::ParseError, lexer::Token};
 /// - `"x"` → terms=["x"], intercept=true
 /// - `"x + z"` → terms=["x", "z"], intercept=true
 /// - `"x + z - 1"` → terms=["x", "z"], intercept=false
+/// - `"0 + x + z"` → terms=[0, "x", "z"], intercept=false
+/// - `"-1 + x"` → terms=["x"], intercept=false
+/// - `"a*b - a:b"` → terms=[a*b, Removal(a:b)], intercept=true
+/// - `"0 + x + 1"` → terms=[0, "x", 1], intercept=true (last marker wins)
+/// - `"1 - 1"` → terms=[1], intercept=false (an empty model, as in R)
 /// - `""` → terms=[], intercept=true (empty RHS)
 pub fn parse_rhs<'a>(
     tokens: &'a [(Token, &'a str)],
@@ -66,49 +74,60 @@ pub fn parse_rhs<'a>(
     let mut terms = Vec::new();
     let mut has_intercept = true;
 
-    // Parse the first term if present (not a comma or plus)
+    // Parse the first term if present (not a comma, plus or minus)
     if crate::internal::peek::peek(tokens, *pos).is_some()
         && !matches!(
             crate::internal::peek::peek(tokens, *pos).unwrap().0,
-            Token::Comma | Token::Plus
+            Token::Comma | Token::Plus | Token::Minus
         )
     {
-        terms.push(crate::internal::parse_term::parse_term(tokens, pos)?);
-    }
-    // Parse additional terms separated by plus signs
-    while crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Plus)) {
-        terms.push(crate::internal::parse_term::parse_term(tokens, pos)?);
-    }
-
-    // If the token is a minus and a one then it has no intercept
-    if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Minus)) {
-        if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::One)) {
-            // Check if we have an intercept term in the terms list
-            let has_intercept_term = terms.iter().any(|term| matches!(term, crate::internal::ast::Term::Intercept));
-            if has_intercept_term {
-                return Err(crate::internal::errors::ParseError::Syntax(
-                    "cannot have both intercept term and intercept removal (e.g., 'y ~ 1 - 1' is invalid)".into(),
-                ));
+        let term = crate::internal::parse_term::parse_term(tokens, pos)?;
+        apply_intercept_marker(&term, &mut has_intercept);
+        terms.push(term);
+    }
+
+    // Parse additional terms separated by plus or minus signs
+    loop {
+        if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Plus)) {
+            let term = crate::internal::parse_term::parse_term(tokens, pos)?;
+            apply_intercept_marker(&term, &mut has_intercept);
+            terms.push(term);
+        } else if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Minus)) {
+            // `- 1` removes the intercept, `- 0` removes the removal (R semantics)
+            if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::One)) {
+                has_intercept = false;
+            } else if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Zero))
+            {
+                has_intercept = true;
+            } else {
+                let term = crate::internal::parse_term::parse_term(tokens, pos)?;
+                terms.push(Term::Removal(Box::new(term)));
             }
-            has_intercept = false;
         } else {
-            return Err(crate::internal::errors::ParseError::Syntax(
-                "expected '1' after '-' to remove intercept".into(),
-            ));
+            break;
         }
     }
 
-    // Validate that zero terms are not combined with other terms
-    let has_zero_term = terms.iter().any(|term| matches!(term, crate::internal::ast::Term::Zero));
-    if has_zero_term && terms.len() > 1 {
-        return Err(crate::internal::errors::ParseError::Syntax(
-            "zero term (0) cannot be combined with other terms (e.g., 'y ~ 0 + 1' is invalid)".into(),
-        ));
-    }
-
     Ok((terms, has_intercept))
 }
 
+/// Updates the intercept flag for `1` and `0` terms; other terms leave it untouched.
+///
+/// The markers of a group such as `(0 + x)` apply in order, as if the group
+/// were written without parentheses.
+fn apply_intercept_marker(term: &Term, has_intercept: &mut bool) {
+    match term {
+        Term::Intercept => *has_intercept = true,
+        Term::Zero => *has_intercept = false,
+        Term::Group(terms) => {
+            for term in terms {
+                apply_intercept_marker(term, has_intercept);
+            }
+        }
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +211,20 @@ mod tests {
         ];
         let mut pos = 0;
 
+        let result = parse_rhs(&tokens, &mut pos);
+        assert!(result.is_ok());
+        let (terms, has_intercept) = result.unwrap();
+        assert_eq!(terms.len(), 2);
+        assert!(matches!(&terms[1], Term::Removal(term) if matches!(term.as_ref(), Term::Column(name) if name == "y")));
+        assert!(has_intercept);
+        assert_eq!(pos, 3);
+    }
+
+    #[test]
+    fn test_parse_rhs_minus_without_term() {
+        let tokens = vec![(Token::ColumnName, "x"), (Token::Minus, "-")];
+        let mut pos = 0;
+
         let result = parse_rhs(&tokens, &mut pos);
         assert!(result.is_err());
         assert_eq!(pos, 2); // Position advanced past x and minus
@@ -252,4 +285,91 @@ mod tests {
         assert!(has_intercept);
         assert_eq!(pos, 1); // Position at comma
     }
+
+    #[test]
+    fn test_parse_rhs_zero_combined_with_terms() {
+        let tokens = vec![
+            (Token::Zero, "0"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "x"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "z"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_rhs(&tokens, &mut pos);
+        assert!(result.is_ok());
+        let (terms, has_intercept) = result.unwrap();
+        assert_eq!(terms.len(), 3); // 0, x and z
+        assert!(!has_intercept);
+    }
+
+    #[test]
+    fn test_parse_rhs_leading_minus_one() {
+        let tokens = vec![
+            (Token::Minus, "-"),
+            (Token::One, "1"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "x"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_rhs(&tokens, &mut pos);
+        assert!(result.is_ok());
+        let (terms, has_intercept) = result.unwrap();
+        assert_eq!(terms.len(), 1);
+        assert!(!has_intercept);
+        assert_eq!(pos, 4);
+    }
+
+    #[test]
+    fn test_parse_rhs_last_intercept_marker_wins() {
+        // 0 + x + 1 -> the trailing `+ 1` restores the intercept
+        let tokens = vec![
+            (Token::Zero, "0"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "x"),
+            (Token::Plus, "+"),
+            (Token::One, "1"),
+        ];
+        let mut pos = 0;
+        let (_, has_intercept) = parse_rhs(&tokens, &mut pos).unwrap();
+        assert!(has_intercept);
+
+        // x + 0 -> the trailing `+ 0` removes the intercept
+        let tokens = vec![
+            (Token::ColumnName, "x"),
+            (Token::Plus, "+"),
+            (Token::Zero, "0"),
+        ];
+        let mut pos = 0;
+        let (_, has_intercept) = parse_rhs(&tokens, &mut pos).unwrap();
+        assert!(!has_intercept);
+
+        // x - 1 - 0 -> `- 0` cancels the earlier removal
+        let tokens = vec![
+            (Token::ColumnName, "x"),
+            (Token::Minus, "-"),
+            (Token::One, "1"),
+            (Token::Minus, "-"),
+            (Token::Zero, "0"),
+        ];
+        let mut pos = 0;
+        let (_, has_intercept) = parse_rhs(&tokens, &mut pos).unwrap();
+        assert!(has_intercept);
+    }
+
+    #[test]
+    fn test_parse_rhs_removed_intercept_only_model_is_empty() {
+        // 1 - 1 and 1 + 0 both leave an empty model without an intercept, as in R
+        let minus = vec![(Token::One, "1"), (Token::Minus, "-"), (Token::One, "1")];
+        let plus = vec![(Token::One, "1"), (Token::Plus, "+"), (Token::Zero, "0")];
+
+        for tokens in [minus, plus] {
+            let mut pos = 0;
+            let (_, has_intercept) = parse_rhs(&tokens, &mut pos).unwrap();
+            assert!(!has_intercept);
+            assert_eq!(pos, 3);
+        }
+    }
 }
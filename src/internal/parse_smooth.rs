@@ -0,0 +1,351 @@
+use crate::internal::{
+    ast::{Argument, Term},
+    errors::ParseError,
+    lexer::Token,
+};
+
+/// The mgcv basis types a smooth accepts as `bs = "..."`
+const BASES: &[&str] = &[
+    "tp", "ts", "ds", "cr", "cs", "cc", "sos", "ps", "cp", "bs", "re", "mrf", "gp", "so", "fs",
+    "ad",
+];
+
+/// The named arguments of `s()`, as in mgcv
+const SMOOTH_OPTIONS: &[&str] = &["k", "bs", "m", "by", "fx", "xt", "id", "sp", "pc"];
+
+/// The named arguments of the tensor products `te()`, `ti()` and `t2()`, as in mgcv
+const TENSOR_OPTIONS: &[&str] = &[
+    "k", "bs", "m", "d", "by", "fx", "np", "xt", "id", "sp", "pc", "mc", "full", "ord",
+];
+
+/// The largest number of basis functions, and so generated columns, of a smooth
+const MAX_BASIS_DIMENSION: u32 = 10_000;
+
+/// Parses a smooth term such as `s(x, k = 10, bs = "cr")` or `te(x, z)`.
+///
+/// Smooths are the mgcv terms of generalized additive models: `s()` for
+/// smooths of one or more variables and the tensor products `te()`, `ti()` and
+/// `t2()`, which build one marginal basis per variable. The variables come
+/// first, followed by named arguments; `k`, `bs` and `by` are checked here and
+/// the other mgcv arguments (`m`, `fx`, `id`, ...) are kept as given.
+///
+/// # Arguments
+/// * `tokens` - Reference to the vector of tokens
+/// * `pos` - Mutable reference to the current position (will be advanced)
+///
+/// # Returns
+/// * `Result<Term, ParseError>` - The smooth as a function term, or an error
+///
+/// # Example
+/// ```
+/// use fiasto::internal::parse_smooth::parse_smooth;
+/// use fiasto::internal::lexer::Token;
+/// use fiasto::internal::ast::{Argument, Term};
+///
+/// // Parse s(x, k = 5)
+/// let tokens = vec![
+///     (Token::Smooth, "s"),
+///     (Token::FunctionStart, "("),
+///     (Token::ColumnName, "x"),
+///     (Token::Comma, ","),
+///     (Token::ColumnName, "k"),
+///     (Token::Equal, "="),
+///     (Token::Integer, "5"),
+///     (Token::FunctionEnd, ")")
+/// ];
+/// let mut pos = 0;
+///
+/// let term = parse_smooth(&tokens, &mut pos).unwrap();
+/// match term {
+///     Term::Function { name, args } => {
+///         assert_eq!(name, "s");
+///         assert_eq!(args[1], Argument::Named("k".to_string(), Box::new(Argument::Integer(5))));
+///     }
+///     _ => panic!("Expected function term")
+/// }
+/// assert_eq!(pos, 8);
+/// ```
+///
+/// # How it works
+/// 1. Expects `s`, `te`, `ti` or `t2` followed by `(`
+/// 2. Parses the argument list like any function call
+/// 3. Checks that the variables come first and that there is at least one
+/// 4. Rejects arguments mgcv does not know, and checks `k` (an integer of at
+///    least 2), `bs` (an mgcv basis type) and `by` (a variable); tensor products
+///    also take `c(...)` with one value per variable
+/// 5. Checks that the basis has at most 10000 functions, so that `te(x, z, w, k = 2000)`
+///    is rejected instead of generating billions of columns
+///
+/// # Grammar Rule
+/// ```text
+/// smooth = ("s" | "te" | "ti" | "t2") "(" column_name ("," column_name)* ("," named_argument)* ")"
+/// named_argument = "k" "=" k | "bs" "=" basis | "by" "=" column_name | column_name "=" value
+/// k = integer | "c(" integer ("," integer)* ")"
+/// basis = string | "c(" string ("," string)* ")"
+/// ```
+///
+/// # Examples of Valid Inputs
+/// - `"s(x)"` → Function { name: "s", args: [x] }
+/// - `"s(x, k = 10, bs = \"cr\")"` → Function { name: "s", args: [x, k = 10, bs = "cr"] }
+/// - `"s(x, by = g)"` → Function { name: "s", args: [x, by = g] }
+/// - `"te(x, z, k = c(5, 7))"` → Function { name: "te", args: [x, z, k = c(5, 7)] }
+pub fn parse_smooth<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Term, ParseError> {
+    let (tok, _) = crate::internal::peek::peek(tokens, *pos)
+        .cloned()
+        .ok_or(ParseError::Eoi)?;
+    let name = match tok {
+        Token::Smooth => "s",
+        Token::TensorSmooth => "te",
+        Token::TensorInteraction => "ti",
+        Token::T2Smooth => "t2",
+        _ => {
+            return Err(ParseError::Unexpected {
+                expected: "smooth (s, te, ti or t2)",
+                found: Some(tok),
+            })
+        }
+    };
+    *pos += 1;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionStart), "(")?;
+    let args = crate::internal::parse_arg_list::parse_arg_list(tokens, pos)?;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+
+    let variables = args
+        .iter()
+        .take_while(|a| matches!(a, Argument::Ident(_)))
+        .count();
+    if variables == 0 {
+        return Err(ParseError::Syntax(format!(
+            "{}() needs at least one variable",
+            name
+        )));
+    }
+    // Tensor products have one marginal basis per variable
+    let margins = if name == "s" { 1 } else { variables };
+    let mut seen: Vec<&str> = Vec::new();
+    for arg in &args[variables..] {
+        let Argument::Named(key, value) = arg else {
+            return Err(ParseError::Syntax(format!(
+                "{}() takes its variables first, then named arguments such as k = 10",
+                name
+            )));
+        };
+        let options = if name == "s" { SMOOTH_OPTIONS } else { TENSOR_OPTIONS };
+        if !options.contains(&key.as_str()) {
+            return Err(ParseError::Syntax(format!(
+                "{}() has no argument named '{}'",
+                name, key
+            )));
+        }
+        if seen.contains(&key.as_str()) {
+            return Err(ParseError::Syntax(format!(
+                "{}() argument '{}' is given more than once",
+                name, key
+            )));
+        }
+        seen.push(key);
+        check_argument(name, key, value, margins)?;
+    }
+    let dimension = basis_dimension(name, &args, variables);
+    if dimension.is_none_or(|d| d > MAX_BASIS_DIMENSION) {
+        return Err(ParseError::Syntax(format!(
+            "{}() basis is too large: at most {} basis functions are supported",
+            name, MAX_BASIS_DIMENSION
+        )));
+    }
+
+    Ok(Term::Function {
+        name: name.to_string(),
+        args,
+    })
+}
+
+/// Computes the number of basis functions of a smooth, or `None` on overflow.
+///
+/// `s()` has a single basis of dimension `k`, by default 10 for one variable,
+/// 30 for two and so on; a tensor product multiplies the dimensions of its
+/// margins, each `k` or by default 5.
+fn basis_dimension(name: &str, args: &[Argument], variables: usize) -> Option<u32> {
+    let k: Vec<u32> = match args.iter().find_map(|a| match a {
+        Argument::Named(key, value) if key == "k" => Some(value.as_ref()),
+        _ => None,
+    }) {
+        Some(Argument::Function { args, .. }) => args
+            .iter()
+            .filter_map(|a| match a {
+                Argument::Integer(k) => Some(*k),
+                _ => None,
+            })
+            .collect(),
+        Some(Argument::Integer(k)) => vec![*k],
+        _ => Vec::new(),
+    };
+    if name == "s" {
+        return match k.first() {
+            Some(k) => Some(*k),
+            None => 3u32.checked_pow(u32::try_from(variables - 1).ok()?)?.checked_mul(10),
+        };
+    }
+    (0..variables).try_fold(1u32, |dimension, i| {
+        dimension.checked_mul(k.get(i).or(k.first()).copied().unwrap_or(5))
+    })
+}
+
+/// Checks the value of a `k`, `bs` or `by` argument of a smooth.
+fn check_argument(name: &str, key: &str, value: &Argument, margins: usize) -> Result<(), ParseError> {
+    // `k = c(5, 7)` and `bs = c("cr", "ps")` give one value per margin
+    let values = match value {
+        Argument::Function { name: c, args } if c == "c" && key != "by" => args.as_slice(),
+        value => std::slice::from_ref(value),
+    };
+    let (is_valid, expected) = match key {
+        "k" => (
+            values.iter().all(|v| matches!(v, Argument::Integer(k) if *k >= 2)),
+            "an integer of at least 2",
+        ),
+        "bs" => (
+            values
+                .iter()
+                .all(|v| matches!(v, Argument::String(bs) if BASES.contains(&bs.as_str()))),
+            "an mgcv basis such as \"tp\", \"cr\" or \"ps\"",
+        ),
+        "by" => (matches!(value, Argument::Ident(_)), "a variable"),
+        _ => return Ok(()),
+    };
+    if !is_valid || values.is_empty() {
+        return Err(ParseError::Syntax(format!(
+            "{}() argument '{}' must be {}",
+            name, key, expected
+        )));
+    }
+    if values.len() != 1 && values.len() != margins {
+        return Err(ParseError::Syntax(format!(
+            "{}() argument '{}' takes one value or one per variable",
+            name, key
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_smooth_options() {
+        // s(x, bs = "cr", by = g)
+        let tokens = vec![
+            (Token::Smooth, "s"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "bs"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"cr\""),
+            (Token::Comma, ","),
+            (Token::ColumnName, "by"),
+            (Token::Equal, "="),
+            (Token::ColumnName, "g"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let term = parse_smooth(&tokens, &mut pos).unwrap();
+        match term {
+            Term::Function { name, args } => {
+                assert_eq!(name, "s");
+                assert_eq!(args.len(), 3);
+            }
+            _ => panic!("Expected function term"),
+        }
+        assert_eq!(pos, 12);
+    }
+
+    #[test]
+    fn test_parse_smooth_tensor_margins() {
+        // te(x, z, k = c(5, 7))
+        let tokens = vec![
+            (Token::TensorSmooth, "te"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "z"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "k"),
+            (Token::Equal, "="),
+            (Token::C, "c"),
+            (Token::FunctionStart, "("),
+            (Token::Integer, "5"),
+            (Token::Comma, ","),
+            (Token::Integer, "7"),
+            (Token::FunctionEnd, ")"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(parse_smooth(&tokens, &mut pos).is_ok());
+        assert_eq!(pos, 15);
+
+        // s() has a single basis, so it takes a single k
+        let mut tokens = tokens;
+        tokens[0] = (Token::Smooth, "s");
+        let mut pos = 0;
+        assert!(parse_smooth(&tokens, &mut pos).is_err());
+    }
+
+    #[test]
+    fn test_parse_smooth_errors() {
+        // s(k = 10): no variable
+        let tokens = vec![
+            (Token::Smooth, "s"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "k"),
+            (Token::Equal, "="),
+            (Token::Integer, "10"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(parse_smooth(&tokens, &mut pos).is_err());
+
+        // s(x, bs = "xx"): unknown basis
+        let tokens = vec![
+            (Token::Smooth, "s"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "bs"),
+            (Token::Equal, "="),
+            (Token::StringLiteral, "\"xx\""),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(parse_smooth(&tokens, &mut pos).is_err());
+
+        // s(x, foo = 1): unknown argument
+        let tokens = vec![
+            (Token::Smooth, "s"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "foo"),
+            (Token::Equal, "="),
+            (Token::Integer, "1"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(parse_smooth(&tokens, &mut pos).is_err());
+    }
+
+    #[test]
+    fn test_basis_dimension() {
+        let k = |k: u32| Argument::Named("k".to_string(), Box::new(Argument::Integer(k)));
+        let x = Argument::Ident("x".to_string());
+        assert_eq!(basis_dimension("s", std::slice::from_ref(&x), 1), Some(10));
+        assert_eq!(basis_dimension("s", &[x.clone(), x.clone()], 2), Some(30));
+        assert_eq!(basis_dimension("te", &[x.clone(), x.clone(), k(7)], 2), Some(49));
+        assert_eq!(basis_dimension("te", &[x.clone(), x.clone(), k(100000)], 2), None);
+        assert_eq!(basis_dimension("s", &vec![x; 30], 30), None);
+    }
+}
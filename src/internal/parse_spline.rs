@@ -0,0 +1,280 @@
+use crate::internal::{
+    ast::{Argument, Term},
+    errors::ParseError,
+    lexer::Token,
+};
+
+/// Parses a spline basis such as `bs(x, df = 5)` or `ns(x, knots = c(1, 2))`.
+///
+/// The splines are the B-splines `bs()` and natural cubic splines `ns()` of
+/// R's splines package. After the variable, the arguments follow R's order,
+/// so `bs(x, 5)` is `bs(x, df = 5)`; positional arguments are returned as
+/// named ones. Each argument is checked here:
+/// - `df`: the number of basis columns, a positive integer
+/// - `knots`: the interior knots, a number or `c(...)` of numbers
+/// - `degree`: the degree of the piecewise polynomial (`bs()` only, 3 by default)
+/// - `intercept`: whether the basis includes an intercept, `TRUE` or `FALSE`
+/// - `Boundary.knots`: the two boundary knots, `c(lower, upper)`
+///
+/// # Arguments
+/// * `tokens` - Reference to the vector of tokens
+/// * `pos` - Mutable reference to the current position (will be advanced)
+///
+/// # Returns
+/// * `Result<Term, ParseError>` - The spline as a function term with named arguments, or an error
+///
+/// # Example
+/// ```
+/// use fiasto::internal::parse_spline::parse_spline;
+/// use fiasto::internal::lexer::Token;
+/// use fiasto::internal::ast::{Argument, Term};
+///
+/// // Parse bs(x, 5)
+/// let tokens = vec![
+///     (Token::BSplines, "bs"),
+///     (Token::FunctionStart, "("),
+///     (Token::ColumnName, "x"),
+///     (Token::Comma, ","),
+///     (Token::Integer, "5"),
+///     (Token::FunctionEnd, ")")
+/// ];
+/// let mut pos = 0;
+///
+/// match parse_spline(&tokens, &mut pos).unwrap() {
+///     Term::Function { name, args } => {
+///         assert_eq!(name, "bs");
+///         assert_eq!(args[1], Argument::Named("df".to_string(), Box::new(Argument::Integer(5))));
+///     }
+///     _ => panic!("Expected function term")
+/// }
+/// assert_eq!(pos, 6);
+/// ```
+///
+/// # How it works
+/// 1. Expects `bs` or `ns` followed by `(`
+/// 2. Parses the argument list like any function call; the first argument is
+///    the variable, which may itself be a call such as `log(x)`
+/// 3. Names the positional arguments after it in R's order
+/// 4. Checks each value, and that `df` leaves room for the degree and intercept
+///
+/// # Grammar Rule
+/// ```text
+/// spline = ("bs" | "ns") "(" (column_name | function_call) ("," argument)* ")"
+/// argument = [name "="] value
+/// name = "df" | "knots" | "degree" | "intercept" | "Boundary.knots"
+/// ```
+///
+/// # Examples of Valid Inputs
+/// - `"bs(x)"` → Function { name: "bs", args: [x] }
+/// - `"bs(x, df = 6, degree = 2)"` → Function { name: "bs", args: [x, df = 6, degree = 2] }
+/// - `"ns(x, knots = c(1, 2), intercept = TRUE)"` → Function { name: "ns", args: [x, knots = c(1, 2), intercept = TRUE] }
+pub fn parse_spline<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Term, ParseError> {
+    let (tok, _) = crate::internal::peek::peek(tokens, *pos)
+        .cloned()
+        .ok_or(ParseError::Eoi)?;
+    let (name, names): (&str, &[&str]) = match tok {
+        Token::BSplines => ("bs", &["df", "knots", "degree", "intercept", "Boundary.knots"]),
+        Token::NaturalSplines => ("ns", &["df", "knots", "intercept", "Boundary.knots"]),
+        _ => {
+            return Err(ParseError::Unexpected {
+                expected: "spline (bs or ns)",
+                found: Some(tok),
+            })
+        }
+    };
+    *pos += 1;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionStart), "(")?;
+    let mut args = crate::internal::parse_arg_list::parse_arg_list(tokens, pos)?;
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+
+    if !matches!(
+        args.first(),
+        Some(Argument::Ident(_) | Argument::Function { .. })
+    ) {
+        return Err(ParseError::Syntax(format!(
+            "{}() needs a variable as its first argument",
+            name
+        )));
+    }
+    // Positional arguments after the variable follow R's argument order
+    let mut next_positional = 0;
+    let mut seen: Vec<String> = Vec::new();
+    for arg in args.iter_mut().skip(1) {
+        if !matches!(arg, Argument::Named(..)) {
+            let Some(key) = names.get(next_positional) else {
+                return Err(ParseError::Syntax(format!(
+                    "{}() takes at most {} arguments",
+                    name,
+                    names.len() + 1
+                )));
+            };
+            next_positional += 1;
+            *arg = Argument::Named(key.to_string(), Box::new(arg.clone()));
+        }
+        let Argument::Named(key, value) = arg else {
+            unreachable!()
+        };
+        if !names.contains(&key.as_str()) {
+            return Err(ParseError::Syntax(format!(
+                "{}() has no argument named '{}'",
+                name, key
+            )));
+        }
+        if seen.contains(key) {
+            return Err(ParseError::Syntax(format!(
+                "{}() argument '{}' is given more than once",
+                name, key
+            )));
+        }
+        seen.push(key.clone());
+        check_argument(name, key, value)?;
+    }
+
+    let value = |key: &str| {
+        args.iter().find_map(|a| match a {
+            Argument::Named(k, value) if k == key => Some(value.as_ref()),
+            _ => None,
+        })
+    };
+    if value("df").is_some() && value("knots").is_some() {
+        return Err(ParseError::Syntax(format!(
+            "{}() takes either 'df' or 'knots', not both",
+            name
+        )));
+    }
+    // The basis needs a column per polynomial degree (one for ns()) and the intercept
+    if let Some(Argument::Integer(df)) = value("df") {
+        let degree = match value("degree") {
+            Some(Argument::Integer(degree)) => *degree,
+            _ if name == "bs" => 3,
+            _ => 1,
+        };
+        let intercept = matches!(value("intercept"), Some(Argument::Boolean(true))) as u32;
+        if *df < degree + intercept {
+            return Err(ParseError::Syntax(format!(
+                "{}() argument 'df' must be at least {} for this degree and intercept",
+                name,
+                degree + intercept
+            )));
+        }
+    }
+
+    Ok(Term::Function {
+        name: name.to_string(),
+        args,
+    })
+}
+
+/// Checks the value of a spline argument.
+fn check_argument(name: &str, key: &str, value: &Argument) -> Result<(), ParseError> {
+    let is_number = |v: &Argument| matches!(v, Argument::Integer(_) | Argument::Number(_));
+    let numbers = match value {
+        Argument::Function { name: c, args } if c == "c" => Some(args.as_slice()),
+        _ => None,
+    };
+    let (is_valid, expected) = match key {
+        "df" | "degree" => (
+            matches!(value, Argument::Integer(n) if *n > 0),
+            "a positive integer",
+        ),
+        "knots" => (
+            is_number(value) || numbers.is_some_and(|n| !n.is_empty() && n.iter().all(is_number)),
+            "a number or c(...) of numbers",
+        ),
+        "intercept" => (matches!(value, Argument::Boolean(_)), "TRUE or FALSE"),
+        _ => (
+            numbers.is_some_and(|n| n.len() == 2 && n.iter().all(is_number)),
+            "c(lower, upper)",
+        ),
+    };
+    if !is_valid {
+        return Err(ParseError::Syntax(format!(
+            "{}() argument '{}' must be {}",
+            name, key, expected
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spline_knots() {
+        // ns(x, knots = c(1, 2), intercept = TRUE)
+        let tokens = vec![
+            (Token::NaturalSplines, "ns"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "knots"),
+            (Token::Equal, "="),
+            (Token::C, "c"),
+            (Token::FunctionStart, "("),
+            (Token::One, "1"),
+            (Token::Comma, ","),
+            (Token::Integer, "2"),
+            (Token::FunctionEnd, ")"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "intercept"),
+            (Token::Equal, "="),
+            (Token::TrueUpper, "TRUE"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        match parse_spline(&tokens, &mut pos).unwrap() {
+            Term::Function { name, args } => {
+                assert_eq!(name, "ns");
+                assert_eq!(args.len(), 3);
+                assert_eq!(
+                    args[2],
+                    Argument::Named("intercept".to_string(), Box::new(Argument::Boolean(true)))
+                );
+            }
+            _ => panic!("Expected function term"),
+        }
+        assert_eq!(pos, 17);
+    }
+
+    #[test]
+    fn test_parse_spline_errors() {
+        // bs(x, 5, knots = 1): df and knots
+        let tokens = vec![
+            (Token::BSplines, "bs"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::Integer, "5"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "knots"),
+            (Token::Equal, "="),
+            (Token::One, "1"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(parse_spline(&tokens, &mut pos).is_err());
+
+        // bs(x, df = 5, df = 6): repeated argument
+        let tokens = vec![
+            (Token::BSplines, "bs"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "df"),
+            (Token::Equal, "="),
+            (Token::Integer, "5"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "df"),
+            (Token::Equal, "="),
+            (Token::Integer, "6"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(parse_spline(&tokens, &mut pos).is_err());
+    }
+}
@@ -1,4 +1,8 @@
-use crate::internal::{ast::Term, errors::ParseError, lexer::Token};
+use crate::internal::{
+    ast::{Argument, InteractionOperator, Term},
+    errors::ParseError,
+    lexer::Token,
+};
 
 /// Parses a single term in a formula, which can be either a column name or a function call.
 ///
@@ -59,10 +63,21 @@ use crate::internal::{ast::Term, errors::ParseError, lexer::Token};
 /// 2. If followed by FunctionStart, parses as a function call
 /// 3. If not followed by FunctionStart, returns as a column term
 /// 4. For functions, parses argument list and expects closing parenthesis
+/// 5. An opening parenthesis starts a random effect if it contains a top-level
+///    `|` or `||`, otherwise it starts a group of terms
 ///
 /// # Grammar Rule
 /// ```text
-/// term = column_name | function_call
+/// term = nested (("*" | "/") nested)*
+/// nested = interaction ("%in%" interaction)*
+/// interaction = power (":" power)*
+/// power = primary ["^" integer]
+/// primary = column_name | function_call | as_is | autocorrelation | smooth | spline | group | random_effect
+/// as_is = "I(" expression ")"
+/// smooth = ("s" | "te" | "ti" | "t2") "(" ... ")"   (see parse_smooth)
+/// spline = ("bs" | "ns") "(" ... ")"   (see parse_spline)
+/// autocorrelation = ("ar" | "ma" | "arma" | "cosy" | "car" | "sar") "(" ... ")"   (see parse_autocorrelation)
+/// group = "(" term (("+" | "-") term)* ")"
 /// function_call = (poly | column_name) "(" arg_list ")"
 /// arg_list = [argument ("," argument)*]
 /// ```
@@ -77,33 +92,83 @@ use crate::internal::{ast::Term, errors::ParseError, lexer::Token};
 /// - `"x"` → Term::Column("x")
 /// - `"poly(x, 2)"` → Term::Function { name: "poly", args: [x, 2] }
 /// - `"log(price)"` → Term::Function { name: "log", args: [price] }
+/// - `"(a + b)"` → Term::Group([a, b])
+/// - `"(a + b) * c"` → Term::Interaction { left: Group([a, b]), right: c, operator: InteractionAndEffect }
+/// - `"a:b * c"` → Term::Interaction { left: a:b, right: c, operator: InteractionAndEffect }
+/// - `"a / b"` → Term::Interaction { left: a, right: b, operator: Nesting }
+/// - `"(a + b + c)^2"` → Term::Power { base: Group([a, b, c]), order: 2 }
+/// - `"I(x^2)"` → Term::AsIs(Binary { Power, x, 2 })
+/// - `"ar(time, g)"` → Term::Autocorrelation(Arma { time, g, p: 1, q: 0 })
+/// - `"s(x, k = 10)"` → Term::Function { name: "s", args: [x, k = 10] }
+/// - `"bs(x, 5)"` → Term::Function { name: "bs", args: [x, df = 5] }
 pub fn parse_term<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result<Term, ParseError> {
-    // Check if this is a random effect (starts with opening parenthesis)
+    parse_crossing(tokens, pos)
+}
+
+/// Parses a single operand: a column, function call, `I()` expression, group or random effect.
+fn parse_primary<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Term, ParseError> {
+    // A parenthesised expression is a random effect when a `|` or `||` appears
+    // directly inside it, otherwise it is a group of fixed-effect terms
     if crate::internal::peek::peek(tokens, *pos)
         .map(|(t, _)| matches!(t, Token::FunctionStart))
         .unwrap_or(false)
     {
-        // Check if the next token after the opening parenthesis suggests a random effect
-        let saved_pos = *pos;
-        *pos += 1; // Skip the opening parenthesis
-
-        let is_random_effect = crate::internal::peek::peek(tokens, *pos)
-            .map(|(t, _)| {
-                matches!(
-                    t,
-                    Token::One | Token::Zero | Token::Minus | Token::ColumnName
-                )
-            })
-            .unwrap_or(false);
-
-        *pos = saved_pos; // Restore position
-
-        if is_random_effect {
-            // Parse as random effect
+        if is_random_effect(tokens, *pos) {
             let random_effect =
                 crate::internal::parse_random_effect::parse_random_effect(tokens, pos)?;
             return Ok(Term::RandomEffect(random_effect));
         }
+        return parse_group(tokens, pos);
+    }
+
+    // `I(...)` switches to the arithmetic expression grammar
+    let is_as_is = matches!(
+        crate::internal::peek::peek(tokens, *pos),
+        Some((Token::ColumnName, "I"))
+    ) && matches!(
+            crate::internal::peek::peek(tokens, *pos + 1),
+            Some((Token::FunctionStart, _))
+        );
+    if is_as_is {
+        *pos += 2;
+        let expression = crate::internal::parse_expression::parse_expression(tokens, pos)?;
+        crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+        return Ok(Term::AsIs(expression));
+    }
+
+    // `ar(...)`, `cosy(...)`, ... are autocorrelation structures, not transformations
+    let is_autocorrelation = matches!(
+        crate::internal::peek::peek(tokens, *pos),
+        Some((Token::ColumnName, "ar" | "ma" | "arma" | "cosy" | "car" | "sar"))
+    ) && matches!(
+        crate::internal::peek::peek(tokens, *pos + 1),
+        Some((Token::FunctionStart, _))
+    );
+    if is_autocorrelation {
+        let autocorrelation =
+            crate::internal::parse_autocorrelation::parse_autocorrelation(tokens, pos)?;
+        return Ok(Term::Autocorrelation(autocorrelation));
+    }
+
+    // `s(...)`, `te(...)`, ... are smooths with their own argument checks
+    if matches!(
+        crate::internal::peek::peek(tokens, *pos),
+        Some((
+            Token::Smooth | Token::TensorSmooth | Token::TensorInteraction | Token::T2Smooth,
+            _
+        ))
+    ) {
+        return crate::internal::parse_smooth::parse_smooth(tokens, pos);
+    }
+    // `bs(...)` and `ns(...)` are spline bases with their own argument checks
+    if matches!(
+        crate::internal::peek::peek(tokens, *pos),
+        Some((Token::BSplines | Token::NaturalSplines, _))
+    ) {
+        return crate::internal::parse_spline::parse_spline(tokens, pos);
     }
 
     // Parse the leftmost atomic term (column, function, etc.)
@@ -123,7 +188,6 @@ pub fn parse_term<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result
                         | Token::Scale
                         | Token::Standardize
                         | Token::Center
-                        | Token::BSplines
                         | Token::GaussianProcess
                         | Token::Monotonic
                         | Token::MeasurementError
@@ -158,7 +222,6 @@ pub fn parse_term<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result
                 Token::Scale => "scale".to_string(),
                 Token::Standardize => "standardize".to_string(),
                 Token::Center => "center".to_string(),
-                Token::BSplines => "bs".to_string(),
                 Token::GaussianProcess => "gp".to_string(),
                 Token::Monotonic => "mono".to_string(),
                 Token::MeasurementError => "me".to_string(),
@@ -172,11 +235,23 @@ pub fn parse_term<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result
                 Token::Weights => "weights".to_string(),
                 Token::Trials => "trials".to_string(),
                 Token::Censored => "cens".to_string(),
+                Token::Gr => "gr".to_string(),
+                Token::Mm => "mm".to_string(),
+                Token::Mmc => "mmc".to_string(),
+                Token::Cs => "cs".to_string(),
                 Token::ColumnName => name_slice.to_string(),
-                _ => unreachable!(),
+                _ => {
+                    return Err(ParseError::Unexpected {
+                        expected: "function name",
+                        found: Some(tok),
+                    })
+                }
             };
-            let args = crate::internal::parse_arg_list::parse_arg_list(tokens, pos)?;
+            let mut args = crate::internal::parse_arg_list::parse_arg_list(tokens, pos)?;
             crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+            if fname == "poly" {
+                check_poly_degree(&mut args)?;
+            }
             Term::Function { name: fname, args }
         } else {
             match tok {
@@ -213,9 +288,6 @@ pub fn parse_term<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result
                 Token::Center => {
                     return Err(ParseError::Syntax("expected '(' after 'center'".into()))
                 }
-                Token::BSplines => {
-                    return Err(ParseError::Syntax("expected '(' after 'bs'".into()))
-                }
                 Token::GaussianProcess => {
                     return Err(ParseError::Syntax("expected '(' after 'gp'".into()))
                 }
@@ -253,6 +325,10 @@ pub fn parse_term<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result
                 Token::Censored => {
                     return Err(ParseError::Syntax("expected '(' after 'cens'".into()))
                 }
+                Token::Gr => return Err(ParseError::Syntax("expected '(' after 'gr'".into())),
+                Token::Mm => return Err(ParseError::Syntax("expected '(' after 'mm'".into())),
+                Token::Mmc => return Err(ParseError::Syntax("expected '(' after 'mmc'".into())),
+                Token::Cs => return Err(ParseError::Syntax("expected '(' after 'cs'".into())),
                 _ => {
                     return Err(ParseError::Unexpected {
                         expected: "term",
@@ -263,22 +339,231 @@ pub fn parse_term<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result
         }
     };
 
-    // Now check for multiplication (interaction) tokens and build up the interaction chain
-    let mut term = atomic_term;
+    Ok(atomic_term)
+}
+
+/// The largest degree accepted by `poly()`
+const MAX_POLY_DEGREE: u32 = 100;
+
+/// Checks the degree of `poly(x, degree)`.
+///
+/// A numeric degree is a whole number between 1 and 100. A whole number
+/// written as a decimal, such as `2.0`, is stored as the integer `2`.
+fn check_poly_degree(args: &mut [Argument]) -> Result<(), ParseError> {
+    let value = match args.get_mut(1) {
+        Some(Argument::Integer(value)) => Some(*value),
+        Some(Argument::Number(value)) if value.fract() == 0.0 && *value >= 0.0 => {
+            Some(value.min(u32::MAX as f64) as u32)
+        }
+        Some(Argument::Number(_)) => None,
+        _ => return Ok(()),
+    };
+    match value {
+        Some(value) if (1..=MAX_POLY_DEGREE).contains(&value) => {
+            args[1] = Argument::Integer(value);
+            Ok(())
+        }
+        _ => Err(ParseError::Syntax(format!(
+            "poly() degree must be a whole number between 1 and {}",
+            MAX_POLY_DEGREE
+        ))),
+    }
+}
+
+/// Checks whether the parenthesis at `start` opens a random effect.
+///
+/// Scans to the matching closing parenthesis and reports whether a `|` or
+/// `||` appears at the top level of the parenthesised expression, so that
+/// `(x | g)` is a random effect while `(a + b)` and `(log(x) + z)` are groups.
+fn is_random_effect(tokens: &[(Token, &str)], start: usize) -> bool {
+    let mut depth = 0usize;
+    for (tok, _) in &tokens[start..] {
+        match tok {
+            Token::FunctionStart => depth += 1,
+            Token::FunctionEnd => {
+                depth -= 1;
+                if depth == 0 {
+                    return false;
+                }
+            }
+            Token::Pipe | Token::DoublePipe if depth == 1 => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Parses a parenthesised group of terms such as `(a + b)` or `(a + b - a)`.
+///
+/// As on the right-hand side, `- term` becomes `Term::Removal(term)`, and
+/// `- 1` and `- 0` are kept as the markers `0` and `1`. The markers of a
+/// group on its own set the intercept of the formula (`y ~ (0 + x)`); a group
+/// combined with an operator cannot contain them.
+///
+/// # Grammar Rule
+/// ```text
+/// group = "(" term (("+" | "-") term)* ")"
+/// ```
+fn parse_group<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result<Term, ParseError> {
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionStart), "(")?;
+    let mut terms = vec![parse_term(tokens, pos)?];
     loop {
-        if crate::internal::matches::matches(tokens, pos, |t| {
-            matches!(t, Token::InteractionAndEffect | Token::InteractionOnly)
-        }) {
-            // `matches` already consumed the interaction token, so parse the right-hand term now
-            let right = parse_term(tokens, pos)?;
-            term = Term::Interaction {
-                left: Box::new(term),
-                right: Box::new(right),
-            };
+        if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Plus)) {
+            terms.push(parse_term(tokens, pos)?);
+        } else if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Minus)) {
+            if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::One)) {
+                terms.push(Term::Zero);
+            } else if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Zero))
+            {
+                terms.push(Term::Intercept);
+            } else {
+                terms.push(Term::Removal(Box::new(parse_term(tokens, pos)?)));
+            }
         } else {
             break;
         }
     }
+    crate::internal::expect::expect(tokens, pos, |t| matches!(t, Token::FunctionEnd), ")")?;
+    for term in &terms {
+        let term = match term {
+            Term::Removal(term) => term.as_ref(),
+            term => term,
+        };
+        if let Term::Autocorrelation(autocorrelation) = term {
+            return Err(ParseError::Syntax(format!(
+                "autocorrelation structure '{}' cannot be used inside a group",
+                autocorrelation.term()
+            )));
+        }
+    }
+    Ok(Term::Group(terms))
+}
+
+/// Parses a left-associative chain of `*` crossings and `/` nestings.
+///
+/// `*` and `/` bind looser than `%in%` and `:`, so `a:b*c` is `(a:b)*c`.
+fn parse_crossing<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Term, ParseError> {
+    let mut term = parse_nested_within(tokens, pos)?;
+    loop {
+        let operator = match crate::internal::peek::peek(tokens, *pos) {
+            Some((Token::InteractionAndEffect, _)) => InteractionOperator::InteractionAndEffect,
+            Some((Token::Slash, _)) => InteractionOperator::Nesting,
+            _ => break,
+        };
+        *pos += 1;
+        let right = parse_nested_within(tokens, pos)?;
+        let symbol = match operator {
+            InteractionOperator::Nesting => "/",
+            _ => "*",
+        };
+        check_operand(&term, symbol)?;
+        check_operand(&right, symbol)?;
+        term = Term::Interaction {
+            left: Box::new(term),
+            right: Box::new(right),
+            operator,
+        };
+    }
+    Ok(term)
+}
+
+/// Parses a left-associative chain of `%in%` nestings.
+fn parse_nested_within<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Term, ParseError> {
+    let mut term = parse_interaction_only(tokens, pos)?;
+    while crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::NestedWithin)) {
+        let right = parse_interaction_only(tokens, pos)?;
+        check_operand(&term, "%in%")?;
+        check_operand(&right, "%in%")?;
+        term = Term::Interaction {
+            left: Box::new(term),
+            right: Box::new(right),
+            operator: InteractionOperator::NestedWithin,
+        };
+    }
+    Ok(term)
+}
+
+/// Parses a left-associative chain of `:` interactions.
+fn parse_interaction_only<'a>(
+    tokens: &'a [(Token, &'a str)],
+    pos: &mut usize,
+) -> Result<Term, ParseError> {
+    let mut term = parse_power(tokens, pos)?;
+    while crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::InteractionOnly)) {
+        let right = parse_power(tokens, pos)?;
+        check_operand(&term, ":")?;
+        check_operand(&right, ":")?;
+        term = Term::Interaction {
+            left: Box::new(term),
+            right: Box::new(right),
+            operator: InteractionOperator::InteractionOnly,
+        };
+    }
+    Ok(term)
+}
+
+/// Rejects an operand that cannot be combined with `operator`.
+///
+/// Autocorrelation structures describe the residuals rather than adding
+/// columns, so `ar(time):z` or `ar(time)^2` has no meaning. Intercept markers
+/// inside a group such as `(0 + x):z` are rejected too, since only a group on
+/// its own sets the intercept. Smooths and splines generate one column per basis
+/// function, which an interaction cannot name; `s(x, by = z)` varies a smooth
+/// with `z`.
+fn check_operand(term: &Term, operator: &str) -> Result<(), ParseError> {
+    match term {
+        Term::Autocorrelation(autocorrelation) => Err(ParseError::Syntax(format!(
+            "autocorrelation structure '{}' cannot be used with '{}'",
+            autocorrelation.term(),
+            operator
+        ))),
+        Term::Intercept | Term::Zero => Err(ParseError::Syntax(format!(
+            "intercept markers 0 and 1 cannot be used in a group combined with '{}'",
+            operator
+        ))),
+        Term::Function { name, .. } if matches!(name.as_str(), "s" | "te" | "ti" | "t2") => {
+            Err(ParseError::Syntax(format!(
+                "smooth {}() cannot be used with '{}'; use its 'by' argument instead",
+                name, operator
+            )))
+        }
+        Term::Function { name, .. } if matches!(name.as_str(), "bs" | "ns") => Err(ParseError::Syntax(format!(
+            "spline basis {}() cannot be used with '{}'",
+            name, operator
+        ))),
+        Term::Group(terms) => terms.iter().try_for_each(|t| check_operand(t, operator)),
+        _ => Ok(()),
+    }
+}
+
+/// Parses an operand with an optional `^` power, which binds tightest.
+fn parse_power<'a>(tokens: &'a [(Token, &'a str)], pos: &mut usize) -> Result<Term, ParseError> {
+    let term = parse_primary(tokens, pos)?;
+    if crate::internal::matches::matches(tokens, pos, |t| matches!(t, Token::Caret)) {
+        check_operand(&term, "^")?;
+        let start = *pos;
+        let (_, order) = crate::internal::expect::expect(
+            tokens,
+            pos,
+            |t| matches!(t, Token::Integer | Token::One),
+            "interaction order after '^'",
+        )?;
+        let order = order.parse().map_err(|_| {
+            // Leave the position on the order so the error points at it
+            *pos = start;
+            ParseError::Syntax(format!("interaction order '{}' after '^' is too large", order))
+        })?;
+        return Ok(Term::Power {
+            base: Box::new(term),
+            order,
+        });
+    }
     Ok(term)
 }
 
@@ -347,6 +632,67 @@ mod tests {
         assert_eq!(pos, 4);
     }
 
+    #[test]
+    fn test_parse_term_poly_degree() {
+        // poly(x, 2.0) has the integer degree 2
+        let tokens = vec![
+            (Token::Poly, "poly"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Comma, ","),
+            (Token::Number, "2.0"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        match parse_term(&tokens, &mut pos).unwrap() {
+            Term::Function { args, .. } => assert_eq!(args[1], Argument::Integer(2)),
+            _ => panic!("Expected function term"),
+        }
+
+        // poly(x, 1e9) is too large
+        let mut tokens = tokens;
+        tokens[4] = (Token::Number, "1e9");
+        let mut pos = 0;
+        assert!(matches!(parse_term(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_parse_term_keyword_functions() {
+        for (tok, name) in [
+            (Token::Cs, "cs"),
+            (Token::Gr, "gr"),
+            (Token::Mm, "mm"),
+            (Token::Mmc, "mmc"),
+        ] {
+            let tokens = vec![
+                (tok, name),
+                (Token::FunctionStart, "("),
+                (Token::ColumnName, "x"),
+                (Token::FunctionEnd, ")"),
+            ];
+            let mut pos = 0;
+
+            match parse_term(&tokens, &mut pos).unwrap() {
+                Term::Function { name: fname, args } => {
+                    assert_eq!(fname, name);
+                    assert_eq!(args.len(), 1);
+                }
+                _ => panic!("Expected function term"),
+            }
+            assert_eq!(pos, 4);
+        }
+    }
+
+    #[test]
+    fn test_parse_term_keyword_function_without_parentheses() {
+        let tokens = vec![(Token::Cs, "cs")];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_parse_term_poly_without_parentheses() {
         let tokens = vec![(Token::Poly, "poly")];
@@ -424,4 +770,326 @@ mod tests {
         }
         assert_eq!(pos, 1);
     }
+
+    #[test]
+    fn test_parse_term_group_interaction() {
+        // (a + b) * c
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "a"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "b"),
+            (Token::FunctionEnd, ")"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::ColumnName, "c"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_ok());
+        match result.unwrap() {
+            Term::Interaction { left, right, .. } => {
+                assert!(matches!(left.as_ref(), Term::Group(terms) if terms.len() == 2));
+                assert!(matches!(right.as_ref(), Term::Column(name) if name == "c"));
+            }
+            _ => panic!("Expected interaction term"),
+        }
+        assert_eq!(pos, 7);
+    }
+
+    #[test]
+    fn test_parse_term_group_with_function_is_not_random_effect() {
+        // (log(x) + z) - the inner parentheses must not hide the missing `|`
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::Log, "log"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::FunctionEnd, ")"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "z"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_ok());
+        assert!(matches!(result.unwrap(), Term::Group(terms) if terms.len() == 2));
+        assert_eq!(pos, 8);
+    }
+
+    #[test]
+    fn test_parse_term_pipe_inside_parentheses_is_random_effect() {
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::Pipe, "|"),
+            (Token::ColumnName, "g"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_ok());
+        assert!(matches!(result.unwrap(), Term::RandomEffect(_)));
+        assert_eq!(pos, 5);
+    }
+
+    #[test]
+    fn test_parse_term_group_with_removal_and_marker() {
+        // (a + b - a - 1)
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "a"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "b"),
+            (Token::Minus, "-"),
+            (Token::ColumnName, "a"),
+            (Token::Minus, "-"),
+            (Token::One, "1"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        match parse_term(&tokens, &mut pos).unwrap() {
+            Term::Group(terms) => {
+                assert_eq!(terms.len(), 4);
+                assert!(matches!(&terms[2], Term::Removal(t) if matches!(**t, Term::Column(ref a) if a == "a")));
+                assert!(matches!(terms[3], Term::Zero));
+            }
+            _ => panic!("Expected group term"),
+        }
+        assert_eq!(pos, 9);
+    }
+
+    #[test]
+    fn test_parse_term_unclosed_group() {
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "a"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "b"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_term_power() {
+        // (a + b)^2
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "a"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "b"),
+            (Token::FunctionEnd, ")"),
+            (Token::Caret, "^"),
+            (Token::Integer, "2"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_ok());
+        match result.unwrap() {
+            Term::Power { base, order } => {
+                assert!(matches!(base.as_ref(), Term::Group(terms) if terms.len() == 2));
+                assert_eq!(order, 2);
+            }
+            _ => panic!("Expected power term"),
+        }
+        assert_eq!(pos, 7);
+    }
+
+    #[test]
+    fn test_parse_term_power_requires_order() {
+        let tokens = vec![(Token::ColumnName, "a"), (Token::Caret, "^"), (Token::Zero, "0")];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_term_power_order_too_large() {
+        // (a + b)^99999999999 does not fit the order
+        let tokens = vec![
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "a"),
+            (Token::Plus, "+"),
+            (Token::ColumnName, "b"),
+            (Token::FunctionEnd, ")"),
+            (Token::Caret, "^"),
+            (Token::Integer, "99999999999"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(matches!(result, Err(ParseError::Syntax(message)) if message.contains("99999999999")));
+        assert_eq!(pos, 6);
+    }
+
+    #[test]
+    fn test_parse_term_colon_binds_tighter_than_star() {
+        // a:b*c → (a:b)*c
+        let tokens = vec![
+            (Token::ColumnName, "a"),
+            (Token::InteractionOnly, ":"),
+            (Token::ColumnName, "b"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::ColumnName, "c"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_ok());
+        match result.unwrap() {
+            Term::Interaction {
+                left,
+                right,
+                operator,
+            } => {
+                assert_eq!(operator, InteractionOperator::InteractionAndEffect);
+                assert!(matches!(
+                    left.as_ref(),
+                    Term::Interaction {
+                        operator: InteractionOperator::InteractionOnly,
+                        ..
+                    }
+                ));
+                assert!(matches!(right.as_ref(), Term::Column(name) if name == "c"));
+            }
+            _ => panic!("Expected interaction term"),
+        }
+        assert_eq!(pos, 5);
+    }
+
+    #[test]
+    fn test_parse_term_star_is_left_associative() {
+        // a*b*c → (a*b)*c
+        let tokens = vec![
+            (Token::ColumnName, "a"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::ColumnName, "b"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::ColumnName, "c"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_ok());
+        match result.unwrap() {
+            Term::Interaction { left, right, .. } => {
+                assert!(matches!(left.as_ref(), Term::Interaction { .. }));
+                assert!(matches!(right.as_ref(), Term::Column(name) if name == "c"));
+            }
+            _ => panic!("Expected interaction term"),
+        }
+    }
+
+    #[test]
+    fn test_parse_term_nesting() {
+        // a/b %in% c → a/(b %in% c)
+        let tokens = vec![
+            (Token::ColumnName, "a"),
+            (Token::Slash, "/"),
+            (Token::ColumnName, "b"),
+            (Token::NestedWithin, "%in%"),
+            (Token::ColumnName, "c"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(result.is_ok());
+        match result.unwrap() {
+            Term::Interaction {
+                left,
+                right,
+                operator,
+            } => {
+                assert_eq!(operator, InteractionOperator::Nesting);
+                assert!(matches!(left.as_ref(), Term::Column(name) if name == "a"));
+                assert!(matches!(
+                    right.as_ref(),
+                    Term::Interaction {
+                        operator: InteractionOperator::NestedWithin,
+                        ..
+                    }
+                ));
+            }
+            _ => panic!("Expected interaction term"),
+        }
+        assert_eq!(pos, 5);
+    }
+
+    #[test]
+    fn test_parse_term_autocorrelation() {
+        // ar(time, g)
+        let tokens = vec![
+            (Token::ColumnName, "ar"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "time"),
+            (Token::Comma, ","),
+            (Token::ColumnName, "g"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+
+        let result = parse_term(&tokens, &mut pos);
+        assert!(matches!(result.unwrap(), Term::Autocorrelation(_)));
+        assert_eq!(pos, 6);
+    }
+
+    #[test]
+    fn test_parse_term_autocorrelation_operand() {
+        // ar(t):z
+        let tokens = vec![
+            (Token::ColumnName, "ar"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "t"),
+            (Token::FunctionEnd, ")"),
+            (Token::InteractionOnly, ":"),
+            (Token::ColumnName, "z"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(parse_term(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+
+        // ar(t)^2
+        let mut tokens = tokens;
+        tokens[4] = (Token::Caret, "^");
+        tokens[5] = (Token::Integer, "2");
+        let mut pos = 0;
+        assert!(matches!(parse_term(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_parse_term_smooth_operand() {
+        // z * s(x)
+        let tokens = vec![
+            (Token::ColumnName, "z"),
+            (Token::InteractionAndEffect, "*"),
+            (Token::Smooth, "s"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::FunctionEnd, ")"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(parse_term(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_parse_term_spline_operand() {
+        // bs(x):z
+        let tokens = vec![
+            (Token::BSplines, "bs"),
+            (Token::FunctionStart, "("),
+            (Token::ColumnName, "x"),
+            (Token::FunctionEnd, ")"),
+            (Token::InteractionOnly, ":"),
+            (Token::ColumnName, "z"),
+        ];
+        let mut pos = 0;
+        assert!(matches!(parse_term(&tokens, &mut pos), Err(ParseError::Syntax(_))));
+    }
 }
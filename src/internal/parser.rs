@@ -29,12 +29,17 @@
 //!
 //! let formula = "y ~ x + poly(x, 2) + (1 | group), family = gaussian";
 //! let mut parser = Parser::new(formula).unwrap();
-//! let (response, terms, has_intercept, family) = parser.parse_formula().unwrap();
+//! let parsed = parser.parse_formula().unwrap();
 //! 
-//! // response = "y"
-//! // terms = [Term::Column("x"), Term::Function{...}, Term::RandomEffect{...}]
-//! // has_intercept = true
-//! // family = Some(Family::Gaussian)
+//! // parsed.response = Some(Response::Single("y"))
+//! // parsed.terms = [Term::Column("x"), Term::Function{...}, Term::RandomEffect{...}]
+//! // parsed.has_intercept = true
+//! // parsed.family = Some(FamilySpec { family: Family::Gaussian, links: [...] })
+//! // parsed.parameters = [] (sub-formulas such as `sigma ~ x`)
+//! // parsed.nonlinear = None (the expression of an `nl = TRUE` formula)
+//! // parsed.additions = [] (response addition terms such as `y | trials(n)`)
+//! // parsed.fixed = [] (fixed parameters such as `sigma2 = "sigma1"`)
+//! // parsed.options = [] (formula options such as `fill = "mean"`)
 //! ```
 //!
 //! ## Error Handling
@@ -46,7 +51,7 @@
 //! - Missing required arguments
 
 use crate::internal::{
-    ast::{Family, Response, Term},
+    ast::{ModelComponent, ParsedFormula},
     errors::ParseError,
     lexer::Token,
 };
@@ -163,32 +168,42 @@ impl<'a> Parser<'a> {
     ///
     /// # Returns
     /// 
-    /// A tuple containing:
-    /// * `String` - The response variable (left side of ~)
-    /// * `Vec<Term>` - All terms in the formula (fixed effects, random effects, etc.)
-    /// * `bool` - Whether the model includes an intercept
-    /// * `Option<Family>` - The distribution family (if specified)
+    /// A `ParsedFormula` with:
+    /// * `response` - The response variable (left side of ~), `None` for one-sided formulas
+    /// * `terms` - All terms in the formula (fixed effects, random effects, etc.)
+    /// * `has_intercept` - Whether the model includes an intercept
+    /// * `family` - The distribution family and its links (if specified)
+    /// * `parameters` - Sub-formulas for model parameters such as `sigma ~ x`
+    /// * `nonlinear` - The non-linear expression of an `nl = TRUE` formula
+    /// * `additions` - Response addition terms such as `trials(n)` in `y | trials(n) ~ x`
+    /// * `fixed` - Fixed parameters such as `sigma2 = "sigma1"`
+    /// * `options` - Formula options such as `fill = "mean"`
     ///
     /// # Examples
     ///
     /// ```rust
 /// use fiasto::internal::parser::Parser;
-/// use fiasto::internal::ast::Response;
+/// use fiasto::internal::ast::{ParsedFormula, Response};
 ///
 /// let formula = "y ~ x + (1 | group), family = gaussian";
 /// let mut parser = Parser::new(formula).unwrap();
-/// let (response, terms, has_intercept, family) = parser.parse_formula().unwrap();
+/// let ParsedFormula { response, has_intercept, family, parameters, nonlinear, additions, fixed, options, .. } = parser.parse_formula().unwrap();
 /// 
 /// match response {
-///     Response::Single(name) => assert_eq!(name, "y"),
+///     Some(Response::Single(name)) => assert_eq!(name, "y"),
 ///     _ => panic!("Expected single response")
 /// }
 /// assert!(has_intercept);
 /// assert!(family.is_some());
+/// assert!(parameters.is_empty());
+/// assert!(nonlinear.is_none());
+/// assert!(additions.is_empty());
+/// assert!(fixed.is_empty());
+/// assert!(options.is_empty());
 /// ```
     pub fn parse_formula(
         &mut self,
-    ) -> Result<(Response, Vec<Term>, bool, Option<Family>), ParseError> {
+    ) -> Result<ParsedFormula, ParseError> {
         match crate::internal::parse_formula::parse_formula(&self.tokens, &mut self.pos) {
             Ok(v) => Ok(v),
             Err(e) => {
@@ -197,4 +212,50 @@ impl<'a> Parser<'a> {
             }
         }
     }
+
+    /// Checks whether the input is a model specification rather than a formula
+    ///
+    /// Model specifications start with a component: `bf(`, `brmsformula(`,
+    /// `lf(`, `nlf(`, an autocorrelation such as `cor_ar(` or a parenthesized
+    /// formula such as `(y ~ x) + gaussian()`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fiasto::internal::parser::Parser;
+    ///
+    /// assert!(Parser::new("bf(y ~ x) + gaussian()").unwrap().is_model_spec());
+    /// assert!(!Parser::new("y ~ x").unwrap().is_model_spec());
+    /// ```
+    pub fn is_model_spec(&self) -> bool {
+        match self.tokens.first() {
+            Some((Token::FunctionStart, _)) => true,
+            Some((
+                Token::ColumnName,
+                "bf" | "brmsformula" | "lf" | "nlf" | "cor_ar" | "cor_ma" | "cor_arma" | "cor_cosy"
+                | "cor_car" | "cor_sar",
+            )) => {
+                matches!(self.tokens.get(1), Some((Token::FunctionStart, _)))
+            }
+            _ => false,
+        }
+    }
+
+    /// Parses a model specification and returns its components
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fiasto::internal::parser::Parser;
+    /// use fiasto::internal::ast::ModelComponent;
+    ///
+    /// let mut parser = Parser::new("bf(y ~ x) + lf(sigma ~ z) + gaussian()").unwrap();
+    /// let components = parser.parse_model_spec().unwrap();
+    ///
+    /// assert_eq!(components.len(), 3);
+    /// assert!(matches!(components[1], ModelComponent::Linear { .. }));
+    /// ```
+    pub fn parse_model_spec(&mut self) -> Result<Vec<ModelComponent>, ParseError> {
+        crate::internal::parse_model_spec::parse_model_spec(&self.tokens, &mut self.pos)
+    }
 }
@@ -105,8 +105,8 @@
 //! let result = parse_formula("bind(y1, y2) ~ x + z");
 //! match result {
 //!     Ok(metadata) => {
-//!         // The metadata will include both y1 and y2 as response variables
-//!         // with ID 1, and x, z as predictors with IDs 2, 3
+//!         // The metadata will include y1 and y2 as response variables
+//!         // with IDs 1, 2, and x, z as predictors with IDs 3, 4
 //!         println!("{}", serde_json::to_string_pretty(&metadata).unwrap());
 //!     }
 //!     Err(e) => eprintln!("Error: {}", e),
@@ -218,12 +218,30 @@
 //!
 //! ### Basic Models
 //! - Linear models: `y ~ x + z`
+//! - Column names: `y ~ Sepal.Length + _id + é_score` or backtick-quoted `` `revenue 2024` ``
 //! - Intercept-only models: `y ~ 1`
-//! - No-intercept models: `y ~ 0`
-//! - Multivariate models: `bind(y1, y2) ~ x + z`
+//! - One-sided formulas: `~ x + z`
+//! - No-intercept models: `y ~ 0`, `y ~ 0 + x` or `y ~ x - 1`
+//! - Term removal: `y ~ a*b*c - a:b:c`
+//! - Multivariate models: `bind(y1, y2) ~ x + z`, `mvbind(y1, y2) ~ x` or `bf(y1 ~ x) + bf(y2 ~ z) + set_rescor(TRUE)`
+//! - Transformed and binomial responses: `log(y) ~ x` or `cbind(successes, failures) ~ x`
+//! - Response addition terms: `y | trials(n) ~ x`, `y | cens(c) + weights(w) ~ x` or `y | trunc(lb = 0) ~ x`
 //! - Polynomial terms: `y ~ poly(x, 3)`
-//! - Interactions: `y ~ x:z` or `y ~ x*z`
-//! - Family specification: `y ~ x, family = gaussian`
+//! - Nested functions: `y ~ offset(log(exposure)) + poly(log(x), 2)`
+//! - Numeric arguments: `y ~ poly(x, 10) + lag(x, -1) + trunc(z, lb = 0.5)`
+//! - Inline arithmetic: `y ~ I(x^2) + I(price / 1000)`
+//! - Smooth terms: `y ~ s(x, k = 10, bs = "cr")`, `y ~ s(x, by = g)` or `y ~ te(x, z, k = c(5, 7))`
+//! - Splines: `y ~ bs(x, df = 5)`, `y ~ bs(x, knots = c(1, 2), degree = 2)` or `y ~ ns(x, 4)`
+//! - Interactions: `y ~ x:z`, `y ~ x*z`, `y ~ (a + b)*c` or `y ~ (a + b + c)^2`
+//! - Nesting: `y ~ block/plot` or `y ~ a + b %in% a`
+//! - Family specification: `y ~ x, family = gaussian`, `y ~ x, family = binomial(link = "probit")` or `bf(y ~ x, zi ~ z) + zero_inflated_poisson()`
+//! - Distributional parameters: `y ~ x, sigma ~ x + (1 | g)`
+//! - Mixture families: `y ~ x, mu2 ~ z, sigma2 = "sigma1", family = mixture(gaussian, gaussian)`
+//! - Fixed parameters: `rt | dec(decision) ~ x, bias = 0.5, family = wiener`
+//! - Non-linear models: `y ~ a1 - a2^x, a1 + a2 ~ 1, nl = TRUE` or `y ~ a1 - a2^x, a1 ~ 1, a2 ~ x + (x | g), nl = TRUE`
+//! - Model specifications: `bf(y ~ x, sigma ~ z) + gaussian()` or `(y ~ 1) + nlf(sigma ~ a * exp(b * x), a ~ x) + lf(b ~ z, dpar = "sigma")`
+//! - Autocorrelation: `y ~ x + ar(time, g, p = 2)`, `y ~ x + car(M)` or `(y ~ x) + cor_ar(~1|g)`
+//! - Formula options: `y ~ x + (1 | g), fill = "mean"` or `y ~ x, center = FALSE, decomp = "QR"`
 //!
 //! ### Random Effects
 //! - Random intercepts: `(1 | group)`
@@ -234,28 +252,42 @@
 
 pub mod internal {
     pub mod ast;
+    pub mod build_metadata;
+    pub mod check_model;
     pub mod data_structures;
     pub mod errors;
     pub mod expect;
+    pub mod is_call;
     pub mod lexer;
     pub mod matches;
     pub mod meta_builder;
+    pub mod model;
     pub mod new;
     pub mod next;
     pub mod parse;
+    pub mod parse_addition_terms;
     pub mod parse_arg;
     pub mod parse_arg_list;
+    pub mod parse_autocorrelation;
+    pub mod parse_expression;
     pub mod parse_family;
     pub mod parse_formula;
+    pub mod parse_model_spec;
+    pub mod parse_parameter_formula;
     pub mod parse_random_effect;
     pub mod parse_response;
     pub mod parse_rhs;
+    pub mod parse_smooth;
+    pub mod parse_spline;
     pub mod parse_term;
     pub mod parser;
     pub mod peek;
 }
 
-use internal::parse::{MetaBuilder, Parser, Term};
+use internal::build_metadata::build_metadata;
+use internal::check_model::check_model;
+use internal::model::{assemble_model, Model};
+use internal::parse::Parser;
 use serde_json::Value;
 
 /// Parse a statistical formula string and return comprehensive metadata as JSON
@@ -270,25 +302,59 @@ use serde_json::Value;
 ///
 /// ## Basic Syntax
 /// - **Response**: `y ~ x` (y is the response variable)
+/// - **Transformed Response**: `log(y) ~ x` (the response `y` gets a `log` transformation and generates `y_log`; `I(y / 2) ~ x` generates `I(y / 2)`)
+/// - **Binomial Response**: `cbind(successes, failures) ~ x` (both columns form one response, described under `response`)
+/// - **Multivariate Responses**: `mvbind(y1, y2) ~ x` or `bf(y1 ~ x) + bf(y2 ~ z) + set_rescor(TRUE)`
+///   (each response gets its own ID and its own block under `responses`; `lf(sigma ~ w, resp = "y2")` targets one response)
+/// - **One-Sided Formulas**: `~ x + z` (no response: `response_variable_count` is 0 and IDs start at 1)
+/// - **Response Additions**: `y | trials(n) ~ x`, `y | se(s, sigma = TRUE) + weights(w, scale = TRUE) ~ x` (`trials`, `cens`, `weights`, `se`, `trunc`, `mi`, `dec` and `rate`, collected under `response`)
 /// - **Fixed Effects**: `y ~ x + z + w` (multiple predictors)
-/// - **Intercept Control**: `y ~ x - 1` (no intercept) or `y ~ x + 0` (explicit intercept)
-/// - **Family Specification**: `y ~ x, family = gaussian` (distribution family)
+/// - **Column Names**: `Sepal.Length`, `_id`, `é_score` or backtick-quoted `` `revenue 2024` ``
+/// - **Keywords as Names**: `lag ~ id + by` (function and option names are only keywords where they have meaning)
+/// - **Intercept Control**: `y ~ x - 1` or `y ~ 0 + x` (no intercept), `y ~ 1 + x` (explicit intercept).
+///   The markers may appear anywhere on the right-hand side and the last one wins.
+/// - **Term Removal**: `y ~ x + z + w - w` (removes `w` after expansion; unknown terms produce a warning)
+/// - **Family Specification**: `y ~ x, family = gaussian` or `family = binomial(link = "probit")` (distribution family;
+///   the brms families such as `student`, `Gamma`, `beta`, `negbinomial`, `cumulative` or `hurdle_gamma` are described under `family`
+///   with their distributional parameters and links, and parameter formulas such as `sigma ~ x` must predict one of those parameters)
+/// - **Parameter Formulas**: `y ~ x, sigma ~ z + (1 | g)` (each parameter gets its own `columns` and `all_generated_columns` under `dpars`)
+/// - **Mixture Families**: `family = mixture(gaussian, gaussian)` or `mixture(gaussian, nmix = 3)` (component parameters `mu1`, `sigma2`, `theta1`, ...;
+///   the component families are listed under `family.components`)
+/// - **Fixed Parameters**: `rt | dec(d) ~ x, bias = 0.5` or `sigma2 = "sigma1"` (fixes a parameter to a constant or sets it equal
+///   to another one; with a family both must belong to it; listed under `fixed_parameters`)
+/// - **Non-Linear Formulas**: `y ~ a1 - a2^x, a1 ~ 1, a2 ~ x, nl = TRUE` or `a1 + a2 ~ 1` for a shared right-hand side (the expression tree and its parameters under `nonlinear`, one block per parameter under `nlpars`)
+/// - **Model Specifications**: `bf(y ~ a * x, nl = TRUE) + lf(a ~ 1 + (1 | g)) + gaussian()` or `(y ~ 1) + nlf(sigma ~ a * exp(b * x), a ~ x)`
+///   (components joined with `+`: `bf()`, `lf()`, `nlf()`, a family and `set_rescor()`; every formula is listed with its role under `formulas`)
+/// - **Autocorrelation**: `y ~ x + ar(time, g)`, `ma()`, `arma()`, `cosy()`, `car(M)`, `sar(W)` or a `cor_ar(~1|g)` component
+///   (each structure is described with its arguments under `autocorrelation`)
+/// - **Formula Options**: `y ~ x + (1 | g), fill = "mean"`, `nl = TRUE`, `center = FALSE`, `sparse = TRUE` or `decomp = "QR"`
+///   (listed under `metadata.options`; a `name = value` clause that is neither a known option nor a distributional parameter is kept with a warning)
 ///
 /// ## Transformations
 /// - **Polynomial**: `poly(x, 3)` (orthogonal polynomials of degree 3)
 /// - **Logarithm**: `log(x)` (natural logarithm)
 /// - **Custom Functions**: `scale(x)`, `center(x)`, `factor(x)`, etc.
+/// - **Nested Functions**: `offset(log(exposure))` (a chain of transformations, innermost first, generating `exposure_log_offset`)
+/// - **Numeric Arguments**: `lag(x, -1)`, `trunc(x, lb = 0.5)`, `I(x * 1e-3)` (decimals, negatives and scientific notation)
+/// - **Inline Arithmetic**: `I(x^2)`, `I(price / 1000)` (expression tree in the transformation parameters)
+/// - **Smooths**: `s(x, k = 10, bs = "cr")`, `s(x, z)`, `s(x, by = g)`, `te(x, z)`, `ti(x, z)`, `t2(x, z, k = c(5, 7))`
+///   (variables, basis type and dimension in the transformation parameters, with the mgcv defaults)
+/// - **Splines**: `bs(x, df = 5)`, `bs(x, knots = c(1, 2), degree = 2, intercept = TRUE)`, `ns(x, 4, Boundary.knots = c(0, 10))`
+///   (`df`, `degree`, `knots`, `intercept` and `Boundary.knots` in the transformation parameters)
 ///
 /// ## Interactions
-/// - **Simple**: `x:z` (interaction between x and z)
+/// - **Simple**: `x:z` (interaction between x and z only, no main effects)
 /// - **Full**: `x*z` (equivalent to `x + z + x:z`)
+/// - **Grouped**: `(a + b)*c` (equivalent to `a + b + c + a:c + b:c`)
+/// - **Limited Order**: `(a + b + c)^2` (main effects and two-way interactions only)
+/// - **Nesting**: `a/b` (equivalent to `a + a:b`) and `b %in% a` (equivalent to `b:a`)
 ///
 /// ## Random Effects (brms-style)
 /// - **Random Intercepts**: `(1 | group)` (random intercepts by group)
 /// - **Random Slopes**: `(0 + x | group)` (random slopes for x by group)
 /// - **Correlated Effects**: `(x | group)` (random intercept + slope, correlated)
 /// - **Uncorrelated Effects**: `(x || group)` (random intercept + slope, uncorrelated)
-/// - **Cross-Parameter**: `(x |ID| group)` (cross-parameter correlations)
+/// - **Cross-Parameter**: `(x |ID1| group)` or `gr(group, id = 2)` (shared IDs are linked across formula blocks in `correlation_ids`)
 /// - **Enhanced Grouping**: `(1 | gr(group, cor = FALSE))` (advanced grouping options)
 /// - **Multi-Membership**: `(1 | mm(group1, group2))` (multiple membership)
 /// - **Nested**: `(1 | group1/group2)` (nested grouping)
@@ -420,10 +486,13 @@ use serde_json::Value;
 ///
 /// Variables can have multiple roles in the model:
 ///
-/// - **Response**: The dependent variable (always gets ID 1)
+/// - **Response**: The dependent variable (gets ID 1, multivariate responses 1, 2, ...; one-sided formulas have none)
 /// - **FixedEffect**: Predictor variables in the fixed effects part
 /// - **GroupingVariable**: Variables used for grouping in random effects
 /// - **RandomEffect**: Variables that have random effects
+/// - **ResponseAddition**: Variables read by response addition terms such as `trials(n)`
+/// - **Autocorrelation**: Time and grouping variables of autocorrelation structures such as `ar(time, g)`
+/// - **NonLinearCovariate**: Covariates read directly by a non-linear (`nl = TRUE`) expression
 ///
 /// # Generated Columns
 ///
@@ -431,6 +500,9 @@ use serde_json::Value;
 /// - `poly(x, 2)` generates `x_poly_1`, `x_poly_2`
 /// - `log(x)` generates `x_log`
 /// - `x:z` interaction generates `x_z`
+/// - `s(x, k = 10)` generates `x_s_1` .. `x_s_10`, one column per basis function
+/// - `bs(x, df = 5)` generates `x_bs_1` .. `x_bs_5`; with knots, `bs()` has knots + degree + intercept
+///   columns and `ns()` knots + 1 + intercept
 ///
 /// The `all_generated_columns` array contains all generated column names ordered by variable ID.
 ///
@@ -451,7 +523,12 @@ use serde_json::Value;
 /// - Fast pattern matching
 pub fn parse_formula(formula: &str) -> Result<Value, Box<dyn std::error::Error>> {
     let mut p = Parser::new(formula)?;
-    let (response, terms, mut has_intercept, family_opt) = match p.parse_formula() {
+    let model = if p.is_model_spec() {
+        p.parse_model_spec().and_then(assemble_model)
+    } else {
+        p.parse_formula().map(Model::from_formula)
+    };
+    let model = match model.and_then(check_model) {
         Ok(v) => v,
         Err(e) => {
             // Print pretty, colored error by default for CLI users
@@ -459,34 +536,7 @@ pub fn parse_formula(formula: &str) -> Result<Value, Box<dyn std::error::Error>>
             return Err(Box::new(e));
         }
     };
-
-    let mut mb = MetaBuilder::new();
-    mb.push_response(&response);
-
-    // Check if we have a zero term, which means no intercept
-    let has_zero_term = terms.iter().any(|t| matches!(t, Term::Zero));
-    if has_zero_term {
-        has_intercept = false;
-    }
-
-    for t in terms {
-        match t {
-            Term::Column(name) => mb.push_plain_term(&name),
-            Term::Function { name, args } => mb.push_function_term(&name, &args),
-            Term::Interaction { left, right } => mb.push_interaction(&left, &right),
-            Term::RandomEffect(random_effect) => mb.push_random_effect(&random_effect),
-            Term::Intercept => {
-                // Intercept terms are handled by the has_intercept flag in the build method
-                // No additional processing needed here
-            }
-            Term::Zero => {
-                // Zero terms indicate no intercept - this is handled by the has_intercept flag
-                // No additional processing needed here
-            }
-        }
-    }
-    let family_name = family_opt.map(|f| format!("{:?}", f).to_lowercase());
-    let meta = mb.build(formula, has_intercept, family_name);
+    let meta = build_metadata(formula, model);
 
     Ok(serde_json::to_value(meta)?)
 }
@@ -863,41 +913,69 @@ mod tests {
     }
 
     #[test]
-    fn test_invalid_intercept_syntax() {
-        // Test that y ~ 1 - 1 fails (contradictory syntax)
-        let formula = "y ~ 1 - 1";
-        let result = parse_formula(formula);
-
-        assert!(
-            result.is_err(),
-            "y ~ 1 - 1 should fail because it's contradictory syntax"
-        );
-
-        if let Err(e) = result {
-            let error_msg = format!("{}", e);
-            assert!(
-                error_msg.contains("cannot have both intercept term and intercept removal"),
-                "Error should mention contradictory syntax"
+    fn test_removed_intercept_only_model_is_empty() {
+        // As in R, y ~ 1 - 1 and y ~ 1 + 0 are both the empty model y ~ 0
+        let expected = parse_formula("y ~ 0").unwrap();
+
+        for formula in ["y ~ 1 - 1", "y ~ 1 + 0"] {
+            let result = parse_formula(formula).expect("Should parse successfully");
+            let metadata = result.get("metadata").expect("Should have metadata");
+            assert_eq!(
+                metadata.get("has_intercept").unwrap().as_bool(),
+                Some(false),
+                "has_intercept mismatch for {}",
+                formula
+            );
+            assert_eq!(
+                result["all_generated_columns"], expected["all_generated_columns"],
+                "generated columns mismatch for {}",
+                formula
             );
         }
     }
 
     #[test]
-    fn test_invalid_zero_combination() {
-        // Test that y ~ 0 + 1 fails (0 cannot be combined with other terms)
-        let formula = "y ~ 0 + 1";
-        let result = parse_formula(formula);
+    fn test_zero_combined_with_terms() {
+        // Test the common no-intercept spelling: y ~ 0 + x + z
+        let formula = "y ~ 0 + x + z";
+        let result = parse_formula(formula).expect("Should parse successfully");
 
-        assert!(
-            result.is_err(),
-            "y ~ 0 + 1 should fail because 0 cannot be combined with other terms"
+        let all_columns: Vec<&str> = result
+            .get("all_generated_columns")
+            .expect("Should have all_generated_columns")
+            .as_array()
+            .expect("Should be an array")
+            .iter()
+            .map(|col| col.as_str().unwrap())
+            .collect();
+        assert_eq!(all_columns, vec!["y", "x", "z"]);
+
+        let metadata = result.get("metadata").expect("Should have metadata");
+        assert_eq!(
+            metadata.get("has_intercept").unwrap().as_bool(),
+            Some(false)
         );
+    }
 
-        if let Err(e) = result {
-            let error_msg = format!("{}", e);
-            assert!(
-                error_msg.contains("zero term (0) cannot be combined with other terms"),
-                "Error should mention zero term combination restriction"
+    #[test]
+    fn test_intercept_markers_last_one_wins() {
+        // (formula, expected has_intercept)
+        let cases = [
+            ("y ~ -1 + x", false),
+            ("y ~ x + 0", false),
+            ("y ~ 0 + x + 1", true),
+            ("y ~ x - 1 + 1", true),
+            ("y ~ 0 + 1", true),
+        ];
+
+        for (formula, expected) in cases {
+            let result = parse_formula(formula).expect("Should parse successfully");
+            let metadata = result.get("metadata").expect("Should have metadata");
+            assert_eq!(
+                metadata.get("has_intercept").unwrap().as_bool(),
+                Some(expected),
+                "has_intercept mismatch for {}",
+                formula
             );
         }
     }
@@ -927,9 +1005,9 @@ mod tests {
         let y2_roles = y2_info.get("roles").expect("Should have roles").as_array().expect("Should be array");
         assert!(y2_roles.iter().any(|r| r.as_str() == Some("Response")), "y2 should have Response role");
 
-        // Check that both have ID 1 (response variables)
+        // Check that each response has its own ID, in formula order
         assert_eq!(y1_info.get("id").expect("Should have id").as_u64(), Some(1));
-        assert_eq!(y2_info.get("id").expect("Should have id").as_u64(), Some(1));
+        assert_eq!(y2_info.get("id").expect("Should have id").as_u64(), Some(2));
 
         // Check generated columns include both response variables
         let all_columns = result
@@ -962,20 +1040,22 @@ mod tests {
             .as_object()
             .expect("Should be an object");
 
-        for var_name in &["y1", "y2", "y3"] {
+        for (id, var_name) in (1..).zip(&["y1", "y2", "y3"]) {
             assert!(columns.contains_key(*var_name), "Should contain {} response variable", var_name);
             
-            let var_info = columns.get(*var_name).expect(&format!("Should have {}", var_name));
+            let var_info = columns
+                .get(*var_name)
+                .unwrap_or_else(|| panic!("Should have {}", var_name));
             let roles = var_info.get("roles").expect("Should have roles").as_array().expect("Should be array");
             assert!(roles.iter().any(|r| r.as_str() == Some("Response")), "{} should have Response role", var_name);
-            assert_eq!(var_info.get("id").expect("Should have id").as_u64(), Some(1));
+            assert_eq!(var_info.get("id").expect("Should have id").as_u64(), Some(id));
         }
 
-        // Check that predictor variables have correct IDs (starting from 2)
+        // Check that predictor variables have correct IDs (after the responses)
         let x_info = columns.get("x").expect("Should have x");
         let z_info = columns.get("z").expect("Should have z");
-        assert_eq!(x_info.get("id").expect("Should have id").as_u64(), Some(2));
-        assert_eq!(z_info.get("id").expect("Should have id").as_u64(), Some(3));
+        assert_eq!(x_info.get("id").expect("Should have id").as_u64(), Some(4));
+        assert_eq!(z_info.get("id").expect("Should have id").as_u64(), Some(5));
     }
 
     #[test]
@@ -1016,4 +1096,1490 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_remove_highest_order_interaction() {
+        let result = parse_formula("y ~ a*b*d - a:b:d").expect("Should parse successfully");
+
+        let all_columns: Vec<&str> = result
+            .get("all_generated_columns")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|col| col.as_str().unwrap())
+            .collect();
+        assert_eq!(
+            all_columns,
+            vec!["y", "intercept", "a", "b", "d", "a_b", "a_d", "b_d"]
+        );
+
+        let columns = result.get("columns").unwrap();
+        assert!(columns.get("a_b_d").is_none());
+        assert_eq!(columns.get("b_d").unwrap().get("id").unwrap(), 7);
+        let a_interactions = columns
+            .get("a")
+            .unwrap()
+            .get("interactions")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(a_interactions.len(), 2);
+        assert!(a_interactions.iter().all(|i| i.get("order").unwrap() == 2));
+    }
+
+    #[test]
+    fn test_remove_plain_term() {
+        let result = parse_formula("y ~ x + z + w - w").expect("Should parse successfully");
+
+        let columns = result.get("columns").unwrap();
+        assert!(columns.get("w").is_none());
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(all_columns, &serde_json::json!(["y", "intercept", "x", "z"]));
+
+        let warnings = result.get("metadata").unwrap().get("warnings").unwrap();
+        assert_eq!(warnings, &serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_remove_main_effect_keeps_interaction() {
+        let result = parse_formula("y ~ a*b - a").expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(all_columns, &serde_json::json!(["y", "intercept", "b", "a_b"]));
+        // `a` is still described because it participates in `a_b`
+        assert!(result.get("columns").unwrap().get("a").is_some());
+    }
+
+    #[test]
+    fn test_remove_missing_term_warns() {
+        let result = parse_formula("y ~ x - w + log(z) - log(x)").expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!(["y", "intercept", "x", "z_log"])
+        );
+
+        let warnings = result.get("metadata").unwrap().get("warnings").unwrap();
+        assert_eq!(
+            warnings,
+            &serde_json::json!([
+                "term 'w' is not in the model and cannot be removed",
+                "term 'log(x)' is not in the model and cannot be removed"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_remove_function_term_matches_all_arguments() {
+        let result = parse_formula("y ~ poly(x, 2) - poly(x, 3)").expect("Should parse successfully");
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["y", "intercept", "x_poly_1", "x_poly_2"])
+        );
+        assert_eq!(
+            result["metadata"]["warnings"],
+            serde_json::json!(["term 'poly(x, 3)' is not in the model and cannot be removed"])
+        );
+
+        let result = parse_formula("y ~ s(x, k = 5) - s(x)").expect("Should parse successfully");
+        assert_eq!(result["columns"]["x"]["transformations"].as_array().unwrap().len(), 1);
+        assert_eq!(result["metadata"]["warnings"].as_array().unwrap().len(), 1);
+
+        let result = parse_formula("y ~ lag(x, 1) + lag(x, 2) - lag(x, 2)")
+            .expect("Should parse successfully");
+        let transformations = result["columns"]["x"]["transformations"].as_array().unwrap();
+        assert_eq!(transformations.len(), 1);
+        assert_eq!(transformations[0]["parameters"]["arg_1"], 1);
+    }
+
+    #[test]
+    fn test_terms_apply_in_formula_order() {
+        // A removal only affects the terms written before it
+        let result = parse_formula("y ~ x - x + x").expect("Should parse successfully");
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["y", "intercept", "x"])
+        );
+        assert_eq!(result["metadata"]["warnings"], serde_json::json!([]));
+
+        let result = parse_formula("y ~ a*b - a:b + a:b").expect("Should parse successfully");
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["y", "intercept", "a", "b", "a_b"])
+        );
+
+        let result = parse_formula("y ~ - x + x").expect("Should parse successfully");
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["y", "intercept", "x"])
+        );
+        assert_eq!(
+            result["metadata"]["warnings"],
+            serde_json::json!(["term 'x' is not in the model and cannot be removed"])
+        );
+    }
+
+    #[test]
+    fn test_group_distributes_over_crossing() {
+        let result = parse_formula("y ~ (a + b) * d").expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!(["y", "intercept", "a", "b", "d", "a_d", "b_d"])
+        );
+        assert!(result.get("columns").unwrap().get("a_b").is_none());
+    }
+
+    #[test]
+    fn test_group_of_groups_and_random_effect() {
+        let result =
+            parse_formula("y ~ (a + b) * (d + e) + (1 | g)").expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!([
+                "y", "intercept", "a", "b", "d", "e", "a_d", "a_e", "b_d", "b_e", "g"
+            ])
+        );
+        let metadata = result.get("metadata").unwrap();
+        assert_eq!(metadata.get("is_random_effects_model").unwrap(), true);
+    }
+
+    #[test]
+    fn test_group_removal() {
+        let result = parse_formula("y ~ (x + z + w) - w").expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(all_columns, &serde_json::json!(["y", "intercept", "x", "z"]));
+    }
+
+    #[test]
+    fn test_group_intercept_markers() {
+        // The markers of a group on its own set the intercept, as in R
+        for (formula, has_intercept) in [
+            ("y ~ (0 + x)", false),
+            ("y ~ (x - 1)", false),
+            ("y ~ (x - 1) + 1", true),
+            ("y ~ (x + 1) - 1", false),
+        ] {
+            let result = parse_formula(formula).expect("Should parse successfully");
+            assert_eq!(result["metadata"]["has_intercept"], has_intercept, "{formula}");
+            assert_eq!(result["columns"]["x"]["generated_columns"], serde_json::json!(["x"]));
+        }
+
+        // A group combined with an operator cannot contain them
+        assert!(parse_formula("y ~ (0 + x):z").is_err());
+        assert!(parse_formula("y ~ (x - 1)*z").is_err());
+        assert!(parse_formula("y ~ (x + 1)^2").is_err());
+    }
+
+    #[test]
+    fn test_removal_inside_group() {
+        let result = parse_formula("y ~ (a + b - a):c").expect("Should parse successfully");
+        assert_eq!(result["all_generated_columns"], serde_json::json!(["y", "intercept", "b_c"]));
+        assert!(result["columns"]["a"].is_null());
+
+        let result = parse_formula("y ~ (a*b - a:b) + c").expect("Should parse successfully");
+        assert_eq!(
+            result["all_generated_columns"],
+            serde_json::json!(["y", "intercept", "a", "b", "c"])
+        );
+        assert_eq!(result["metadata"]["warnings"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_power_limits_interaction_order() {
+        let result = parse_formula("y ~ (a + b + d + e)^2").expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!([
+                "y", "intercept", "a", "b", "d", "e", "a_b", "a_d", "a_e", "b_d", "b_e", "d_e"
+            ])
+        );
+        let columns = result.get("columns").unwrap();
+        assert!(columns.get("a_b_d").is_none());
+        assert_eq!(
+            columns.get("a").unwrap().get("interactions").unwrap().as_array().unwrap().len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_power_order_above_term_count() {
+        // An order above the number of terms is the full expansion, as in R
+        let result = parse_formula("y ~ (a + b)^4294967295").expect("Should parse successfully");
+        let expected = parse_formula("y ~ a*b").unwrap();
+        assert_eq!(result["all_generated_columns"], expected["all_generated_columns"]);
+
+        // An order that does not fit is an error, not a panic
+        let error = parse_formula("y ~ (a + b)^99999999999").unwrap_err().to_string();
+        assert!(error.contains("interaction order '99999999999' after '^' is too large"));
+    }
+
+    #[test]
+    fn test_power_crossed_with_other_terms() {
+        let result = parse_formula("y ~ (a + b + d)^2 * x - a:b:x").expect("Should parse successfully");
+
+        let columns = result.get("columns").unwrap();
+        assert!(columns.get("a_b_d").is_none());
+        assert!(columns.get("a_b_x").is_none());
+        assert!(columns.get("a_d_x").is_some());
+        assert!(columns.get("a_x").is_some());
+    }
+
+    #[test]
+    fn test_colon_interaction_has_no_main_effects() {
+        let result = parse_formula("y ~ x:z").expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(all_columns, &serde_json::json!(["y", "intercept", "x_z"]));
+
+        let x = result.get("columns").unwrap().get("x").unwrap();
+        assert_eq!(x.get("roles").unwrap(), &serde_json::json!([]));
+        assert_eq!(x.get("generated_columns").unwrap(), &serde_json::json!([]));
+        assert_eq!(
+            x.get("interactions").unwrap().as_array().unwrap()[0]
+                .get("with")
+                .unwrap(),
+            &serde_json::json!(["z"])
+        );
+    }
+
+    #[test]
+    fn test_colon_and_star_precedence() {
+        // a:b*d is (a:b)*d → d + a:b + a:b:d
+        let result = parse_formula("y ~ a:b*d").expect("Should parse successfully");
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!(["y", "intercept", "d", "a_b", "a_b_d"])
+        );
+
+        // Main effect added back after a colon-only interaction
+        let result = parse_formula("y ~ x:z + x").expect("Should parse successfully");
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(all_columns, &serde_json::json!(["y", "intercept", "x", "x_z"]));
+    }
+
+    #[test]
+    fn test_remove_crossed_term() {
+        // `- a*b` removes a, b and a:b
+        let result = parse_formula("y ~ a*b + e - a*b").expect("Should parse successfully");
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(all_columns, &serde_json::json!(["y", "intercept", "e"]));
+        assert!(result.get("columns").unwrap().get("a").is_none());
+    }
+
+    #[test]
+    fn test_nesting_operator() {
+        let result = parse_formula("y ~ block/plot").expect("Should parse successfully");
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!(["y", "intercept", "block", "block_plot"])
+        );
+
+        let columns = result.get("columns").unwrap();
+        assert_eq!(
+            columns.get("block").unwrap().get("roles").unwrap(),
+            &serde_json::json!(["FixedEffect"])
+        );
+        assert_eq!(
+            columns.get("plot").unwrap().get("roles").unwrap(),
+            &serde_json::json!([])
+        );
+
+        // Nesting within a group uses all of its variables
+        let result = parse_formula("y ~ (a + b)/d").expect("Should parse successfully");
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!(["y", "intercept", "a", "b", "a_b_d"])
+        );
+    }
+
+    #[test]
+    fn test_nested_within_operator() {
+        let result = parse_formula("y ~ a + b %in% a").expect("Should parse successfully");
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(all_columns, &serde_json::json!(["y", "intercept", "a", "b_a"]));
+
+        // Nested grouping in random effects is unaffected
+        let result = parse_formula("y ~ a/b + (1 | a/b)").expect("Should parse successfully");
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!(["y", "intercept", "a", "a_b", "a/b"])
+        );
+    }
+
+    #[test]
+    fn test_as_is_expression_term() {
+        let result = parse_formula("y ~ x + I(x^2) + I(price / 2000)").expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!(["y", "intercept", "x", "I(x^2)", "I(price / 2000)"])
+        );
+
+        let transformation = &result.get("columns").unwrap().get("x").unwrap()
+            .get("transformations").unwrap()[0];
+        assert_eq!(transformation.get("function").unwrap(), "I");
+        assert_eq!(
+            transformation.get("parameters").unwrap(),
+            &serde_json::json!({
+                "expression": {
+                    "type": "binary",
+                    "operator": "^",
+                    "left": { "type": "column", "name": "x" },
+                    "right": { "type": "number", "value": 2.0 }
+                },
+                "variables": ["x"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_as_is_expression_with_calls_and_unary_minus() {
+        let result = parse_formula("y ~ I(-log(x) + z) + I((x + z) / 2)").expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!(["y", "intercept", "I(-log(x) + z)", "I((x + z) / 2)"])
+        );
+        let parameters = result.get("columns").unwrap().get("x").unwrap()
+            .get("transformations").unwrap()[0]
+            .get("parameters").unwrap();
+        assert_eq!(parameters.get("variables").unwrap(), &serde_json::json!(["x", "z"]));
+        assert_eq!(
+            parameters.get("expression").unwrap().get("left").unwrap().get("type").unwrap(),
+            "negate"
+        );
+    }
+
+    #[test]
+    fn test_as_is_expression_registers_every_variable() {
+        for formula in ["y ~ I(x * z)", "y ~ I(-x + 2 * z)"] {
+            let result = parse_formula(formula).expect("Should parse successfully");
+            let z = &result["columns"]["z"];
+            assert_eq!(z["roles"], serde_json::json!(["FixedEffect"]), "{formula}");
+            assert_eq!(z["generated_columns"], serde_json::json!([]), "{formula}");
+        }
+
+        let result = parse_formula("y ~ I(x * z) - I(x * z)").expect("Should parse successfully");
+        assert_eq!(result["columns"].as_object().unwrap().len(), 1);
+
+        // A constant expression has no variable to attach to
+        let result = parse_formula("y ~ x + I(2)").expect("Should parse successfully");
+        assert_eq!(result["all_generated_columns"], serde_json::json!(["y", "intercept", "x"]));
+        assert_eq!(
+            result["metadata"]["warnings"],
+            serde_json::json!(["term 'I(2)' does not use any variable and is ignored"])
+        );
+    }
+
+    #[test]
+    fn test_as_is_expression_interaction() {
+        let result = parse_formula("y ~ I(x^2):z").expect("Should parse successfully");
+        assert_eq!(result["all_generated_columns"], serde_json::json!(["y", "intercept", "I(x^2)_z"]));
+        assert_eq!(result["columns"]["x"]["interactions"][0]["with"], serde_json::json!(["z"]));
+        assert_eq!(result["columns"]["z"]["interactions"][0]["with"], serde_json::json!(["I(x^2)"]));
+
+        let result = parse_formula("y ~ I(x^2)*z").expect("Should parse successfully");
+        assert_eq!(
+            result["all_generated_columns"],
+            serde_json::json!(["y", "intercept", "I(x^2)", "z", "I(x^2)_z"])
+        );
+        assert_eq!(result["columns"]["x"]["transformations"][0]["function"], "I");
+
+        let result = parse_formula("y ~ I(x^2)*z - z:I(x^2)").expect("Should parse successfully");
+        assert_eq!(result["all_generated_columns"], serde_json::json!(["y", "intercept", "I(x^2)", "z"]));
+        assert_eq!(result["metadata"]["warnings"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_as_is_expression_invalid() {
+        assert!(parse_formula("y ~ I(x + )").is_err());
+        assert!(parse_formula("y ~ I(x^2").is_err());
+    }
+
+    #[test]
+    fn test_multi_digit_polynomial_degree() {
+        let result = parse_formula("y ~ poly(x, 10)").expect("Should parse successfully");
+
+        let x_columns = result.get("columns").unwrap().get("x").unwrap()
+            .get("generated_columns").unwrap().as_array().unwrap();
+        assert_eq!(x_columns.len(), 10);
+        assert_eq!(x_columns[9], "x_poly_10");
+    }
+
+    #[test]
+    fn test_polynomial_degree_values() {
+        // A whole number written as a decimal is a degree too
+        let result = parse_formula("y ~ poly(x, 2.0)").expect("Should parse successfully");
+        let poly = &result["columns"]["x"]["transformations"][0];
+        assert_eq!(poly["parameters"], serde_json::json!({"degree": 2, "orthogonal": true}));
+        assert_eq!(poly["generates_columns"], serde_json::json!(["x_poly_1", "x_poly_2"]));
+
+        assert!(parse_formula("y ~ poly(x, 2.5)").is_err());
+        assert!(parse_formula("y ~ poly(x, 0)").is_err());
+        assert!(parse_formula("y ~ poly(x, -1)").is_err());
+        assert!(parse_formula("y ~ poly(x, 1e9)").is_err());
+    }
+
+    #[test]
+    fn test_remove_transformation_restores_roles() {
+        let result = parse_formula("y ~ poly(x, 2) + x - poly(x, 2)").expect("Should parse successfully");
+        assert_eq!(result["columns"]["x"]["roles"], serde_json::json!(["Identity"]));
+        assert_eq!(result["columns"]["x"]["generated_columns"], serde_json::json!(["x"]));
+
+        // A main effect from `*` keeps its column and role next to a transformation
+        let result = parse_formula("y ~ x*z + poly(x, 2)").expect("Should parse successfully");
+        assert_eq!(
+            result["columns"]["x"]["generated_columns"],
+            serde_json::json!(["x", "x_poly_1", "x_poly_2"])
+        );
+        let result = parse_formula("y ~ x*z + poly(x, 2) - poly(x, 2)").expect("Should parse successfully");
+        assert_eq!(result["columns"]["x"]["roles"], serde_json::json!(["FixedEffect"]));
+        assert_eq!(
+            result["all_generated_columns"],
+            serde_json::json!(["y", "intercept", "x", "z", "x_z"])
+        );
+    }
+
+    #[test]
+    fn test_decimal_and_negative_arguments() {
+        let result = parse_formula("y ~ lag(x, -1) + trunc(z, lb = 0.5) + I(w * 1e-3)")
+            .expect("Should parse successfully");
+
+        let columns = result.get("columns").unwrap();
+        let lag = columns.get("x").unwrap().get("transformations").unwrap()[0]
+            .get("parameters").unwrap();
+        assert_eq!(lag.get("arg_1").unwrap(), &serde_json::json!(-1));
+        let trunc = columns.get("z").unwrap().get("transformations").unwrap()[0]
+            .get("parameters").unwrap();
+        assert_eq!(trunc.get("lb").unwrap(), &serde_json::json!(0.5));
+        assert!(result.get("all_generated_columns").unwrap().as_array().unwrap()
+            .contains(&serde_json::json!("I(w * 0.001)")));
+    }
+
+    #[test]
+    fn test_zero_and_one_remain_intercept_markers() {
+        for formula in ["y ~ 0 + x", "y ~ x - 1", "y ~ x + 0"] {
+            let result = parse_formula(formula).expect("Should parse successfully");
+            assert_eq!(
+                result.get("metadata").unwrap().get("has_intercept").unwrap(),
+                false,
+                "{}",
+                formula
+            );
+        }
+        let result = parse_formula("y ~ 1 + x + (1 | g)").expect("Should parse successfully");
+        assert_eq!(result.get("metadata").unwrap().get("has_intercept").unwrap(), true);
+    }
+
+    #[test]
+    fn test_quoted_dotted_and_unicode_column_names() {
+        let result = parse_formula("y ~ Sepal.Length + log(`revenue 2024`) + é_score:_id")
+            .expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!([
+                "y",
+                "intercept",
+                "Sepal.Length",
+                "revenue 2024_log",
+                "é_score__id"
+            ])
+        );
+        let columns = result.get("columns").unwrap();
+        assert!(columns.get("revenue 2024").is_some());
+        assert!(columns.get("é_score").is_some());
+        assert!(columns.get("_id").is_some());
+    }
+
+    #[test]
+    fn test_unterminated_backtick_name_is_lex_error() {
+        assert!(parse_formula("y ~ `revenue 2024 + x").is_err());
+    }
+
+    #[test]
+    fn test_keywords_as_column_names() {
+        let result = parse_formula("lag ~ id + by + c + family + lag(x, 1) + (1 | gr(g, id = \"a\"))")
+            .expect("Should parse successfully");
+
+        let columns = result.get("columns").unwrap();
+        assert_eq!(
+            columns.get("lag").unwrap().get("roles").unwrap(),
+            &serde_json::json!(["Response"])
+        );
+        for name in ["id", "by", "c", "family"] {
+            assert_eq!(
+                columns.get(name).unwrap().get("roles").unwrap(),
+                &serde_json::json!(["Identity"]),
+                "{}",
+                name
+            );
+        }
+        assert_eq!(
+            columns.get("x").unwrap().get("generated_columns").unwrap(),
+            &serde_json::json!(["x_lag"])
+        );
+    }
+
+    #[test]
+    fn test_nested_function_calls() {
+        let result = parse_formula("y ~ offset(log(exposure)) + poly(log(z), 2)")
+            .expect("Should parse successfully");
+
+        let all_columns = result.get("all_generated_columns").unwrap();
+        assert_eq!(
+            all_columns,
+            &serde_json::json!(["y", "intercept", "exposure_log_offset", "z_log_poly_1", "z_log_poly_2"])
+        );
+
+        let transformations = result.get("columns").unwrap().get("exposure").unwrap()
+            .get("transformations").unwrap();
+        assert_eq!(
+            transformations,
+            &serde_json::json!([
+                { "function": "log", "parameters": {}, "generates_columns": ["exposure_log"] },
+                {
+                    "function": "offset",
+                    "parameters": { "arg_0": "exposure_log" },
+                    "generates_columns": ["exposure_log_offset"],
+                    "input": "exposure_log"
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_remove_nested_function_call() {
+        let result = parse_formula("y ~ log(x) + offset(log(x)) - offset(log(x))")
+            .expect("Should parse successfully");
+
+        let x = result.get("columns").unwrap().get("x").unwrap();
+        assert_eq!(x.get("generated_columns").unwrap(), &serde_json::json!(["x_log"]));
+        assert_eq!(x.get("transformations").unwrap().as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_keyword_function_terms() {
+        // Keyword tokens followed by `(` parse as ordinary function terms
+        for formula in [
+            "y ~ cs(x)",
+            "y ~ gr(x)",
+            "y ~ mm(a, b)",
+            "y ~ mmc(a, b)",
+            "y ~ cs(x) + (cs(1)|g)",
+        ] {
+            let result = parse_formula(formula);
+            assert!(result.is_ok(), "{formula} failed: {:?}", result.err());
+        }
+        let result = parse_formula("y ~ cs(x) + (cs(1)|g)").unwrap();
+        assert!(result["columns"]["x"].is_object());
+        assert!(result["columns"]["g"].is_object());
+    }
+
+    #[test]
+    fn test_distributional_parameter_formulas() {
+        let result = parse_formula("y ~ x, sigma ~ z + (1 | g), family = gaussian")
+            .expect("Should parse successfully");
+
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["y", "intercept", "x"])
+        );
+        let sigma = result.get("dpars").unwrap().get("sigma").unwrap();
+        assert_eq!(
+            sigma.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["intercept", "z", "g"])
+        );
+        let sigma_columns = sigma.get("columns").unwrap();
+        assert_eq!(sigma_columns.get("z").unwrap().get("id").unwrap(), 1);
+        assert_eq!(
+            sigma_columns.get("g").unwrap().get("roles").unwrap(),
+            &serde_json::json!(["GroupingVariable"])
+        );
+        assert!(result.get("columns").unwrap().get("z").is_none());
+        assert_eq!(result.get("metadata").unwrap().get("is_random_effects_model").unwrap(), true);
+    }
+
+    #[test]
+    fn test_correlation_ids_link_parameter_blocks() {
+        let result = parse_formula("y ~ x * z + (1 + x |ID1| g), zi ~ x + (1 |ID1| g)")
+            .expect("Should parse successfully");
+
+        assert_eq!(
+            result.get("correlation_ids").unwrap(),
+            &serde_json::json!({ "ID1": ["mu", "zi"] })
+        );
+        let zi_group = result.get("dpars").unwrap().get("zi").unwrap()
+            .get("columns").unwrap().get("g").unwrap();
+        assert_eq!(
+            zi_group.get("random_effects").unwrap()[0].get("correlation_id").unwrap(),
+            "ID1"
+        );
+    }
+
+    #[test]
+    fn test_formula_without_parameter_formulas_omits_dpars() {
+        let result = parse_formula("y ~ x + (1 | g)").expect("Should parse successfully");
+        assert!(result.get("dpars").is_none());
+        assert!(result.get("correlation_ids").is_none());
+    }
+
+    #[test]
+    fn test_nonlinear_formula() {
+        let result = parse_formula("y ~ a1 - a2^x, a1 ~ 1, a2 ~ x + (x | g), nl = TRUE")
+            .expect("Should parse successfully");
+
+        let nonlinear = result.get("nonlinear").unwrap();
+        assert_eq!(nonlinear.get("parameters").unwrap(), &serde_json::json!(["a1", "a2"]));
+        assert_eq!(nonlinear.get("covariates").unwrap(), &serde_json::json!(["x"]));
+        assert_eq!(nonlinear.get("expression").unwrap().get("operator").unwrap(), "-");
+        assert_eq!(result.get("all_generated_columns").unwrap(), &serde_json::json!(["y", "x"]));
+        assert_eq!(result.get("metadata").unwrap().get("has_intercept").unwrap(), false);
+
+        let nlpars = result.get("nlpars").unwrap();
+        assert_eq!(
+            nlpars.get("a1").unwrap().get("all_generated_columns").unwrap(),
+            &serde_json::json!(["intercept"])
+        );
+        assert_eq!(
+            nlpars.get("a2").unwrap().get("all_generated_columns").unwrap(),
+            &serde_json::json!(["intercept", "x", "g"])
+        );
+        assert!(result.get("dpars").is_none());
+    }
+
+    #[test]
+    fn test_nonlinear_formula_keeps_distributional_parameters() {
+        let result = parse_formula("y ~ b1 * exp(b2 * x), b1 + b2 ~ 1, sigma ~ x, nl = TRUE")
+            .expect("Should parse successfully");
+        assert!(result.get("dpars").unwrap().get("sigma").is_some());
+        assert_eq!(result.get("nlpars").unwrap().as_object().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_nonlinear_formula_errors() {
+        // No sub-formula for any name in the expression
+        assert!(parse_formula("y ~ a * x, b ~ 1, nl = TRUE").is_err());
+        // A parameter formula the expression does not use
+        let error = parse_formula("y ~ a1 * exp(a2 * x), a1 + a2 ~ 1, a3 ~ 1, nl = TRUE")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("parameter formula for 'a3' is not used by the non-linear formula"));
+        assert!(parse_formula("bf(y ~ a * x, nl = TRUE) + lf(a ~ 1) + lf(c ~ z)").is_err());
+        // nl must be TRUE or FALSE
+        assert!(parse_formula("y ~ b1 * x, b1 ~ 1, nl = \"yes\"").is_err());
+        // nl = FALSE keeps the linear right-hand side
+        let result = parse_formula("y ~ x + z, nl = FALSE").expect("Should parse successfully");
+        assert!(result.get("nonlinear").is_none());
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["y", "intercept", "x", "z"])
+        );
+    }
+
+    #[test]
+    fn test_response_addition_terms() {
+        let result = parse_formula("y | trials(n) + cens(c, y2) + trunc(lb = 0, ub = 100) ~ x")
+            .expect("Should parse successfully");
+
+        assert_eq!(
+            result.get("response").unwrap(),
+            &serde_json::json!({
+                "name": "y",
+                "trials": "n",
+                "censoring": { "variable": "c", "upper_bound_var": "y2" },
+                "truncation": { "lower": 0, "upper": 100 }
+            })
+        );
+        let columns = result.get("columns").unwrap();
+        assert_eq!(
+            columns.get("n").unwrap().get("roles").unwrap(),
+            &serde_json::json!(["ResponseAddition"])
+        );
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["y", "intercept", "n", "c", "y2", "x"])
+        );
+    }
+
+    #[test]
+    fn test_response_addition_terms_constants_and_flags() {
+        let result = parse_formula("y | trials(10) + se(s, sigma = TRUE) + mi() ~ x")
+            .expect("Should parse successfully");
+        let response = result.get("response").unwrap();
+        assert_eq!(response.get("trials").unwrap(), 10);
+        assert_eq!(response.get("mi").unwrap(), true);
+        assert_eq!(
+            response.get("known_meas_error").unwrap(),
+            &serde_json::json!({ "variable": "s", "include_residual_sigma": true })
+        );
+
+        assert!(parse_formula("y ~ x").unwrap().get("response").is_none());
+        assert!(parse_formula("bind(y1, y2) | weights(w) ~ x").is_err());
+        assert!(parse_formula("y | weights(w) + weights(v) ~ x").is_err());
+
+        // Weights can be rescaled
+        let result = parse_formula("y | weights(w, scale = TRUE) ~ x").unwrap();
+        assert_eq!(
+            result["response"],
+            serde_json::json!({ "name": "y", "weights": "w", "scale_weights": true })
+        );
+        assert!(parse_formula("y | weights(w, scale = 2) ~ x").is_err());
+        // Numeric truncation bounds must be in order
+        let error = parse_formula("y | trunc(ub = 1, lb = 5) ~ x").unwrap_err().to_string();
+        assert!(error.contains("trunc() lower bound 5 must be below the upper bound 1"));
+        assert!(parse_formula("y | trunc(2, 2) ~ x").is_err());
+        assert!(parse_formula("y | trunc(lb = -5, ub = -1.5) ~ x").is_ok());
+        assert!(parse_formula("y | trunc(lb = 5, ub = u) ~ x").is_ok());
+    }
+
+    #[test]
+    fn test_one_sided_formula() {
+        let result = parse_formula("~ x + z + (1 | g)").expect("Should parse successfully");
+
+        assert_eq!(result.get("metadata").unwrap().get("response_variable_count").unwrap(), 0);
+        let columns = result.get("columns").unwrap();
+        assert_eq!(columns.get("x").unwrap().get("id").unwrap(), 1);
+        assert_eq!(columns.get("z").unwrap().get("id").unwrap(), 2);
+        assert_eq!(columns.get("g").unwrap().get("id").unwrap(), 3);
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["intercept", "x", "z", "g"])
+        );
+        assert_eq!(
+            result.get("all_generated_columns_formula_order").unwrap().get("1").unwrap(),
+            "intercept"
+        );
+    }
+
+    #[test]
+    fn test_one_sided_formula_without_intercept() {
+        let result = parse_formula("~ 0 + x").expect("Should parse successfully");
+        assert_eq!(result.get("all_generated_columns").unwrap(), &serde_json::json!(["x"]));
+        assert!(parse_formula("| weights(w) ~ x").is_err());
+    }
+
+    #[test]
+    fn test_transformed_response() {
+        let result = parse_formula("log(y) ~ x").expect("Should parse successfully");
+
+        let y = result.get("columns").unwrap().get("y").unwrap();
+        assert_eq!(y.get("id").unwrap(), 1);
+        assert_eq!(y.get("roles").unwrap(), &serde_json::json!(["Response"]));
+        assert_eq!(y.get("generated_columns").unwrap(), &serde_json::json!(["y_log"]));
+        assert_eq!(y.get("transformations").unwrap()[0].get("function").unwrap(), "log");
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["y_log", "intercept", "x"])
+        );
+        assert_eq!(result.get("metadata").unwrap().get("response_variable_count").unwrap(), 1);
+
+        // An arithmetic response generates a column named after its expression
+        let result = parse_formula("I(y / 2) ~ x").unwrap();
+        let y = &result["columns"]["y"];
+        assert_eq!(y["roles"], serde_json::json!(["Response"]));
+        assert_eq!(y["generated_columns"], serde_json::json!(["I(y / 2)"]));
+        assert_eq!(y["transformations"][0]["function"], "I");
+
+        // Only functions of each value keep the response a single column
+        assert!(parse_formula("sqrt(y) ~ x").is_ok());
+        assert!(parse_formula("poly(y, 2) ~ x").is_err());
+        assert!(parse_formula("s(y) ~ x").is_err());
+        assert!(parse_formula("log(bs(y)) ~ x").is_err());
+
+        // The response cannot reappear as a predictor
+        assert!(parse_formula("log(y) ~ log(y)").is_err());
+        assert!(parse_formula("y ~ x + y").is_err());
+        assert!(parse_formula("y ~ x:y").is_err());
+        assert!(parse_formula("cbind(succ, fail) ~ fail").is_err());
+        assert!(parse_formula("I(y / 2) ~ I(y^2)").is_err());
+        assert!(parse_formula("y ~ x + (0 + y | site)").is_ok());
+    }
+
+    #[test]
+    fn test_binomial_response() {
+        let result = parse_formula("cbind(succ, fail) ~ x").expect("Should parse successfully");
+
+        assert_eq!(
+            result.get("response").unwrap(),
+            &serde_json::json!({
+                "name": "succ",
+                "binomial": { "successes": "succ", "failures": "fail" }
+            })
+        );
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["succ", "fail", "intercept", "x"])
+        );
+        assert_eq!(result.get("metadata").unwrap().get("response_variable_count").unwrap(), 1);
+        assert!(parse_formula("cbind(succ) ~ x").is_err());
+    }
+
+    #[test]
+    fn test_multivariate_response_keeps_formula_order() {
+        let result = parse_formula("bind(y3, y1, y2) ~ x").expect("Should parse successfully");
+        assert_eq!(
+            result.get("all_generated_columns").unwrap(),
+            &serde_json::json!(["y3", "y1", "y2", "intercept", "x"])
+        );
+    }
+
+    #[test]
+    fn test_model_specification() {
+        let result = parse_formula(
+            "(y ~ 1) + nlf(sigma ~ a * exp(b * x), a ~ x) + lf(b ~ z + (1|g), dpar = \"sigma\") + gaussian()",
+        )
+        .expect("Should parse successfully");
+
+        assert_eq!(
+            result.get("formulas").unwrap(),
+            &serde_json::json!([
+                { "formula": "y ~ 1", "role": "Main", "parameter": "mu" },
+                {
+                    "formula": "sigma ~ a * exp(b * x)",
+                    "role": "NonLinearPredictor",
+                    "parameter": "sigma"
+                },
+                { "formula": "a ~ x", "role": "LinearPredictor", "parameter": "a", "dpar": "sigma" },
+                {
+                    "formula": "b ~ z + (1 | g)",
+                    "role": "LinearPredictor",
+                    "parameter": "b",
+                    "dpar": "sigma"
+                }
+            ])
+        );
+        assert_eq!(result["metadata"]["family"], "gaussian");
+        assert_eq!(result["metadata"]["is_random_effects_model"], true);
+
+        let sigma = &result["dpars"]["sigma"];
+        assert_eq!(sigma["nonlinear"]["parameters"], serde_json::json!(["a", "b"]));
+        assert_eq!(sigma["all_generated_columns"], serde_json::json!(["x"]));
+        let nlpars = result.get("nlpars").unwrap().as_object().unwrap();
+        assert_eq!(nlpars.len(), 2);
+        assert_eq!(
+            nlpars["b"]["all_generated_columns"],
+            serde_json::json!(["intercept", "z", "g"])
+        );
+    }
+
+    #[test]
+    fn test_model_specification_nonlinear_main_formula() {
+        // The parameters of an nl = TRUE formula may be given by lf()
+        let result = parse_formula("bf(y ~ a1 - a2^x, nl = TRUE) + lf(a1 + a2 ~ 1)")
+            .expect("Should parse successfully");
+
+        assert_eq!(
+            result["nonlinear"]["parameters"],
+            serde_json::json!(["a1", "a2"])
+        );
+        let nlpars = result.get("nlpars").unwrap().as_object().unwrap();
+        assert!(nlpars.contains_key("a1") && nlpars.contains_key("a2"));
+        let formulas: Vec<&str> = result["formulas"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["formula"].as_str().unwrap())
+            .collect();
+        assert_eq!(formulas, vec!["y ~ a1 - a2^x", "a1 ~ 1", "a2 ~ 1"]);
+    }
+
+    #[test]
+    fn test_model_specification_errors() {
+        // No main formula
+        assert!(parse_formula("lf(a ~ 1) + gaussian()").is_err());
+        // Two families
+        assert!(parse_formula("bf(y ~ x, family = poisson) + gaussian()").is_err());
+        // A parameter with two formulas
+        assert!(parse_formula("bf(y ~ x, sigma ~ z) + lf(sigma ~ w)").is_err());
+        // nlf() whose expression uses no parameter formula
+        assert!(parse_formula("bf(y ~ x) + nlf(sigma ~ a * x)").is_err());
+        // Unknown component
+        assert!(parse_formula("bf(y ~ x) + foo()").is_err());
+    }
+
+    #[test]
+    fn test_multivariate_model_specification() {
+        let result = parse_formula("bf(y1 ~ x) + bf(y2 ~ z + (1 | g)) + set_rescor(TRUE)")
+            .expect("Should parse successfully");
+
+        assert_eq!(result["metadata"]["rescor"], true);
+        assert_eq!(result["metadata"]["response_variable_count"], 2);
+        // The top level combines the responses and every right-hand side
+        assert_eq!(
+            result["all_generated_columns"],
+            serde_json::json!(["y1", "y2", "intercept", "x", "z", "g"])
+        );
+        assert_eq!(result["columns"]["y1"]["id"], 1);
+        assert_eq!(result["columns"]["y2"]["id"], 2);
+        assert_eq!(result["metadata"]["is_random_effects_model"], true);
+
+        // Each response has its own block
+        let responses = result.get("responses").unwrap().as_object().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(
+            responses["y1"]["all_generated_columns"],
+            serde_json::json!(["y1", "intercept", "x"])
+        );
+        assert_eq!(
+            responses["y2"]["all_generated_columns"],
+            serde_json::json!(["y2", "intercept", "z", "g"])
+        );
+        assert_eq!(responses["y2"]["columns"]["y2"]["id"], 1);
+
+        let formulas = result["formulas"].as_array().unwrap();
+        assert_eq!(formulas[0]["response"], "y1");
+        assert_eq!(formulas[1]["response"], "y2");
+    }
+
+    #[test]
+    fn test_multivariate_model_specification_families_and_parameters() {
+        // A family applies to the formula before it and lf() names its response
+        let result = parse_formula(
+            "bf(y1 ~ x) + gaussian() + bf(y2 ~ x) + poisson() + lf(sigma ~ z, resp = \"y1\")",
+        )
+        .expect("Should parse successfully");
+
+        assert_eq!(result["metadata"]["family"], serde_json::Value::Null);
+        assert!(result.get("rescor").is_none());
+        let responses = &result["responses"];
+        assert_eq!(responses["y1"]["metadata"]["family"], "gaussian");
+        assert_eq!(responses["y2"]["metadata"]["family"], "poisson");
+        assert_eq!(
+            responses["y1"]["dpars"]["sigma"]["all_generated_columns"],
+            serde_json::json!(["intercept", "z"])
+        );
+        assert!(responses["y2"].get("dpars").is_none());
+        assert_eq!(result["formulas"][2]["response"], "y1");
+
+        // A trailing family applies to every formula without one
+        let result = parse_formula("bf(y1 ~ x) + bf(y2 ~ z) + gaussian()").unwrap();
+        assert_eq!(result["responses"]["y1"]["metadata"]["family"], "gaussian");
+        assert_eq!(result["responses"]["y2"]["metadata"]["family"], "gaussian");
+        let result = parse_formula("bf(y1 ~ x, family = student) + bf(y2 ~ z) + bf(y3 ~ w) + poisson()")
+            .unwrap();
+        assert_eq!(result["responses"]["y1"]["metadata"]["family"], "student");
+        assert_eq!(result["responses"]["y2"]["metadata"]["family"], "poisson");
+        assert_eq!(result["responses"]["y3"]["metadata"]["family"], "poisson");
+    }
+
+    #[test]
+    fn test_mvbind_response_blocks() {
+        let result = parse_formula("mvbind(y1, y2) ~ x").expect("Should parse successfully");
+
+        assert_eq!(result["columns"]["y1"]["id"], 1);
+        assert_eq!(result["columns"]["y2"]["id"], 2);
+        assert_eq!(result["columns"]["x"]["id"], 3);
+        let responses = result.get("responses").unwrap().as_object().unwrap();
+        assert_eq!(
+            responses["y2"]["all_generated_columns"],
+            serde_json::json!(["y2", "intercept", "x"])
+        );
+    }
+
+    #[test]
+    fn test_multivariate_model_specification_errors() {
+        // Two formulas for one response
+        assert!(parse_formula("bf(y ~ x) + bf(y ~ z)").is_err());
+        assert!(parse_formula("bf(y ~ x) + bf(log(y) ~ z)").is_err());
+        let error = parse_formula("bf(cbind(s, f) ~ x) + bf(f ~ z)").unwrap_err().to_string();
+        assert!(error.contains("response 'f' has more than one formula"));
+        // A response bound twice
+        let error = parse_formula("mvbind(y1, y1) ~ x").unwrap_err().to_string();
+        assert!(error.contains("response 'y1' appears more than once in mvbind()"));
+        assert!(parse_formula("bind(y1, y2, y1) ~ x").is_err());
+        // lf() without resp = in a model with several responses
+        assert!(parse_formula("bf(y1 ~ x) + bf(y2 ~ z) + lf(sigma ~ w)").is_err());
+        // lf() naming an unknown response
+        assert!(parse_formula("bf(y1 ~ x) + bf(y2 ~ z) + lf(sigma ~ w, resp = \"y3\")").is_err());
+        // mvbind() formulas cannot be combined with other formulas
+        assert!(parse_formula("bf(mvbind(y1, y2) ~ x) + bf(y3 ~ z)").is_err());
+        // A second family for the same response
+        assert!(parse_formula("bf(y1 ~ x) + gaussian() + poisson() + bf(y2 ~ z)").is_err());
+    }
+
+    #[test]
+    fn test_family_with_links() {
+        let result = parse_formula("y ~ x, family = binomial(link = \"probit\")")
+            .expect("Should parse successfully");
+
+        assert_eq!(result["metadata"]["family"], "binomial");
+        assert_eq!(
+            result["family"],
+            serde_json::json!({ "name": "binomial", "dpars": ["mu"], "links": { "mu": "probit" } })
+        );
+
+        let result = parse_formula("bf(y ~ x, zi ~ z) + zero_inflated_negbinomial(link_zi = \"identity\")")
+            .expect("Should parse successfully");
+        assert_eq!(result["metadata"]["family"], "zero_inflated_negbinomial");
+        assert_eq!(result["family"]["dpars"], serde_json::json!(["mu", "shape", "zi"]));
+        assert_eq!(
+            result["family"]["links"],
+            serde_json::json!({ "mu": "log", "shape": "log", "zi": "identity" })
+        );
+
+        // Each parameter has one link
+        let error = parse_formula("y ~ x, family = gaussian(link = \"log\", link = \"identity\")")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("gaussian() is given more than one link for mu"));
+        assert!(parse_formula("y ~ x, family = gaussian(\"log\", link_mu = \"identity\")").is_err());
+        assert!(parse_formula("y ~ x, family = student(link_nu = \"log\", link_nu = \"log\")").is_err());
+    }
+
+    #[test]
+    fn test_family_names() {
+        for (formula, name) in [
+            ("y ~ x, family = student", "student"),
+            ("y ~ x, family = Gamma", "Gamma"),
+            ("y ~ x, family = lognormal()", "lognormal"),
+            ("y ~ x, family = bernoulli", "bernoulli"),
+            ("y ~ x, family = cumulative(\"probit\")", "cumulative"),
+            ("y ~ x, family = categorical", "categorical"),
+            ("bf(y ~ x) + hurdle_gamma()", "hurdle_gamma"),
+            ("rt | dec(d) ~ x, family = wiener", "wiener"),
+        ] {
+            let result = parse_formula(formula).expect("Should parse successfully");
+            assert_eq!(result["metadata"]["family"], name, "{}", formula);
+        }
+        // Without a family there is no family section
+        let result = parse_formula("y ~ x").expect("Should parse successfully");
+        assert!(result.get("family").is_none());
+    }
+
+    #[test]
+    fn test_family_validates_parameter_formulas() {
+        // sigma is a parameter of student, but not of poisson
+        assert!(parse_formula("y ~ x, sigma ~ z, family = student").is_ok());
+        assert!(parse_formula("y ~ x, sigma ~ z, family = poisson").is_err());
+        assert!(parse_formula("bf(y ~ x) + lf(hu ~ z) + hurdle_poisson()").is_ok());
+        assert!(parse_formula("bf(y ~ x) + lf(zi ~ z) + hurdle_poisson()").is_err());
+        // Non-linear parameters are not distributional parameters
+        assert!(parse_formula("y ~ a * exp(b * x), a + b ~ 1, family = poisson, nl = TRUE").is_ok());
+        // Unknown links, parameters and families
+        assert!(parse_formula("y ~ x, family = poisson(link = \"logit\")").is_err());
+        assert!(parse_formula("y ~ x, family = gaussian(link_zi = \"logit\")").is_err());
+        assert!(parse_formula("y ~ x, family = gamma_poisson").is_err());
+    }
+
+    #[test]
+    fn test_mixture_family() {
+        let result = parse_formula("y ~ 1, mu1 ~ x, mu2 ~ z, family = mixture(gaussian, gaussian)")
+            .unwrap();
+        assert_eq!(result["metadata"]["family"], "mixture");
+        assert_eq!(result["family"]["components"], serde_json::json!(["gaussian", "gaussian"]));
+        assert_eq!(
+            result["family"]["dpars"],
+            serde_json::json!(["mu1", "sigma1", "mu2", "sigma2", "theta1", "theta2"])
+        );
+        assert_eq!(result["family"]["links"]["sigma2"], "log");
+        assert_eq!(result["dpars"]["mu1"]["all_generated_columns"], serde_json::json!(["intercept", "x"]));
+        assert_eq!(result["dpars"]["mu2"]["all_generated_columns"], serde_json::json!(["intercept", "z"]));
+
+        let result = parse_formula("y ~ x, family = mixture(gaussian, nmix = 3)").unwrap();
+        assert_eq!(result["family"]["components"], serde_json::json!(["gaussian", "gaussian", "gaussian"]));
+
+        // Component indices beyond the number of components
+        let error = parse_formula("y ~ x, mu3 ~ z, family = mixture(gaussian, gaussian)")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("mixture has 2 components, so 'mu3' has no component"));
+        assert!(parse_formula("y ~ x, family = mixture(gaussian)").is_err());
+        assert!(parse_formula("y ~ x, family = mixture(gaussian, nmix = 3000000)").is_err());
+        assert!(parse_formula("y ~ x, family = mixture(gaussian, mixture(gaussian, gaussian))").is_err());
+    }
+
+    #[test]
+    fn test_fixed_parameters() {
+        let result =
+            parse_formula("y ~ x, sigma2 = \"sigma1\", family = mixture(gaussian, gaussian)").unwrap();
+        assert_eq!(result["fixed_parameters"], serde_json::json!({"sigma2": "sigma1"}));
+        assert!(result.get("dpars").is_none());
+
+        // Plain formulas have no fixed parameters
+        let result = parse_formula("y ~ x").unwrap();
+        assert!(result.get("fixed_parameters").is_none());
+
+        // Both parameters must belong to the family
+        assert!(parse_formula("y ~ x, sigma2 = \"sigma7\", family = mixture(gaussian, gaussian)").is_err());
+        assert!(parse_formula("y ~ x, sigma = \"nu\", family = gaussian").is_err());
+        assert!(parse_formula("y ~ x, nu = \"sigma\", family = gaussian").is_err());
+        // A parameter is either fixed or predicted
+        let error = parse_formula(
+            "y ~ x, sigma2 = \"sigma1\", sigma2 ~ z, family = mixture(gaussian, gaussian)",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("parameter 'sigma2' already has a formula or a fixed value"));
+        // A parameter cannot be fixed to itself, directly or in a cycle
+        let error = parse_formula("y ~ x, sigma2 = \"sigma2\", family = mixture(gaussian, gaussian)")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("parameter 'sigma2' cannot be fixed to itself"));
+        assert!(parse_formula(
+            "y ~ x, sigma1 = \"sigma2\", sigma2 = \"sigma1\", family = mixture(gaussian, gaussian)"
+        )
+        .is_err());
+        assert!(parse_formula(
+            "y ~ x, theta1 = \"theta3\", theta2 = \"theta1\", theta3 = \"theta2\", family = mixture(gaussian, nmix = 3)"
+        )
+        .is_err());
+        let result = parse_formula(
+            "y ~ x, sigma1 = \"sigma2\", sigma2 = \"sigma3\", family = mixture(gaussian, nmix = 3)",
+        )
+        .unwrap();
+        assert_eq!(result["fixed_parameters"], serde_json::json!({"sigma1": "sigma2", "sigma2": "sigma3"}));
+    }
+
+    #[test]
+    fn test_fixed_parameter_constants() {
+        let result = parse_formula("rt | dec(decision) ~ x, bias = 0.5, family = wiener").unwrap();
+        assert_eq!(result["fixed_parameters"], serde_json::json!({"bias": 0.5}));
+        assert_eq!(result["family"]["dpars"], serde_json::json!(["mu", "bs", "ndt", "bias"]));
+        assert!(result.get("dpars").is_none());
+
+        // Whole numbers are written as integers, in model specifications too
+        let result = parse_formula("bf(y ~ x, sigma = 1, nu = -2) + student()").unwrap();
+        assert_eq!(result["fixed_parameters"], serde_json::json!({"sigma": 1, "nu": -2}));
+
+        // Constants are checked against the family like any other parameter
+        assert!(parse_formula("y ~ x, sigma = TRUE, family = gaussian").is_err());
+        assert!(parse_formula("y ~ x, zi = 0, family = gaussian").is_err());
+        // Formulas without a family are not checked against one
+        let result = parse_formula("y ~ x, sigma = 2").unwrap();
+        assert_eq!(result["fixed_parameters"], serde_json::json!({"sigma": 2}));
+        let result = parse_formula("rt | dec(decision) ~ x, bias = 0.5").unwrap();
+        assert_eq!(result["fixed_parameters"], serde_json::json!({"bias": 0.5}));
+        assert!(result["metadata"].get("options").is_none());
+        assert_eq!(result["metadata"]["warnings"], serde_json::json!([]));
+        let error = parse_formula("y ~ x, nu = 1, nu ~ z, family = student")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("parameter 'nu' already has a formula or a fixed value"));
+        // The main formula predicts mu
+        assert!(parse_formula("y ~ x, mu = 1").is_err());
+        assert!(parse_formula("bf(y ~ x, mu = 0) + gaussian()").is_err());
+        // Constants must be finite, since JSON has no infinity
+        assert!(parse_formula("y ~ x, sigma = 1e400").is_err());
+        assert!(parse_formula("y ~ x, sigma = -1e400, family = gaussian").is_err());
+        // A parameter cannot be fixed to itself, directly or in a cycle
+        assert!(parse_formula("y ~ x, sigma = \"sigma\"").is_err());
+        assert!(parse_formula("y ~ x, bs = \"ndt\", ndt = \"bs\", family = wiener").is_err());
+    }
+
+    #[test]
+    fn test_autocorrelation_terms() {
+        let result = parse_formula("y ~ x + ar(time, g, p = 2)").unwrap();
+        let autocorrelation = &result["autocorrelation"][0];
+        assert_eq!(autocorrelation["structure"], "arma");
+        assert_eq!(
+            autocorrelation["term"],
+            "arma(time = time, gr = g, p = 2, q = 0, cov = FALSE)"
+        );
+        assert_eq!(
+            autocorrelation["parameters"],
+            serde_json::json!({"time": "time", "gr": "g", "p": 2, "q": 0, "cov": false})
+        );
+        // The time and grouping variables are not transformed predictors
+        assert_eq!(result["columns"]["time"]["roles"], serde_json::json!(["Autocorrelation"]));
+        assert_eq!(result["columns"]["g"]["transformations"], serde_json::json!([]));
+
+        for (formula, term) in [
+            ("y ~ x + ma(gr = g)", "arma(time = NA, gr = g, p = 0, q = 1, cov = FALSE)"),
+            ("y ~ x + cosy(t)", "cosy(time = t, gr = NA)"),
+            ("y ~ x + car(M, type = \"icar\")", "car(M, gr = NA, type = \"icar\")"),
+            ("y ~ x + sar(W)", "sar(W, type = \"lag\")"),
+        ] {
+            let result = parse_formula(formula).expect("Should parse successfully");
+            assert_eq!(result["autocorrelation"][0]["term"], term, "{}", formula);
+        }
+
+        // Formulas without a structure have no autocorrelation section
+        let result = parse_formula("y ~ x").unwrap();
+        assert!(result.get("autocorrelation").is_none());
+
+        assert!(parse_formula("y ~ x + arma(p = 0, q = 0)").is_err());
+        assert!(parse_formula("y ~ x + ar(time) + ma(time)").is_err());
+        assert!(parse_formula("y ~ x, sigma ~ ar(time)").is_err());
+    }
+
+    #[test]
+    fn test_autocorrelation_with_operators() {
+        // Autocorrelation structures are not columns and cannot be combined
+        for formula in [
+            "y ~ ar(x):z",
+            "y ~ z:cosy(t)",
+            "y ~ ar(x)*z",
+            "y ~ z/ma(t)",
+            "y ~ z %in% arma(t)",
+            "y ~ ar(x)^2",
+            "y ~ (ar(x) + z)",
+            "y ~ (z + car(M)):w",
+            "y ~ sar(W) * z",
+        ] {
+            assert!(parse_formula(formula).is_err(), "{formula}");
+        }
+    }
+
+    #[test]
+    fn test_autocorrelation_component() {
+        let result = parse_formula(
+            "(y1 ~ x + (1|g)) + gaussian() + cor_ar(~1|g) + bf(y2 ~ z) + poisson()",
+        )
+        .unwrap();
+        let formulas: Vec<&str> = result["formulas"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["formula"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            formulas,
+            vec![
+                "y1 ~ x + (1 | g)",
+                "autocor ~ arma(time = NA, gr = g, p = 1, q = 0, cov = FALSE)",
+                "y2 ~ z"
+            ]
+        );
+        assert_eq!(result["formulas"][1]["role"], "Autocorrelation");
+        assert_eq!(result["formulas"][1]["response"], "y1");
+        // The structure belongs to y1 only
+        assert_eq!(result["responses"]["y1"]["autocorrelation"][0]["structure"], "arma");
+        assert!(result["responses"]["y2"].get("autocorrelation").is_none());
+        assert!(result.get("autocorrelation").is_none());
+        assert_eq!(
+            result["responses"]["y1"]["columns"]["g"]["roles"],
+            serde_json::json!(["GroupingVariable", "Autocorrelation"])
+        );
+
+        // A trailing structure applies to every formula since the previous one
+        let result = parse_formula("bf(y1 ~ x) + bf(y2 ~ z) + cor_ar(~ t)").unwrap();
+        for response in ["y1", "y2"] {
+            assert_eq!(result["responses"][response]["autocorrelation"][0]["structure"], "arma");
+        }
+        let result = parse_formula("bf(y1 ~ x) + cor_ar(~ t) + bf(y2 ~ z) + bf(y3 ~ w) + cor_cosy(~ t)")
+            .unwrap();
+        assert_eq!(result["responses"]["y1"]["autocorrelation"].as_array().unwrap().len(), 1);
+        assert_eq!(result["responses"]["y2"]["autocorrelation"][0]["structure"], "cosy");
+        assert_eq!(result["responses"]["y3"]["autocorrelation"][0]["structure"], "cosy");
+
+        let result = parse_formula("bf(y ~ x) + cor_arma(~ t | g, p = 1, q = 1)").unwrap();
+        assert_eq!(
+            result["autocorrelation"][0]["term"],
+            "arma(time = t, gr = g, p = 1, q = 1, cov = FALSE)"
+        );
+    }
+
+    #[test]
+    fn test_formula_options() {
+        let result = parse_formula("y ~ x + (1 | g), fill = \"mean\"").unwrap();
+        assert_eq!(result["metadata"]["options"], serde_json::json!({"fill": "mean"}));
+        assert_eq!(result["metadata"]["warnings"], serde_json::json!([]));
+
+        let result = parse_formula("y ~ x, center = FALSE, sparse = TRUE, decomp = \"QR\"").unwrap();
+        assert_eq!(
+            result["metadata"]["options"],
+            serde_json::json!({"center": false, "sparse": true, "decomp": "QR"})
+        );
+        let result = parse_formula("y ~ a * x, a ~ 1, nl = TRUE").unwrap();
+        assert_eq!(result["metadata"]["options"], serde_json::json!({"nl": true}));
+
+        // Plain formulas have no options
+        let result = parse_formula("y ~ x").unwrap();
+        assert!(result["metadata"].get("options").is_none());
+
+        // Known options have their values checked
+        let error = parse_formula("y ~ x, center = 3").unwrap_err().to_string();
+        assert!(error.contains("formula option 'center' must be TRUE or FALSE"));
+        assert!(parse_formula("y ~ x, decomp = \"LU\"").is_err());
+        assert!(parse_formula("y ~ x, fill = TRUE").is_err());
+        let error = parse_formula("y ~ x, fill = \"average\"").unwrap_err().to_string();
+        assert!(error.contains("formula option 'fill' must be one of \"mean\""));
+        let result = parse_formula("y ~ x, fill = locf").unwrap();
+        assert_eq!(result["metadata"]["options"], serde_json::json!({"fill": "locf"}));
+        // The family is given once, like any option
+        let error = parse_formula("y ~ x, family = gaussian, family = poisson")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("family is given more than once"));
+        assert!(parse_formula("y ~ x, fill = mean, fill = zero").is_err());
+        assert!(parse_formula("y ~ x, foo = (1)").is_err());
+
+        // Unknown options are kept with a warning
+        let result = parse_formula("y ~ x, foo = bar").unwrap();
+        assert_eq!(result["metadata"]["options"], serde_json::json!({"foo": "bar"}));
+        assert_eq!(
+            result["metadata"]["warnings"],
+            serde_json::json!(["unknown formula option 'foo'"])
+        );
+        let result = parse_formula("y ~ x, foo = 1, family = poisson").unwrap();
+        assert_eq!(result["metadata"]["options"], serde_json::json!({"foo": 1}));
+        assert!(result.get("fixed_parameters").is_none());
+    }
+
+    #[test]
+    fn test_smooth_terms() {
+        let result = parse_formula("y ~ s(x, k = 4, bs = \"cr\")").unwrap();
+        assert_eq!(
+            result["all_generated_columns"],
+            serde_json::json!(["y", "intercept", "x_s_1", "x_s_2", "x_s_3", "x_s_4"])
+        );
+        let smooth = &result["columns"]["x"]["transformations"][0];
+        assert_eq!(smooth["function"], "s");
+        assert_eq!(
+            smooth["parameters"],
+            serde_json::json!({"variables": ["x"], "bs": "cr", "k": 4})
+        );
+
+        // mgcv defaults: a thin plate spline of dimension 10 per variable, tripling
+        let result = parse_formula("y ~ s(x)").unwrap();
+        assert_eq!(result["columns"]["x"]["generated_columns"].as_array().unwrap().len(), 10);
+        let result = parse_formula("y ~ s(x, z)").unwrap();
+        assert_eq!(result["columns"]["x"]["generated_columns"][29], "x_z_s_30");
+
+        // Tensor products multiply the dimensions of their margins
+        let result = parse_formula("y ~ te(x, z, k = c(2, 3), bs = \"ps\")").unwrap();
+        let smooth = &result["columns"]["x"]["transformations"][0];
+        assert_eq!(
+            smooth["parameters"],
+            serde_json::json!({"variables": ["x", "z"], "bs": ["ps", "ps"], "k": [2, 3]})
+        );
+        assert_eq!(smooth["generates_columns"].as_array().unwrap().len(), 6);
+        let result = parse_formula("y ~ ti(x, z) + t2(x, z, k = 3)").unwrap();
+        assert_eq!(result["columns"]["x"]["transformations"][0]["generates_columns"][24], "x_z_ti_25");
+        assert_eq!(result["columns"]["x"]["transformations"][1]["generates_columns"][8], "x_z_t2_9");
+
+        // A smooth by a factor is named after it
+        let result = parse_formula("y ~ s(x, by = g, k = 2) + g").unwrap();
+        assert_eq!(
+            result["all_generated_columns"],
+            serde_json::json!(["y", "intercept", "x_by_g_s_1", "x_by_g_s_2", "g"])
+        );
+        assert_eq!(result["columns"]["x"]["transformations"][0]["parameters"]["by"], "g");
+
+        // Every variable a smooth reads is registered, without a column of its own
+        let result = parse_formula("y ~ s(x, z, by = g)").unwrap();
+        for name in ["z", "g"] {
+            let column = &result["columns"][name];
+            assert_eq!(column["roles"], serde_json::json!(["FixedEffect"]));
+            assert_eq!(column["generated_columns"], serde_json::json!([]));
+        }
+        let result = parse_formula("y ~ s(x, z) + s(w, z) - s(x, z)").unwrap();
+        assert!(result["columns"]["x"].is_null());
+        assert!(result["columns"]["z"].is_object());
+        let result = parse_formula("y ~ te(x, z) - te(x, z)").unwrap();
+        assert!(result["columns"]["z"].is_null());
+
+        // Without a call, the smooth names are ordinary columns
+        let result = parse_formula("y ~ s + te").unwrap();
+        assert_eq!(result["all_generated_columns"], serde_json::json!(["y", "intercept", "s", "te"]));
+
+        // Each smooth of a variable has its own columns; repeating one is an error
+        let result = parse_formula("y ~ s(x, k = 5) + s(x, k = 6)").unwrap();
+        let columns = result["columns"]["x"]["generated_columns"].as_array().unwrap();
+        assert_eq!(columns.len(), 11);
+        assert_eq!(columns[4], "x_s_5");
+        assert_eq!(columns[10], "x_s2_6");
+        let result = parse_formula("y ~ s(x, k = 3) + s(x, bs = \"cr\", k = 3) + s(x, k = 4)").unwrap();
+        assert_eq!(result["columns"]["x"]["transformations"][2]["generates_columns"][0], "x_s3_1");
+        assert!(parse_formula("y ~ s(x) + s(x)").is_err());
+        assert!(parse_formula("y ~ te(x, z, k = 3) + te(x, z, k = 3)").is_err());
+
+        assert!(parse_formula("y ~ s(k = 10)").is_err());
+        assert!(parse_formula("y ~ s(x, k = 0)").is_err());
+        assert!(parse_formula("y ~ s(x, k = 1)").is_err());
+        assert!(parse_formula("y ~ s(x, foo = 1)").is_err());
+        assert!(parse_formula("y ~ te(x, z, kk = 3)").is_err());
+        // A smooth has several columns, so it cannot be part of an interaction
+        for formula in ["y ~ s(x):z", "y ~ z * te(x, w)", "y ~ s(x)/g", "y ~ s(x)^2", "y ~ (s(x) + w):z"] {
+            assert!(parse_formula(formula).is_err(), "{}", formula);
+        }
+        assert!(parse_formula("y ~ s(x, bs = \"spline\")").is_err());
+        assert!(parse_formula("y ~ s(x, by = \"g\")").is_err());
+        assert!(parse_formula("y ~ te(x, z, k = c(5, 5, 5))").is_err());
+        // The number of basis functions is bounded
+        assert!(parse_formula("y ~ s(x, k = 100000)").is_err());
+        assert!(parse_formula("y ~ te(x, z, w, k = 2000)").is_err());
+        assert!(parse_formula("y ~ s(a, b, c, d, e, f, g, h, i, j, l, m, n, o, p, q, r, t, u, v, w)").is_err());
+    }
+
+    #[test]
+    fn test_spline_terms() {
+        let result = parse_formula("y ~ bs(x, df = 5)").unwrap();
+        assert_eq!(
+            result["all_generated_columns"],
+            serde_json::json!(["y", "intercept", "x_bs_1", "x_bs_2", "x_bs_3", "x_bs_4", "x_bs_5"])
+        );
+        assert_eq!(
+            result["columns"]["x"]["transformations"][0]["parameters"],
+            serde_json::json!({"df": 5, "degree": 3, "intercept": false})
+        );
+
+        // Knots + degree + intercept columns for bs(), knots + 1 + intercept for ns()
+        let result = parse_formula(
+            "y ~ bs(x, knots = c(0.2, 0.5), degree = 2, intercept = TRUE, Boundary.knots = c(0, 1))",
+        )
+        .unwrap();
+        let spline = &result["columns"]["x"]["transformations"][0];
+        assert_eq!(
+            spline["parameters"],
+            serde_json::json!({
+                "df": 5,
+                "degree": 2,
+                "intercept": true,
+                "knots": [0.2, 0.5],
+                "Boundary.knots": [0, 1]
+            })
+        );
+        assert_eq!(spline["generates_columns"][4], "x_bs_5");
+        let result = parse_formula("y ~ ns(x, knots = c(1, 2, 3))").unwrap();
+        assert_eq!(
+            result["columns"]["x"]["generated_columns"],
+            serde_json::json!(["x_ns_1", "x_ns_2", "x_ns_3", "x_ns_4"])
+        );
+
+        // Positional arguments follow R's order: bs(x, df, knots, degree, ...)
+        let result = parse_formula("y ~ z + ns(z, 3)").unwrap();
+        assert_eq!(
+            result["all_generated_columns"],
+            serde_json::json!(["y", "intercept", "z", "z_ns_1", "z_ns_2", "z_ns_3"])
+        );
+        // Without df or knots the basis has no interior knots
+        let result = parse_formula("y ~ bs(x)").unwrap();
+        assert_eq!(result["columns"]["x"]["generated_columns"].as_array().unwrap().len(), 3);
+
+        assert!(parse_formula("y ~ bs(x, df = 2)").is_err());
+        assert!(parse_formula("y ~ ns(x, degree = 2)").is_err());
+        assert!(parse_formula("y ~ bs(x, knots = \"a\")").is_err());
+        assert!(parse_formula("y ~ bs(x, Boundary.knots = c(0, 1, 2))").is_err());
+        assert!(parse_formula("y ~ bs(df = 3)").is_err());
+
+        // Each spline of a variable has its own columns; repeating one is an error
+        let result = parse_formula("y ~ bs(x, df = 5) + bs(x, df = 3) + ns(x, df = 3)").unwrap();
+        let columns = result["columns"]["x"]["generated_columns"].as_array().unwrap();
+        assert_eq!(columns.len(), 11);
+        assert_eq!(columns[5], "x_bs2_1");
+        assert_eq!(columns[8], "x_ns_1");
+        assert!(parse_formula("y ~ bs(x, df = 4) + bs(x, df = 4)").is_err());
+        // A spline has several columns, so it cannot be part of an interaction
+        for formula in ["y ~ bs(x, df = 3):z", "y ~ z * ns(x)", "y ~ bs(x)^2"] {
+            assert!(parse_formula(formula).is_err(), "{}", formula);
+        }
+    }
 }